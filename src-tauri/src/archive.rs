@@ -0,0 +1,1214 @@
+use serde_json::{json, Value};
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::io::{BufReader, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::Emitter;
+
+use crate::fileops::ConflictPolicy;
+use crate::jobs::Job;
+
+/// Copy buffer — also the granularity of progress events, cancellation and bomb checks
+const CHUNK: usize = 1024 * 1024;
+const CANCELLED: &str = "Archivvorgang abgebrochen";
+
+/// Defaults of the zip-bomb guards (overridable per call)
+const DEFAULT_MAX_ENTRIES: u64 = 1_000_000;
+const DEFAULT_MAX_RATIO: u64 = 1_000;
+/// Ratios are only checked above this size — small files compress absurdly well
+const RATIO_FLOOR: u64 = 64 * 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Zip,
+    Tar,
+    TarGz,
+    TarXz,
+    TarZst,
+}
+
+impl Format {
+    const ALL: [Format; 5] = [Self::Zip, Self::Tar, Self::TarGz, Self::TarXz, Self::TarZst];
+
+    /// Format by file name (".tar.gz", ".tgz", …)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let lower = name.to_lowercase();
+        let ends = |suffixes: &[&str]| suffixes.iter().any(|s| lower.ends_with(s));
+        if ends(&[".zip"]) {
+            Some(Self::Zip)
+        } else if ends(&[".tar.gz", ".tgz"]) {
+            Some(Self::TarGz)
+        } else if ends(&[".tar.xz", ".txz"]) {
+            Some(Self::TarXz)
+        } else if ends(&[".tar.zst", ".tzst"]) {
+            Some(Self::TarZst)
+        } else if ends(&[".tar"]) {
+            Some(Self::Tar)
+        } else {
+            None
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.trim_start_matches('.').to_lowercase().as_str() {
+            "zip" => Ok(Self::Zip),
+            "tar" => Ok(Self::Tar),
+            "tar.gz" | "tgz" | "gz" => Ok(Self::TarGz),
+            "tar.xz" | "txz" | "xz" => Ok(Self::TarXz),
+            "tar.zst" | "tzst" | "zst" => Ok(Self::TarZst),
+            _ => Err(format!(
+                "Unbekanntes Archivformat: {} (erlaubt: {})",
+                s,
+                Self::ALL.iter().map(|f| f.as_str()).collect::<Vec<_>>().join(", ")
+            )),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Zip => "zip",
+            Self::Tar => "tar",
+            Self::TarGz => "tar.gz",
+            Self::TarXz => "tar.xz",
+            Self::TarZst => "tar.zst",
+        }
+    }
+
+    /// Name without the archive suffix ("data.tar.gz" → "data")
+    pub fn strip_suffix(self, name: &str) -> String {
+        let suffixes: &[&str] = match self {
+            Self::Zip => &[".zip"],
+            Self::Tar => &[".tar"],
+            Self::TarGz => &[".tar.gz", ".tgz"],
+            Self::TarXz => &[".tar.xz", ".txz"],
+            Self::TarZst => &[".tar.zst", ".tzst"],
+        };
+        let lower = name.to_lowercase();
+        suffixes.iter()
+            .find(|s| lower.ends_with(*s))
+            .map(|s| name[..name.len() - s.len()].to_string())
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    /// (min, max, default) compression level
+    fn levels(self) -> (i32, i32, i32) {
+        match self {
+            Self::Zip | Self::TarGz | Self::TarXz => (0, 9, 6),
+            Self::Tar => (0, 0, 0),
+            Self::TarZst => (1, 22, 3),
+        }
+    }
+
+    /// Validated level; None picks the format's default
+    pub fn level(self, level: Option<i32>) -> Result<i32, String> {
+        let (min, max, default) = self.levels();
+        match level {
+            None => Ok(default),
+            Some(l) if (min..=max).contains(&l) => Ok(l),
+            Some(l) => Err(format!("Kompressionsstufe {} für {} ungültig (erlaubt: {}–{})", l, self.as_str(), min, max)),
+        }
+    }
+}
+
+// === Jobs ===
+
+const FAMILY: &str = "archive";
+
+pub fn new_job_id() -> String {
+    crate::jobs::new_id(FAMILY)
+}
+
+pub fn cancel(job_id: &str) -> bool {
+    crate::jobs::cancel(FAMILY, job_id)
+}
+
+fn start_job<'a>(app: Option<&'a tauri::AppHandle>, job_id: &'a str, operation: &'static str) -> Result<Job<'a>, String> {
+    Ok(Job::start(FAMILY, app, job_id, "archive-progress")?.with_operation(operation))
+}
+
+fn finish(job: &Job, report: &Value) {
+    let event = if report["status"] == "completed" { "archive-complete" } else { "archive-error" };
+    job.emit(event, report);
+}
+
+/// Report of a job that could not be registered (ID already in use)
+fn start_failed(app: Option<&tauri::AppHandle>, job_id: &str, operation: &str, error: String) -> Value {
+    let report = json!({
+        "jobId": job_id,
+        "operation": operation,
+        "status": "failed",
+        "error": error,
+        "elapsedMs": 0
+    });
+    if let Some(app) = app {
+        let _ = app.emit("archive-error", &report);
+    }
+    report
+}
+
+/// Counts the bytes pulled through a reader (compressed input of tar streams)
+struct Counted<R> {
+    inner: R,
+    count: Rc<Cell<u64>>,
+}
+
+impl<R: Read> Read for Counted<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count.set(self.count.get() + n as u64);
+        Ok(n)
+    }
+}
+
+/// Decompressed tar stream of an archive file
+fn tar_stream<R: Read + 'static>(format: Format, input: R) -> Result<Box<dyn Read>, String> {
+    Ok(match format {
+        Format::Tar => Box::new(input),
+        Format::TarGz => Box::new(flate2::read::MultiGzDecoder::new(input)),
+        Format::TarXz => Box::new(xz2::read::XzDecoder::new_multi_decoder(input)),
+        Format::TarZst => Box::new(zstd::Decoder::new(input).map_err(|e| format!("zstd-Decoder: {}", e))?),
+        Format::Zip => return Err("Zip ist kein Tar-Strom".to_string()),
+    })
+}
+
+fn zip_time_ms(dt: zip::DateTime) -> Option<i64> {
+    // DOS timestamps carry no timezone — treated as UTC
+    Some(chrono::NaiveDate::from_ymd_opt(dt.year() as i32, dt.month() as u32, dt.day() as u32)?
+        .and_hms_opt(dt.hour() as u32, dt.minute() as u32, dt.second() as u32)?
+        .and_utc()
+        .timestamp_millis())
+}
+
+fn zip_time(ms: i64) -> Option<zip::DateTime> {
+    let dt = chrono::DateTime::from_timestamp_millis(ms)?.naive_utc();
+    use chrono::{Datelike, Timelike};
+    zip::DateTime::from_date_and_time(dt.year() as u16, dt.month() as u8, dt.day() as u8, dt.hour() as u8, dt.minute() as u8, dt.second() as u8).ok()
+}
+
+const S_IFMT: u32 = 0o170000;
+const S_IFLNK: u32 = 0o120000;
+
+// === Listing ===
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryKind {
+    File,
+    Dir,
+    Symlink,
+    Hardlink,
+    Other,
+}
+
+impl EntryKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::File => "file",
+            Self::Dir => "dir",
+            Self::Symlink => "symlink",
+            Self::Hardlink => "hardlink",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// One member as stored in the archive (headers only, nothing decompressed)
+#[derive(Clone, Debug)]
+pub struct EntryInfo {
+    /// Member name as stored, `/`-separated
+    pub name: String,
+    pub kind: EntryKind,
+    pub size: u64,
+    /// Only zip stores it per entry
+    pub compressed_size: Option<u64>,
+    pub modified_ms: i64,
+    pub encrypted: bool,
+    pub mode: Option<u32>,
+    pub link_target: Option<String>,
+}
+
+fn zip_kind(f: &zip::read::ZipFile<'_>) -> EntryKind {
+    if f.is_dir() {
+        EntryKind::Dir
+    } else if f.unix_mode().is_some_and(|m| m & S_IFMT == S_IFLNK) {
+        EntryKind::Symlink
+    } else {
+        EntryKind::File
+    }
+}
+
+fn tar_kind(t: tar::EntryType) -> EntryKind {
+    if t.is_file() {
+        EntryKind::File
+    } else if t.is_dir() {
+        EntryKind::Dir
+    } else if t.is_symlink() {
+        EntryKind::Symlink
+    } else if t.is_hard_link() {
+        EntryKind::Hardlink
+    } else {
+        EntryKind::Other
+    }
+}
+
+/// Member name of a tar header. `\` is a separator only on Windows — on Unix it is a
+/// legal file-name byte and must stay part of the name.
+fn tar_name(path: &Path) -> String {
+    let name = crate::ospath::encode(path.as_os_str());
+    if cfg!(windows) { name.replace('\\', "/") } else { name }
+}
+
+/// Read all member headers. Stops after `max_entries` (third value: truncated).
+pub fn list(path: &Path, max_entries: usize) -> Result<(Format, Vec<EntryInfo>, bool), String> {
+    let format = Format::from_name(&path.to_string_lossy())
+        .ok_or_else(|| format!("{}: kein unterstütztes Archivformat", path.display()))?;
+    let file = std::fs::File::open(path).map_err(|e| format!("Archiv öffnen: {}", e))?;
+    let mut entries = Vec::new();
+    if format == Format::Zip {
+        let mut zip = zip::ZipArchive::new(BufReader::new(file)).map_err(|e| format!("Zip-Verzeichnis lesen: {}", e))?;
+        for i in 0..zip.len() {
+            if entries.len() >= max_entries {
+                return Ok((format, entries, true));
+            }
+            let f = zip.by_index_raw(i).map_err(|e| format!("Zip-Eintrag {}: {}", i, e))?;
+            let kind = zip_kind(&f);
+            entries.push(EntryInfo {
+                name: f.name().replace('\\', "/"),
+                kind,
+                size: f.size(),
+                compressed_size: Some(f.compressed_size()),
+                modified_ms: f.last_modified().and_then(zip_time_ms).unwrap_or(0),
+                encrypted: f.encrypted(),
+                mode: f.unix_mode(),
+                link_target: None,
+            });
+        }
+        return Ok((format, entries, false));
+    }
+    let truncated = if format == Format::Tar {
+        // Uncompressed: data blocks are seeked over instead of read
+        let mut ar = tar::Archive::new(BufReader::new(file));
+        list_tar(ar.entries_with_seek().map_err(|e| format!("Tar lesen: {}", e))?, max_entries, &mut entries)?
+    } else {
+        let mut ar = tar::Archive::new(tar_stream(format, BufReader::new(file))?);
+        list_tar(ar.entries().map_err(|e| format!("Tar lesen: {}", e))?, max_entries, &mut entries)?
+    };
+    Ok((format, entries, truncated))
+}
+
+fn list_tar<R: Read>(members: tar::Entries<'_, R>, max_entries: usize, entries: &mut Vec<EntryInfo>) -> Result<bool, String> {
+    for entry in members {
+        if entries.len() >= max_entries {
+            return Ok(true);
+        }
+        let entry = entry.map_err(|e| format!("Tar-Header lesen: {}", e))?;
+        let header = entry.header();
+        let name = match entry.path() {
+            Ok(p) => tar_name(&p),
+            Err(_) => continue,
+        };
+        entries.push(EntryInfo {
+            name,
+            kind: tar_kind(header.entry_type()),
+            size: header.size().unwrap_or(0),
+            compressed_size: None,
+            modified_ms: header.mtime().map(|t| t as i64 * 1000).unwrap_or(0),
+            encrypted: false,
+            mode: header.mode().ok(),
+            link_target: entry.link_name().ok().flatten().map(|l| tar_name(&l)),
+        });
+    }
+    Ok(false)
+}
+
+/// Folder tree of a listing. Folders the archive only implies (members "a/b/c.txt"
+/// without an "a/" entry) are added; folder sizes are the sums of their content.
+pub fn tree(entries: &[EntryInfo]) -> Vec<Value> {
+    #[derive(Default)]
+    struct Node {
+        entry: Option<EntryInfo>,
+        children: std::collections::BTreeMap<String, Node>,
+    }
+
+    fn to_json(name: &str, path: &str, node: Node) -> (Value, u64, u64) {
+        let children: Vec<(Value, u64, u64)> = node.children.into_iter()
+            .map(|(child, n)| {
+                let child_path = if path.is_empty() { child.clone() } else { format!("{}/{}", path, child) };
+                to_json(&child, &child_path, n)
+            })
+            .collect();
+        let is_dir = node.entry.as_ref().map(|e| e.kind).unwrap_or(EntryKind::Dir) == EntryKind::Dir || !children.is_empty();
+        let (mut size, mut compressed) = (0u64, 0u64);
+        if !is_dir {
+            if let Some(e) = &node.entry {
+                size = e.size;
+                compressed = e.compressed_size.unwrap_or(0);
+            }
+        }
+        let mut sorted = children;
+        sorted.sort_by_key(|(v, _, _)| (v["kind"] != "dir", v["name"].as_str().unwrap_or("").to_lowercase()));
+        for (_, s, c) in &sorted {
+            size += s;
+            compressed += c;
+        }
+        let e = node.entry.as_ref();
+        let mut v = json!({
+            "name": name,
+            "path": path,
+            "kind": if is_dir { "dir" } else { e.map_or("file", |e| e.kind.as_str()) },
+            "size": size,
+            "compressedSize": e.and_then(|e| e.compressed_size).map(|_| compressed),
+            "modifiedMs": e.map(|e| e.modified_ms),
+            "encrypted": e.is_some_and(|e| e.encrypted),
+            "linkTarget": e.and_then(|e| e.link_target.clone()),
+            "mode": e.and_then(|e| e.mode),
+            "implicit": e.is_none(),
+        });
+        if is_dir {
+            v["children"] = Value::Array(sorted.into_iter().map(|(v, _, _)| v).collect());
+        }
+        (v, size, compressed)
+    }
+
+    let mut root = Node::default();
+    for e in entries {
+        let parts: Vec<&str> = e.name.split('/').filter(|p| !p.is_empty() && *p != ".").collect();
+        if parts.is_empty() {
+            continue;
+        }
+        let mut node = &mut root;
+        for part in parts {
+            node = node.children.entry(part.to_string()).or_default();
+        }
+        // Duplicate names (appended tar members): the last one wins, like on extraction
+        node.entry = Some(e.clone());
+    }
+    match to_json("", "", root).0 {
+        Value::Object(mut o) => match o.remove("children") {
+            Some(Value::Array(children)) => children,
+            _ => Vec::new(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+/// Bytes `offset..offset+length` of one member, decompressed on the fly without
+/// extracting anything. Returns the data and the member's full size.
+pub fn read_entry(path: &Path, entry: &str, offset: u64, length: u64) -> Result<(Vec<u8>, u64), String> {
+    let format = Format::from_name(&path.to_string_lossy())
+        .ok_or_else(|| format!("{}: kein unterstütztes Archivformat", path.display()))?;
+    let wanted = entry.trim_start_matches('/');
+    let file = std::fs::File::open(path).map_err(|e| format!("Archiv öffnen: {}", e))?;
+    let read_range = |data: &mut dyn Read, size: u64| -> Result<Vec<u8>, String> {
+        // Decompressing streams cannot seek — skip by reading
+        std::io::copy(&mut (&mut *data).take(offset), &mut std::io::sink()).map_err(|e| format!("{}: {}", wanted, e))?;
+        let mut buf = Vec::with_capacity(length.min(size.saturating_sub(offset)).min(CHUNK as u64 * 16) as usize);
+        data.take(length).read_to_end(&mut buf).map_err(|e| format!("{}: {}", wanted, e))?;
+        Ok(buf)
+    };
+
+    if format == Format::Zip {
+        let mut zip = zip::ZipArchive::new(BufReader::new(file)).map_err(|e| format!("Zip-Verzeichnis lesen: {}", e))?;
+        let index = (0..zip.len())
+            .find(|&i| zip.name_for_index(i).is_some_and(|n| n.replace('\\', "/").trim_start_matches('/') == wanted))
+            .ok_or_else(|| format!("Eintrag '{}' nicht im Archiv", entry))?;
+        {
+            let raw = zip.by_index_raw(index).map_err(|e| format!("{}: {}", entry, e))?;
+            if raw.encrypted() {
+                return Err(format!("Eintrag '{}' ist verschlüsselt", entry));
+            }
+            if raw.is_dir() {
+                return Err(format!("'{}' ist ein Ordner", entry));
+            }
+        }
+        let mut f = zip.by_index(index).map_err(|e| format!("{}: {}", entry, e))?;
+        let size = f.size();
+        return Ok((read_range(&mut f, size)?, size));
+    }
+
+    let mut ar = tar::Archive::new(tar_stream(format, BufReader::new(file))?);
+    let mut found = None;
+    for member in ar.entries().map_err(|e| format!("Tar lesen: {}", e))? {
+        let mut member = member.map_err(|e| format!("Tar-Header lesen: {}", e))?;
+        let name = match member.path() {
+            Ok(p) => tar_name(&p),
+            Err(_) => continue,
+        };
+        if name.trim_start_matches("./").trim_start_matches('/') != wanted {
+            continue;
+        }
+        if !member.header().entry_type().is_file() {
+            return Err(format!("'{}' ist keine Datei", entry));
+        }
+        let size = member.header().size().unwrap_or(0);
+        // Later members with the same name replace earlier ones — keep scanning
+        found = Some((read_range(&mut member, size)?, size));
+    }
+    found.ok_or_else(|| format!("Eintrag '{}' nicht im Archiv", entry))
+}
+
+// === Extraction ===
+
+/// Safety limits and behaviour of an extraction
+#[derive(Clone, Copy, Debug)]
+pub struct ExtractOptions {
+    pub conflict: ConflictPolicy,
+    /// Symlink members are skipped unless allowed; even then they must point inside the destination
+    pub allow_symlinks: bool,
+    /// None: free space of the destination
+    pub max_total_bytes: Option<u64>,
+    pub max_entries: u64,
+    pub max_ratio: u64,
+}
+
+impl Default for ExtractOptions {
+    fn default() -> Self {
+        Self { conflict: ConflictPolicy::Skip, allow_symlinks: false, max_total_bytes: None, max_entries: DEFAULT_MAX_ENTRIES, max_ratio: DEFAULT_MAX_RATIO }
+    }
+}
+
+/// Member name → path below the destination. Leading `/` is dropped (like GNU tar),
+/// `..`, drive prefixes and (on Windows) `:` in names are refused.
+fn safe_relative(name: &str) -> Result<PathBuf, &'static str> {
+    let mut out = PathBuf::new();
+    for c in crate::ospath::decode(name).components() {
+        match c {
+            Component::Prefix(_) => return Err("absolute_path"),
+            Component::RootDir | Component::CurDir => {}
+            Component::ParentDir => return Err("path_traversal"),
+            Component::Normal(part) => {
+                // Drive-relative names and alternate data streams
+                if cfg!(windows) && part.to_string_lossy().contains(':') {
+                    return Err("invalid_name");
+                }
+                out.push(part);
+            }
+        }
+    }
+    if out.as_os_str().is_empty() {
+        return Err("empty_name");
+    }
+    Ok(out)
+}
+
+enum Done {
+    Dir(PathBuf),
+    File(PathBuf),
+    /// Existing target moved aside before being overwritten
+    Backup { target: PathBuf, backup: PathBuf },
+}
+
+/// One member to write: where it goes and what it is
+struct Member<'m> {
+    name: &'m str,
+    rel: PathBuf,
+    kind: EntryKind,
+    size: u64,
+    modified_ms: i64,
+    mode: Option<u32>,
+    link_target: Option<String>,
+}
+
+struct Extractor<'a> {
+    job: Job<'a>,
+    dest: PathBuf,
+    options: ExtractOptions,
+    max_total: u64,
+    journal: Vec<Done>,
+    /// Files written by this job (hardlink targets must be among them)
+    written: HashSet<PathBuf>,
+    /// Folders (relative to dest) that accepted symlink targets pass through
+    link_walks: HashSet<PathBuf>,
+    entries_seen: u64,
+    bytes_written: u64,
+    files: u64,
+    dirs: u64,
+    skipped: Vec<Value>,
+    rejected: Vec<Value>,
+}
+
+impl Extractor<'_> {
+    fn skip(&mut self, name: &str, reason: &str) {
+        self.skipped.push(json!({ "entry": name, "reason": reason }));
+    }
+
+    fn reject(&mut self, name: &str, reason: &str) {
+        tracing::warn!(entry = %name, reason = reason, "Archiv-Eintrag abgelehnt");
+        self.rejected.push(json!({ "entry": name, "reason": reason }));
+    }
+
+    /// A symlink at `rel` may only point inside the destination. The target is walked
+    /// step by step from the link's folder, the way the OS resolves it: no step may leave
+    /// the destination or pass through a symlink (on disk already or created by this job —
+    /// a purely lexical check is fooled by `up -> ..` followed by `x -> up/../..`).
+    /// The folders passed are remembered so no later member can turn them into links.
+    fn link_target_allowed(&mut self, rel: &Path, target: &str) -> bool {
+        let mut current: Vec<std::ffi::OsString> = rel.parent()
+            .map(|p| p.iter().map(|c| c.to_os_string()).collect())
+            .unwrap_or_default();
+        let steps: Vec<Component> = Path::new(target).components().collect();
+        let mut passed = Vec::new();
+        for (i, step) in steps.iter().enumerate() {
+            match step {
+                Component::CurDir => continue,
+                Component::ParentDir => {
+                    if current.pop().is_none() {
+                        return false;
+                    }
+                }
+                Component::Normal(part) => current.push(part.to_os_string()),
+                Component::RootDir | Component::Prefix(_) => return false,
+            }
+            // The last step may be anything — only the folders on the way redirect
+            if i + 1 < steps.len() {
+                let at: PathBuf = current.iter().collect();
+                if std::fs::symlink_metadata(self.dest.join(&at)).is_ok_and(|m| m.file_type().is_symlink()) {
+                    return false;
+                }
+                passed.push(at);
+            }
+        }
+        self.link_walks.extend(passed);
+        true
+    }
+
+    fn count_entry(&mut self) -> Result<(), String> {
+        self.entries_seen += 1;
+        if self.entries_seen > self.options.max_entries {
+            return Err(format!("Archiv enthält mehr als {} Einträge — abgebrochen (Schutz vor Archivbomben)", self.options.max_entries));
+        }
+        Ok(())
+    }
+
+    /// Create the folders above `rel`; refuses to pass through symlinks or files
+    fn ensure_parents(&mut self, rel: &Path) -> Result<bool, String> {
+        let mut dir = self.dest.clone();
+        let parents: Vec<_> = rel.parent().map(|p| p.components().collect()).unwrap_or_default();
+        for c in parents {
+            dir.push(c);
+            match std::fs::symlink_metadata(&dir) {
+                Ok(m) if m.is_dir() => {}
+                Ok(_) => return Ok(false),
+                Err(_) => {
+                    std::fs::create_dir(&dir).map_err(|e| format!("Ordner {} erstellen: {}", dir.display(), e))?;
+                    self.journal.push(Done::Dir(dir.clone()));
+                    self.dirs += 1;
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// Decide where a member goes when its target exists. None = skip.
+    fn resolve(&mut self, target: &Path, member: &Member) -> Result<Option<PathBuf>, String> {
+        let existing = match std::fs::symlink_metadata(target) {
+            Ok(m) => m,
+            Err(_) => return Ok(Some(target.to_path_buf())),
+        };
+        if member.kind == EntryKind::Dir && existing.is_dir() {
+            return Ok(Some(target.to_path_buf()));
+        }
+        let policy = match self.options.conflict {
+            ConflictPolicy::OverwriteIfNewer => {
+                let existing_ms = existing.modified().ok()
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_millis() as i64)
+                    .unwrap_or(0);
+                if member.modified_ms > existing_ms { ConflictPolicy::Overwrite } else { ConflictPolicy::Skip }
+            }
+            p => p,
+        };
+        match policy {
+            ConflictPolicy::Fail => Err(format!("Ziel existiert bereits: {}", target.display())),
+            ConflictPolicy::Skip | ConflictPolicy::Ask => Ok(None),
+            ConflictPolicy::Rename => Ok(Some(crate::fileops::unique_target(target, member.kind == EntryKind::Dir))),
+            _ => {
+                if existing.is_dir() {
+                    // Never replace a whole folder by a member
+                    return Ok(None);
+                }
+                let backup = crate::fileops::temp_sibling(target, self.job.job_id, "replaced");
+                std::fs::rename(target, &backup).map_err(|e| format!("Vorhandenes Ziel {} ersetzen: {}", target.display(), e))?;
+                self.journal.push(Done::Backup { target: target.to_path_buf(), backup });
+                Ok(Some(target.to_path_buf()))
+            }
+        }
+    }
+
+    /// Write one member. `data` is the member's content stream.
+    fn extract(&mut self, member: Member, data: &mut dyn Read, archive_consumed: &dyn Fn() -> u64) -> Result<(), String> {
+        self.job.check_cancel(CANCELLED)?;
+        self.count_entry()?;
+        self.job.current = member.name.to_string();
+        match member.kind {
+            EntryKind::Other => {
+                self.skip(member.name, "unsupported_type");
+                return Ok(());
+            }
+            EntryKind::Symlink if !self.options.allow_symlinks => {
+                self.skip(member.name, "symlink");
+                return Ok(());
+            }
+            EntryKind::Symlink if self.link_walks.contains(&member.rel) => {
+                // An earlier link resolves through this path — a symlink here would redirect it
+                self.reject(member.name, "symlink_redirects_link");
+                return Ok(());
+            }
+            EntryKind::Symlink if !member.link_target.clone().is_some_and(|t| self.link_target_allowed(&member.rel, &t)) => {
+                self.reject(member.name, "symlink_outside");
+                return Ok(());
+            }
+            _ => {}
+        }
+        if !self.ensure_parents(&member.rel)? {
+            self.reject(member.name, "parent_not_a_folder");
+            return Ok(());
+        }
+        let target = self.dest.join(&member.rel);
+        let target = match self.resolve(&target, &member)? {
+            Some(t) => t,
+            None => {
+                self.skip(member.name, "conflict");
+                return Ok(());
+            }
+        };
+
+        match member.kind {
+            EntryKind::Dir => {
+                if std::fs::symlink_metadata(&target).is_err() {
+                    std::fs::create_dir(&target).map_err(|e| format!("Ordner {} erstellen: {}", target.display(), e))?;
+                    self.journal.push(Done::Dir(target));
+                    self.dirs += 1;
+                }
+                Ok(())
+            }
+            EntryKind::Symlink => self.write_symlink(&member, &target),
+            EntryKind::Hardlink => {
+                let source = member.link_target.as_deref().and_then(|t| safe_relative(t).ok()).map(|r| self.dest.join(r));
+                match source {
+                    Some(s) if self.written.contains(&s) => {
+                        std::fs::hard_link(&s, &target).map_err(|e| format!("Verknüpfung {} erstellen: {}", target.display(), e))?;
+                        self.journal.push(Done::File(target));
+                        self.files += 1;
+                        Ok(())
+                    }
+                    _ => {
+                        self.reject(member.name, "link_target_outside");
+                        Ok(())
+                    }
+                }
+            }
+            _ => self.write_file(&member, &target, data, archive_consumed),
+        }
+    }
+
+    #[cfg(unix)]
+    fn write_symlink(&mut self, member: &Member, target: &Path) -> Result<(), String> {
+        let link = member.link_target.clone().unwrap_or_default();
+        std::os::unix::fs::symlink(&link, target).map_err(|e| format!("Verknüpfung {} erstellen: {}", target.display(), e))?;
+        self.journal.push(Done::File(target.to_path_buf()));
+        self.files += 1;
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn write_symlink(&mut self, member: &Member, _target: &Path) -> Result<(), String> {
+        self.skip(member.name, "symlink_unsupported");
+        Ok(())
+    }
+
+    fn write_file(&mut self, member: &Member, target: &Path, data: &mut dyn Read, archive_consumed: &dyn Fn() -> u64) -> Result<(), String> {
+        let tmp = crate::fileops::temp_sibling(target, self.job.job_id, "part");
+        let result = (|| -> Result<(), String> {
+            let mut out = std::fs::OpenOptions::new().write(true).create_new(true).open(&tmp)
+                .map_err(|e| format!("{} erstellen: {}", target.display(), e))?;
+            let mut buf = vec![0u8; CHUNK];
+            let mut written = 0u64;
+            loop {
+                self.job.check_cancel(CANCELLED)?;
+                let n = data.read(&mut buf).map_err(|e| format!("{} entpacken: {}", member.name, e))?;
+                if n == 0 {
+                    break;
+                }
+                written += n as u64;
+                self.bytes_written += n as u64;
+                // Headers can lie — the data decides
+                if written > member.size {
+                    return Err(format!("{}: mehr Daten als angegeben ({} Bytes) — abgebrochen (Schutz vor Archivbomben)", member.name, member.size));
+                }
+                if self.bytes_written > self.max_total {
+                    return Err(format!("Entpackte Daten überschreiten {} Bytes — abgebrochen (Schutz vor Archivbomben)", self.max_total));
+                }
+                // Zip has no stream counter (0) — its ratios are checked per entry up front
+                let consumed = archive_consumed();
+                if consumed > 0 && self.bytes_written > RATIO_FLOOR && self.bytes_written / consumed > self.options.max_ratio {
+                    return Err(format!("Kompressionsverhältnis über {}:1 — abgebrochen (Schutz vor Archivbomben)", self.options.max_ratio));
+                }
+                out.write_all(&buf[..n]).map_err(|e| format!("{} schreiben: {}", target.display(), e))?;
+                self.job.bytes_done = self.job.bytes_done.max(progress_position(self.bytes_written, archive_consumed()));
+                self.job.progress(false);
+            }
+            if member.modified_ms > 0 {
+                let mtime = UNIX_EPOCH + Duration::from_millis(member.modified_ms as u64);
+                let _ = out.set_times(std::fs::FileTimes::new().set_modified(mtime).set_accessed(SystemTime::now()));
+            }
+            drop(out);
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                // Permission bits only — never setuid/setgid/sticky from an archive
+                if let Some(mode) = member.mode {
+                    let _ = std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(mode & 0o777));
+                }
+            }
+            std::fs::rename(&tmp, target).map_err(|e| format!("{} ablegen: {}", target.display(), e))
+        })();
+        if let Err(e) = result {
+            let _ = std::fs::remove_file(&tmp);
+            return Err(e);
+        }
+        self.journal.push(Done::File(target.to_path_buf()));
+        self.written.insert(target.to_path_buf());
+        self.files += 1;
+        self.job.files_done += 1;
+        Ok(())
+    }
+
+    fn rollback(&mut self) -> Vec<String> {
+        let mut errors = Vec::new();
+        while let Some(step) = self.journal.pop() {
+            let (path, result) = match &step {
+                Done::File(p) => (p, std::fs::remove_file(p)),
+                Done::Dir(p) => (p, std::fs::remove_dir(p)),
+                Done::Backup { target, backup } => (target, std::fs::rename(backup, target)),
+            };
+            if let Err(e) = result {
+                errors.push(format!("{}: {}", path.display(), e));
+            }
+        }
+        errors
+    }
+
+    fn drop_backups(&mut self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for step in &self.journal {
+            if let Done::Backup { backup, .. } = step {
+                if let Err(e) = std::fs::remove_file(backup) {
+                    errors.push(format!("{}: {}", backup.display(), e));
+                }
+            }
+        }
+        errors
+    }
+}
+
+/// Zip progress counts uncompressed bytes, tar streams count compressed input
+fn progress_position(written: u64, consumed: u64) -> u64 {
+    if consumed > 0 { consumed } else { written }
+}
+
+fn extract_zip(x: &mut Extractor, archive: &Path) -> Result<(), String> {
+    let file = std::fs::File::open(archive).map_err(|e| format!("Archiv öffnen: {}", e))?;
+    let mut zip = zip::ZipArchive::new(BufReader::new(file)).map_err(|e| format!("Zip-Verzeichnis lesen: {}", e))?;
+    // Declared sizes first: a bomb is refused before anything is written
+    let mut declared = 0u64;
+    for i in 0..zip.len() {
+        let f = zip.by_index_raw(i).map_err(|e| format!("Zip-Eintrag {}: {}", i, e))?;
+        declared += f.size();
+        if f.size() > RATIO_FLOOR && f.size() / f.compressed_size().max(1) > x.options.max_ratio {
+            return Err(format!("{}: Kompressionsverhältnis über {}:1 — abgebrochen (Schutz vor Archivbomben)", f.name(), x.options.max_ratio));
+        }
+    }
+    if declared > x.max_total {
+        return Err(format!("Archiv würde {} Bytes entpacken, erlaubt sind {}", declared, x.max_total));
+    }
+    x.job.bytes_total = declared;
+    x.job.progress(true);
+
+    for i in 0..zip.len() {
+        let (name, kind, size, modified_ms, mode, encrypted) = {
+            let f = zip.by_index_raw(i).map_err(|e| format!("Zip-Eintrag {}: {}", i, e))?;
+            (f.name().replace('\\', "/"), zip_kind(&f), f.size(), f.last_modified().and_then(zip_time_ms).unwrap_or(0), f.unix_mode(), f.encrypted())
+        };
+        if encrypted {
+            x.skip(&name, "encrypted");
+            continue;
+        }
+        let rel = match safe_relative(&name) {
+            Ok(r) => r,
+            Err(reason) => {
+                x.reject(&name, reason);
+                continue;
+            }
+        };
+        let mut f = zip.by_index(i).map_err(|e| format!("{}: {}", name, e))?;
+        // Zip stores the link target as the member's content
+        let link_target = if kind == EntryKind::Symlink {
+            let mut t = String::new();
+            (&mut f).take(4096).read_to_string(&mut t).map_err(|e| format!("{}: {}", name, e))?;
+            Some(t)
+        } else {
+            None
+        };
+        let member = Member { name: &name, rel, kind, size, modified_ms, mode, link_target };
+        x.extract(member, &mut f, &|| 0)?;
+    }
+    Ok(())
+}
+
+fn extract_tar(x: &mut Extractor, archive: &Path, format: Format) -> Result<(), String> {
+    let file = std::fs::File::open(archive).map_err(|e| format!("Archiv öffnen: {}", e))?;
+    x.job.bytes_total = file.metadata().map(|m| m.len()).unwrap_or(0);
+    x.job.progress(true);
+    let consumed = Rc::new(Cell::new(0u64));
+    let counted = Counted { inner: BufReader::new(file), count: consumed.clone() };
+    let mut ar = tar::Archive::new(tar_stream(format, counted)?);
+    for entry in ar.entries().map_err(|e| format!("Tar lesen: {}", e))? {
+        let mut entry = entry.map_err(|e| format!("Tar-Header lesen: {}", e))?;
+        let header = entry.header();
+        let kind = tar_kind(header.entry_type());
+        let size = header.size().unwrap_or(0);
+        let modified_ms = header.mtime().map(|t| t as i64 * 1000).unwrap_or(0);
+        let mode = header.mode().ok();
+        let name = match entry.path() {
+            Ok(p) => tar_name(&p),
+            Err(e) => return Err(format!("Tar-Eintrag: {}", e)),
+        };
+        let link_target = entry.link_name().ok().flatten().map(|l| tar_name(&l));
+        let rel = match safe_relative(&name) {
+            Ok(r) => r,
+            Err(reason) => {
+                x.reject(&name, reason);
+                continue;
+            }
+        };
+        let member = Member { name: &name, rel, kind, size, modified_ms, mode, link_target };
+        let count = consumed.clone();
+        x.extract(member, &mut entry, &move || count.get())?;
+    }
+    Ok(())
+}
+
+/// Extract an archive into `dest` (blocking). Every member is checked against path
+/// traversal, links leaving the destination and bomb limits; any error or a cancel
+/// rolls back everything this call wrote.
+pub fn extract(app: Option<&tauri::AppHandle>, job_id: &str, archive: &Path, dest: &Path, options: ExtractOptions) -> Value {
+    let start = Instant::now();
+    let job = match start_job(app, job_id, "extract") {
+        Ok(job) => job,
+        Err(error) => return start_failed(app, job_id, "extract", error),
+    };
+    let mut x = Extractor {
+        job,
+        dest: dest.to_path_buf(),
+        options,
+        max_total: u64::MAX,
+        journal: Vec::new(),
+        written: HashSet::new(),
+        link_walks: HashSet::new(),
+        entries_seen: 0,
+        bytes_written: 0,
+        files: 0,
+        dirs: 0,
+        skipped: Vec::new(),
+        rejected: Vec::new(),
+    };
+    x.job.current = crate::ospath::encode(archive.as_os_str());
+    let result = run_extract(&mut x, archive);
+
+    let report = match result {
+        Ok(()) => {
+            let cleanup_errors = x.drop_backups();
+            json!({
+                "jobId": job_id,
+                "operation": "extract",
+                "status": "completed",
+                "destination": crate::ospath::encode(dest.as_os_str()),
+                "files": x.files,
+                "dirs": x.dirs,
+                "bytesWritten": x.bytes_written,
+                "skipped": x.skipped,
+                "rejected": x.rejected,
+                "cleanupErrors": cleanup_errors,
+                "elapsedMs": start.elapsed().as_millis() as u64
+            })
+        }
+        Err(error) => {
+            let status = if error == CANCELLED { "cancelled" } else { "failed" };
+            let rollback_errors = x.rollback();
+            tracing::warn!(job_id = %job_id, error = %error, rollback_errors = rollback_errors.len(), "Entpacken zurückgerollt");
+            json!({
+                "jobId": job_id,
+                "operation": "extract",
+                "status": status,
+                "error": error,
+                "destination": crate::ospath::encode(dest.as_os_str()),
+                "skipped": x.skipped,
+                "rejected": x.rejected,
+                "cleanupErrors": rollback_errors,
+                "elapsedMs": start.elapsed().as_millis() as u64
+            })
+        }
+    };
+    x.job.progress(true);
+    finish(&x.job, &report);
+    report
+}
+
+fn run_extract(x: &mut Extractor, archive: &Path) -> Result<(), String> {
+    let format = Format::from_name(&archive.to_string_lossy())
+        .ok_or_else(|| format!("{}: kein unterstütztes Archivformat", archive.display()))?;
+    if let ConflictPolicy::Ask = x.options.conflict {
+        return Err("Konfliktregel 'ask' wird beim Entpacken nicht unterstützt".to_string());
+    }
+    let dest = x.dest.clone();
+    if std::fs::symlink_metadata(&dest).is_err() {
+        std::fs::create_dir_all(&dest).map_err(|e| format!("Zielordner {} erstellen: {}", dest.display(), e))?;
+        x.journal.push(Done::Dir(dest.clone()));
+    } else if !dest.is_dir() {
+        return Err(format!("{} ist kein Ordner", dest.display()));
+    }
+    let free = fs2::available_space(&dest).unwrap_or(u64::MAX);
+    x.max_total = x.options.max_total_bytes.map_or(free, |m| m.min(free));
+    tracing::info!(job_id = %x.job.job_id, archive = %archive.display(), dest = %dest.display(), format = format.as_str(), "Entpacken gestartet");
+    match format {
+        Format::Zip => extract_zip(x, archive),
+        _ => extract_tar(x, archive, format),
+    }
+}
+
+// === Creation ===
+
+/// Create an archive from files and folders (blocking). Member names are relative to
+/// each source's parent folder. The archive is written next to `dest` and moved into
+/// place when complete — a cancelled or failed run leaves nothing behind.
+pub fn create(app: Option<&tauri::AppHandle>, job_id: &str, sources: &[PathBuf], dest: &Path, format: Format, level: i32) -> Value {
+    let start = Instant::now();
+    let bytes_total: u64 = sources.iter()
+        .flat_map(|s| walkdir::WalkDir::new(s).follow_links(false).into_iter().flatten())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum();
+    let mut job = match start_job(app, job_id, "create") {
+        Ok(job) => job,
+        Err(error) => return start_failed(app, job_id, "create", error),
+    };
+    job.bytes_total = bytes_total;
+    job.progress(true);
+    let tmp = crate::fileops::temp_sibling(dest, job_id, "part");
+    tracing::info!(job_id = %job_id, dest = %dest.display(), format = format.as_str(), level = level, sources = sources.len(), "Archiv-Erstellung gestartet");
+
+    let result = (|| -> Result<u64, String> {
+        let file = std::fs::OpenOptions::new().write(true).create_new(true).open(&tmp)
+            .map_err(|e| format!("{} erstellen: {}", dest.display(), e))?;
+        let items = collect_items(sources, &[dest, tmp.as_path()])?;
+        let out = std::io::BufWriter::with_capacity(CHUNK, file);
+        let out = match format {
+            Format::Zip => write_zip(&mut job, out, &items, level)?,
+            Format::Tar => write_tar(&mut job, out, &items)?,
+            Format::TarGz => write_tar(&mut job, flate2::write::GzEncoder::new(out, flate2::Compression::new(level as u32)), &items)?
+                .finish().map_err(|e| format!("gzip abschließen: {}", e))?,
+            Format::TarXz => write_tar(&mut job, xz2::write::XzEncoder::new(out, level as u32), &items)?
+                .finish().map_err(|e| format!("xz abschließen: {}", e))?,
+            Format::TarZst => {
+                let encoder = zstd::Encoder::new(out, level).map_err(|e| format!("zstd-Encoder: {}", e))?;
+                write_tar(&mut job, encoder, &items)?.finish().map_err(|e| format!("zstd abschließen: {}", e))?
+            }
+        };
+        let file = out.into_inner().map_err(|e| format!("{} schreiben: {}", dest.display(), e.error()))?;
+        file.sync_all().map_err(|e| format!("{} schreiben: {}", dest.display(), e))?;
+        let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+        drop(file);
+        std::fs::rename(&tmp, dest).map_err(|e| format!("{} ablegen: {}", dest.display(), e))?;
+        Ok(size)
+    })();
+
+    let report = match result {
+        Ok(size) => json!({
+            "jobId": job_id,
+            "operation": "create",
+            "status": "completed",
+            "destination": crate::ospath::encode(dest.as_os_str()),
+            "format": format.as_str(),
+            "level": level,
+            "files": job.files_done,
+            "bytesIn": job.bytes_done,
+            "size": size,
+            "elapsedMs": start.elapsed().as_millis() as u64
+        }),
+        Err(error) => {
+            let _ = std::fs::remove_file(&tmp);
+            let status = if error == CANCELLED { "cancelled" } else { "failed" };
+            tracing::warn!(job_id = %job_id, error = %error, "Archiv-Erstellung fehlgeschlagen");
+            json!({
+                "jobId": job_id,
+                "operation": "create",
+                "status": status,
+                "error": error,
+                "destination": crate::ospath::encode(dest.as_os_str()),
+                "elapsedMs": start.elapsed().as_millis() as u64
+            })
+        }
+    };
+    job.progress(true);
+    finish(&job, &report);
+    report
+}
+
+/// A file system entry to store, with its member name
+struct Item {
+    path: PathBuf,
+    name: PathBuf,
+    meta: std::fs::Metadata,
+}
+
+fn collect_items(sources: &[PathBuf], exclude: &[&Path]) -> Result<Vec<Item>, String> {
+    let mut items = Vec::new();
+    for src in sources {
+        let base = src.parent().unwrap_or(Path::new(""));
+        for entry in walkdir::WalkDir::new(src).follow_links(false).sort_by_file_name() {
+            let entry = entry.map_err(|e| format!("{}: {}", src.display(), e))?;
+            if exclude.iter().any(|x| entry.path() == *x) {
+                continue;
+            }
+            let meta = entry.metadata().map_err(|e| format!("{}: {}", entry.path().display(), e))?;
+            let name = entry.path().strip_prefix(base).unwrap_or(entry.path()).to_path_buf();
+            items.push(Item { path: entry.path().to_path_buf(), name, meta });
+        }
+    }
+    Ok(items)
+}
+
+/// Feeds a file into an archive writer while counting progress and honouring cancel
+struct Feed<'j, 'a> {
+    file: std::fs::File,
+    job: &'j mut Job<'a>,
+}
+
+impl Read for Feed<'_, '_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.job.cancelled() {
+            return Err(std::io::Error::other(CANCELLED));
+        }
+        let n = self.file.read(buf)?;
+        self.job.bytes_done += n as u64;
+        self.job.progress(false);
+        Ok(n)
+    }
+}
+
+/// Fails instead of ending early when the inner reader delivers less than `remaining`
+struct ExactLen<R> {
+    inner: R,
+    remaining: u64,
+}
+
+impl<R: Read> Read for ExactLen<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.remaining == 0 {
+            return Ok(0);
+        }
+        let n = self.inner.read(buf)?;
+        if n == 0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "Datei wurde während des Archivierens kleiner"));
+        }
+        self.remaining -= n as u64;
+        Ok(n)
+    }
+}
+
+fn feed_error(job: &Job, path: &Path, e: impl std::fmt::Display) -> String {
+    if job.cancelled() {
+        return CANCELLED.to_string();
+    }
+    format!("{}: {}", path.display(), e)
+}
+
+fn modified_ms(meta: &std::fs::Metadata) -> i64 {
+    meta.modified().ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(unix)]
+fn unix_mode(meta: &std::fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    meta.permissions().mode() & 0o777
+}
+
+#[cfg(not(unix))]
+fn unix_mode(meta: &std::fs::Metadata) -> u32 {
+    if meta.is_dir() { 0o755 } else if meta.permissions().readonly() { 0o444 } else { 0o644 }
+}
+
+fn write_zip<W: Write + std::io::Seek>(job: &mut Job, out: W, items: &[Item], level: i32) -> Result<W, String> {
+    let mut zip = zip::ZipWriter::new(out);
+    for item in items {
+        job.check_cancel(CANCELLED)?;
+        job.current = crate::ospath::encode(item.path.as_os_str());
+        // Zip names are `/`-separated UTF-8
+        let name = item.name.components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let method = if level == 0 { zip::CompressionMethod::Stored } else { zip::CompressionMethod::Deflated };
+        let mut options = zip::write::SimpleFileOptions::default()
+            .compression_method(method)
+            .compression_level((level > 0).then_some(level as i64))
+            .unix_permissions(unix_mode(&item.meta))
+            .large_file(item.meta.len() >= u32::MAX as u64);
+        if let Some(t) = zip_time(modified_ms(&item.meta)) {
+            options = options.last_modified_time(t);
+        }
+        let ft = item.meta.file_type();
+        if ft.is_dir() {
+            zip.add_directory(name, options).map_err(|e| format!("{}: {}", item.path.display(), e))?;
+        } else if ft.is_symlink() {
+            let target = std::fs::read_link(&item.path).map_err(|e| format!("{}: {}", item.path.display(), e))?;
+            zip.add_symlink(name, target.to_string_lossy(), options).map_err(|e| format!("{}: {}", item.path.display(), e))?;
+        } else {
+            zip.start_file(name, options).map_err(|e| format!("{}: {}", item.path.display(), e))?;
+            let file = std::fs::File::open(&item.path).map_err(|e| format!("{}: {}", item.path.display(), e))?;
+            let copied = std::io::copy(&mut Feed { file, job: &mut *job }, &mut zip);
+            copied.map_err(|e| feed_error(job, &item.path, e))?;
+            job.files_done += 1;
+        }
+    }
+    zip.finish().map_err(|e| format!("Zip abschließen: {}", e))
+}
+
+fn write_tar<W: Write>(job: &mut Job, out: W, items: &[Item]) -> Result<W, String> {
+    let mut builder = tar::Builder::new(out);
+    builder.follow_symlinks(false);
+    for item in items {
+        job.check_cancel(CANCELLED)?;
+        job.current = crate::ospath::encode(item.path.as_os_str());
+        let mut header = tar::Header::new_gnu();
+        header.set_metadata_in_mode(&item.meta, tar::HeaderMode::Complete);
+        let ft = item.meta.file_type();
+        let appended = if ft.is_symlink() {
+            let target = std::fs::read_link(&item.path).map_err(|e| format!("{}: {}", item.path.display(), e))?;
+            header.set_size(0);
+            builder.append_link(&mut header, &item.name, &target)
+        } else if ft.is_dir() {
+            header.set_size(0);
+            builder.append_data(&mut header, &item.name, std::io::empty())
+        } else if ft.is_file() {
+            let file = std::fs::File::open(&item.path).map_err(|e| format!("{}: {}", item.path.display(), e))?;
+            let size = header.size().map_err(|e| format!("{}: {}", item.path.display(), e))?;
+            // Exactly the size in the header — the tar writer pads by what it read, so a
+            // file that changed meanwhile would shift every following member
+            let data = ExactLen { inner: Feed { file, job: &mut *job }.take(size), remaining: size };
+            let appended = builder.append_data(&mut header, &item.name, data);
+            job.files_done += 1;
+            appended
+        } else {
+            continue;
+        };
+        appended.map_err(|e| feed_error(job, &item.path, e))?;
+    }
+    builder.into_inner().map_err(|e| format!("Tar abschließen: {}", e))
+}
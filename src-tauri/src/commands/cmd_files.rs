@@ -1,5 +1,5 @@
 use serde_json::{json, Value};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tauri::Emitter;
 use super::validate_path;
 
@@ -12,6 +12,48 @@ pub async fn delete_to_trash(paths: Vec<String>) -> Result<Value, String> {
         validate_path(p)?;
     }
 
+    #[cfg(target_os = "linux")]
+    {
+        let to_trash = paths.clone();
+        let (items, error) = tokio::task::spawn_blocking(move || {
+            let mut items: Vec<Value> = Vec::new();
+            for p in &to_trash {
+                match crate::trash::trash(&crate::ospath::decode(p)) {
+                    Ok(item) => items.push(json!({
+                        "path": p,
+                        "trashPath": crate::ospath::encode(item.trash_path.as_os_str())
+                    })),
+                    Err(e) => return (items, Some(e)),
+                }
+            }
+            (items, None)
+        }).await.map_err(|e| format!("Papierkorb-Task fehlgeschlagen: {}", e))?;
+
+        // Already trashed items are logged even if a later one failed — undo restores them
+        if !items.is_empty() {
+            let desc = if items.len() == 1 {
+                let name = Path::new(&paths[0]).file_name().and_then(|n| n.to_str()).unwrap_or(&paths[0]);
+                format!("\"{}\" in den Papierkorb verschoben", crate::ospath::display(name))
+            } else {
+                format!("{} Elemente in den Papierkorb verschoben", items.len())
+            };
+            crate::undo::log_action("delete_trash", &desc, json!({ "paths": paths, "items": items }), true);
+        }
+        match error {
+            Some(e) => Err(e),
+            None => Ok(json!({ "success": true, "trashed": items.len() })),
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        delete_to_recycle_bin(paths).await
+    }
+}
+
+/// Windows: Papierkorb über die VisualBasic-FileIO-API
+#[cfg(not(target_os = "linux"))]
+async fn delete_to_recycle_bin(paths: Vec<String>) -> Result<Value, String> {
     let ps_paths = paths.iter().map(|p| format!("'{}'", p.replace("'", "''"))).collect::<Vec<_>>().join(",");
     let script = format!(
         r#"Add-Type -AssemblyName Microsoft.VisualBasic
@@ -27,7 +69,7 @@ pub async fn delete_to_trash(paths: Vec<String>) -> Result<Value, String> {
     crate::ps::run_ps(&script).await?;
 
     // Undo-Log AFTER successful trash operation (failed deletes must not appear in undo log)
-    let file_names: Vec<&str> = paths.iter().filter_map(|p| Path::new(p).file_name().and_then(|n| n.to_str())).collect();
+    let file_names: Vec<std::borrow::Cow<str>> = paths.iter().filter_map(|p| Path::new(p).file_name().and_then(|n| n.to_str())).map(crate::ospath::display).collect();
     let desc = if file_names.len() == 1 {
         format!("\"{}\" in den Papierkorb verschoben", file_names[0])
     } else {
@@ -38,6 +80,80 @@ pub async fn delete_to_trash(paths: Vec<String>) -> Result<Value, String> {
     Ok(json!({ "success": true }))
 }
 
+/// Inhalt aller Papierkörbe (Home + Laufwerke), neueste zuerst
+#[tauri::command]
+pub async fn list_trash() -> Result<Value, String> {
+    #[cfg(target_os = "linux")]
+    {
+        tokio::task::spawn_blocking(|| {
+            let items: Vec<Value> = crate::trash::list().iter().map(|i| i.to_json()).collect();
+            json!({ "items": items, "count": items.len() })
+        }).await.map_err(|e| format!("Papierkorb-Task fehlgeschlagen: {}", e))
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        Err("Papierkorb-Verwaltung ist nur unter Linux verfügbar".to_string())
+    }
+}
+
+/// Stellt Papierkorb-Einträge (`id` aus list_trash) am Ursprungsort wieder her
+#[tauri::command]
+pub async fn restore_from_trash(ids: Vec<String>) -> Result<Value, String> {
+    #[cfg(target_os = "linux")]
+    {
+        tokio::task::spawn_blocking(move || {
+            let mut restored: Vec<String> = Vec::new();
+            let mut errors: Vec<Value> = Vec::new();
+            for id in &ids {
+                match crate::trash::restore(&crate::ospath::decode(id)) {
+                    Ok(p) => restored.push(crate::ospath::encode(p.as_os_str())),
+                    Err(e) => errors.push(json!({ "id": id, "error": e })),
+                }
+            }
+            tracing::info!(restored = restored.len(), errors = errors.len(), "Papierkorb-Wiederherstellung");
+            json!({ "success": errors.is_empty(), "restored": restored, "errors": errors })
+        }).await.map_err(|e| format!("Papierkorb-Task fehlgeschlagen: {}", e))
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = ids;
+        Err("Papierkorb-Verwaltung ist nur unter Linux verfügbar".to_string())
+    }
+}
+
+/// Leert den Papierkorb endgültig — alle Einträge oder nur die angegebenen `ids`
+#[tauri::command]
+pub async fn empty_trash(ids: Option<Vec<String>>) -> Result<Value, String> {
+    #[cfg(target_os = "linux")]
+    {
+        tokio::task::spawn_blocking(move || {
+            let (count, freed, errors) = match ids {
+                Some(ids) => {
+                    let (mut count, mut freed, mut errors) = (0usize, 0u64, Vec::new());
+                    for id in &ids {
+                        match crate::trash::purge(&crate::ospath::decode(id)) {
+                            Ok(bytes) => {
+                                count += 1;
+                                freed += bytes;
+                            }
+                            Err(e) => errors.push(e),
+                        }
+                    }
+                    (count, freed, errors)
+                }
+                None => crate::trash::empty_all(),
+            };
+            tracing::warn!(count = count, freed = freed, errors = errors.len(), "Papierkorb geleert");
+            json!({ "success": errors.is_empty(), "deleted": count, "freedBytes": freed, "errors": errors })
+        }).await.map_err(|e| format!("Papierkorb-Task fehlgeschlagen: {}", e))
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = ids;
+        Err("Papierkorb-Verwaltung ist nur unter Linux verfügbar".to_string())
+    }
+}
+
 #[tauri::command]
 pub async fn delete_permanent(paths: Vec<String>) -> Result<Value, String> {
     tracing::warn!(count = paths.len(), "Permanente Löschung angefordert");
@@ -48,21 +164,21 @@ pub async fn delete_permanent(paths: Vec<String>) -> Result<Value, String> {
     // Collect sizes BEFORE deletion (for undo log info)
     let mut sizes: Vec<Value> = Vec::new();
     for p in &paths {
-        let size = tokio::fs::metadata(p).await.map(|m| m.len()).unwrap_or(0);
+        let size = tokio::fs::metadata(crate::ospath::decode(p)).await.map(|m| m.len()).unwrap_or(0);
         sizes.push(json!({ "path": p, "size": size }));
     }
 
     for p in &paths {
-        let path = Path::new(p);
+        let path = crate::ospath::decode(p);
         if path.is_dir() {
-            tokio::fs::remove_dir_all(path).await.map_err(|e| e.to_string())?;
+            tokio::fs::remove_dir_all(&path).await.map_err(|e| e.to_string())?;
         } else {
-            tokio::fs::remove_file(path).await.map_err(|e| e.to_string())?;
+            tokio::fs::remove_file(&path).await.map_err(|e| e.to_string())?;
         }
     }
 
     // Undo-Log AFTER successful deletion (failed deletes must not appear in undo log)
-    let file_names: Vec<&str> = paths.iter().filter_map(|p| Path::new(p).file_name().and_then(|n| n.to_str())).collect();
+    let file_names: Vec<std::borrow::Cow<str>> = paths.iter().filter_map(|p| Path::new(p).file_name().and_then(|n| n.to_str())).map(crate::ospath::display).collect();
     let desc = if file_names.len() == 1 {
         format!("\"{}\" endgültig gelöscht", file_names[0])
     } else {
@@ -76,113 +192,184 @@ pub async fn delete_permanent(paths: Vec<String>) -> Result<Value, String> {
 #[tauri::command]
 pub async fn create_folder(parent_path: String, name: String) -> Result<Value, String> {
     validate_path(&parent_path)?;
-    let full = Path::new(&parent_path).join(&name);
-    tokio::fs::create_dir_all(&full).await.map_err(|e| e.to_string())?;
-    Ok(json!({ "success": true, "path": full.to_string_lossy() }))
+    let full = crate::ospath::decode(&parent_path).join(&name);
+    let path = crate::ospath::encode(full.as_os_str());
+    if full.is_dir() {
+        return Ok(json!({ "success": true, "path": path }));
+    }
+    // Levels that do not exist yet, outermost first — undo removes exactly these
+    let mut created: Vec<String> = full.ancestors()
+        .take_while(|p| std::fs::symlink_metadata(p).is_err())
+        .map(|p| crate::ospath::encode(p.as_os_str()))
+        .collect();
+    created.reverse();
+    let desc = format!("Ordner \"{}\" erstellt", name);
+    crate::undo::perform("create_folder", &desc, json!({ "path": path, "created": created }))?;
+    Ok(json!({ "success": true, "path": path }))
 }
 
 #[tauri::command]
 pub async fn file_rename(old_path: String, new_name: String) -> Result<Value, String> {
     validate_path(&old_path)?;
-    let src = Path::new(&old_path);
-    let old_name = src.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let src = crate::ospath::decode(&old_path);
+    let old_name = crate::ospath::encode(src.file_name().unwrap_or_default());
     let dest = src.parent().unwrap_or(Path::new(".")).join(&new_name);
-    tokio::fs::rename(&src, &dest).await.map_err(|e| e.to_string())?;
-    // Log undo action AFTER successful rename (not before — failed renames should not appear in undo log)
-    let desc = format!("\"{}\" umbenannt zu \"{}\"", old_name, new_name);
-    crate::undo::log_action("file_rename", &desc, json!({
+    let dest = crate::ospath::encode(dest.as_os_str());
+    // Renames without overwriting; logged only if it succeeded
+    let desc = format!("\"{}\" umbenannt zu \"{}\"", crate::ospath::display(&old_name), new_name);
+    crate::undo::perform("file_rename", &desc, json!({
         "old_path": old_path,
-        "new_path": dest.to_string_lossy(),
+        "new_path": dest,
         "old_name": old_name,
         "new_name": new_name
-    }), true);
-    Ok(json!({ "success": true, "newPath": dest.to_string_lossy() }))
+    }))?;
+    Ok(json!({ "success": true, "newPath": dest }))
+}
+
+#[tauri::command]
+pub async fn file_move(app: tauri::AppHandle, source_paths: Vec<String>, dest_dir: String, options: Option<Value>) -> Result<Value, String> {
+    let options = job_options(crate::fileops::JobKind::Move, options.as_ref(), "fail")?;
+    let report = run_file_job(app, crate::fileops::new_job_id(), crate::fileops::JobKind::Move, source_paths, dest_dir, options).await?;
+    match report["status"].as_str() {
+        Some("completed") => Ok(json!({ "success": true, "report": report })),
+        _ => Err(job_error(&report)),
+    }
 }
 
 #[tauri::command]
-pub async fn file_move(source_paths: Vec<String>, dest_dir: String) -> Result<Value, String> {
-    // Validate all paths first, collect move info for undo log
-    let mut moves: Vec<Value> = Vec::new();
+pub async fn file_copy(app: tauri::AppHandle, source_paths: Vec<String>, dest_dir: String, options: Option<Value>) -> Result<Value, String> {
+    let options = job_options(crate::fileops::JobKind::Copy, options.as_ref(), "fail")?;
+    let report = run_file_job(app, crate::fileops::new_job_id(), crate::fileops::JobKind::Copy, source_paths, dest_dir, options).await?;
+    match report["status"].as_str() {
+        Some("completed") => Ok(json!({ "success": true, "report": report })),
+        _ => Err(job_error(&report)),
+    }
+}
+
+/// `{ conflict, verify, preserve }` → job options; unset keys take the kind's defaults
+fn job_options(kind: crate::fileops::JobKind, options: Option<&Value>, default_conflict: &str) -> Result<crate::fileops::JobOptions, String> {
+    let get = |key: &str| options.and_then(|o| o.get(key));
+    let conflict = crate::fileops::ConflictPolicy::parse(get("conflict").and_then(|c| c.as_str()).unwrap_or(default_conflict))?;
+    let mut job = crate::fileops::JobOptions::new(kind, conflict);
+    if let Some(v) = get("verify").and_then(|v| v.as_bool()) {
+        job.verify = v;
+    }
+    if let Some(p) = get("preserve").and_then(|p| p.as_bool()) {
+        job.preserve = p;
+    }
+    Ok(job)
+}
+
+/// First failure of a job report as a command error
+fn job_error(report: &Value) -> String {
+    if report["status"] == "cancelled" {
+        return "Vorgang abgebrochen".to_string();
+    }
+    report["failed"].get(0)
+        .map(|f| format!("{}: {}", f["source"].as_str().unwrap_or(""), f["error"].as_str().unwrap_or("")))
+        .unwrap_or_else(|| "Dateivorgang fehlgeschlagen".to_string())
+}
+
+/// Validate, run a copy/move job on the blocking pool and log moves for undo
+async fn run_file_job(app: tauri::AppHandle, job_id: String, kind: crate::fileops::JobKind, source_paths: Vec<String>, dest_dir: String, options: crate::fileops::JobOptions) -> Result<Value, String> {
     for src in &source_paths {
         validate_path(src)?;
-        let source_dir = Path::new(src).parent().and_then(|p| p.to_str()).unwrap_or("").to_string();
-        let name = Path::new(src).file_name().unwrap_or_default();
-        let dest = Path::new(&dest_dir).join(name);
-        moves.push(json!({ "source": src, "source_dir": source_dir, "dest": dest.to_string_lossy() }));
     }
+    validate_path(&dest_dir)?;
+    let sources: Vec<_> = source_paths.iter().map(|s| crate::ospath::decode(s)).collect();
+    let dest = crate::ospath::decode(&dest_dir);
+    let report = tokio::task::spawn_blocking(move || crate::fileops::run(Some(app), &job_id, kind, sources, dest, options))
+        .await.map_err(|e| format!("Datei-Auftrag fehlgeschlagen: {}", e))?;
+    log_job(kind, &report, &dest_dir);
+    Ok(report)
+}
 
-    // Perform the actual move operations first
-    for src in &source_paths {
-        let name = Path::new(src).file_name().unwrap_or_default();
-        let dest = Path::new(&dest_dir).join(name);
-        // Try rename first (fast, same-volume). Fall back to copy+delete for cross-volume moves.
-        match tokio::fs::rename(src, &dest).await {
-            Ok(_) => {},
-            Err(_) => {
-                let src_path = Path::new(src);
-                if src_path.is_dir() {
-                    // Recursive directory copy + delete
-                    for entry in walkdir::WalkDir::new(src_path).into_iter().filter_map(|e| e.ok()) {
-                        let rel = entry.path().strip_prefix(src_path).unwrap_or(entry.path());
-                        let target = dest.join(rel);
-                        if entry.file_type().is_dir() {
-                            tokio::fs::create_dir_all(&target).await.map_err(|e| e.to_string())?;
-                        } else {
-                            if let Some(parent) = target.parent() {
-                                tokio::fs::create_dir_all(parent).await.map_err(|e| e.to_string())?;
-                            }
-                            tokio::fs::copy(entry.path(), &target).await.map_err(|e| e.to_string())?;
-                        }
-                    }
-                    tokio::fs::remove_dir_all(src_path).await.map_err(|e| e.to_string())?;
-                } else {
-                    tokio::fs::copy(src, &dest).await.map_err(|e| e.to_string())?;
-                    tokio::fs::remove_file(src).await.map_err(|e| e.to_string())?;
+/// Undo-Log for a finished job. Only targets the job created from scratch are listed —
+/// undoing a merge into an existing folder would take the folder's old content along.
+/// Partial moves stay out too: their source folder still holds the skipped entries.
+fn log_job(kind: crate::fileops::JobKind, report: &Value, dest_dir: &str) {
+    let is_move = kind == crate::fileops::JobKind::Move;
+    let done: Vec<Value> = report["succeeded"].as_array().into_iter().flatten()
+        .filter(|m| m["created"].as_bool() != Some(false) && !(is_move && m["partial"] == true))
+        .filter_map(|m| {
+            let src = m["source"].as_str()?;
+            let source_dir = Path::new(src).parent().and_then(|p| p.to_str()).unwrap_or("").to_string();
+            Some(if is_move {
+                json!({ "source": src, "source_dir": source_dir, "dest": m["target"] })
+            } else {
+                let mut copy = json!({ "source": src, "target": m["target"] });
+                // Undo deletes the copy only while it still matches this state
+                if let Some(stamp) = m["target"].as_str().and_then(|t| crate::undo::CopyStamp::of(&crate::ospath::decode(t))) {
+                    stamp.record(&mut copy);
                 }
-            }
-        }
+                copy
+            })
+        })
+        .collect();
+    if done.is_empty() {
+        return;
     }
-
-    // Undo-Log AFTER successful move (failed moves must not appear in undo log)
-    let file_names: Vec<&str> = source_paths.iter().filter_map(|p| Path::new(p).file_name().and_then(|n| n.to_str())).collect();
+    let file_names: Vec<std::borrow::Cow<str>> = done.iter().filter_map(|m| m["source"].as_str()).filter_map(|p| Path::new(p).file_name().and_then(|n| n.to_str())).map(crate::ospath::display).collect();
+    let dest_name = dest_dir.split(&['\\', '/'][..]).last().unwrap_or(dest_dir);
+    let verb = if is_move { "verschoben" } else { "kopiert" };
     let desc = if file_names.len() == 1 {
-        format!("\"{}\" verschoben nach {}", file_names[0], dest_dir.split(&['\\', '/'][..]).last().unwrap_or(&dest_dir))
+        format!("\"{}\" {} nach {}", file_names[0], verb, dest_name)
     } else {
-        format!("{} Elemente verschoben nach {}", file_names.len(), dest_dir.split(&['\\', '/'][..]).last().unwrap_or(&dest_dir))
+        format!("{} Elemente {} nach {}", file_names.len(), verb, dest_name)
     };
-    crate::undo::log_action("file_move", &desc, json!({ "moves": moves }), true);
+    if is_move {
+        crate::undo::log_action("file_move", &desc, json!({ "moves": done }), true);
+    } else {
+        crate::undo::log_action("file_copy", &desc, json!({ "copies": done }), true);
+    }
+}
 
+/// Start a copy/move job in the background. Progress, conflict questions and the
+/// final report arrive as `file-job-*` events; options: `{ conflict, verify, preserve }`
+/// (conflict defaults to "ask", moves verify by default).
+#[tauri::command]
+pub async fn start_file_job(app: tauri::AppHandle, kind: String, source_paths: Vec<String>, dest_dir: String, options: Option<Value>) -> Result<Value, String> {
+    let kind = match kind.as_str() {
+        "copy" => crate::fileops::JobKind::Copy,
+        "move" => crate::fileops::JobKind::Move,
+        _ => return Err(format!("Unbekannte Auftragsart: {} (erlaubt: copy, move)", kind)),
+    };
+    let options = job_options(kind, options.as_ref(), "ask")?;
+    let job_id = crate::fileops::new_job_id();
+    let id = job_id.clone();
+    tauri::async_runtime::spawn(async move {
+        // Failures before the job starts (validation) have no report yet
+        if let Err(e) = run_file_job(app.clone(), id.clone(), kind, source_paths, dest_dir, options).await {
+            tracing::warn!(job_id = %id, error = %e, "Datei-Auftrag nicht gestartet");
+            let _ = app.emit("file-job-error", json!({ "jobId": id, "status": "failed", "error": e }));
+        }
+    });
+    Ok(json!({ "jobId": job_id }))
+}
+
+#[tauri::command]
+pub async fn cancel_file_job(job_id: String) -> Result<Value, String> {
+    if !crate::fileops::cancel(&job_id) {
+        return Err(format!("Kein laufender Auftrag '{}'", job_id));
+    }
     Ok(json!({ "success": true }))
 }
 
 #[tauri::command]
-pub async fn file_copy(source_paths: Vec<String>, dest_dir: String) -> Result<Value, String> {
-    for src in &source_paths {
-        validate_path(src)?;
-        let name = Path::new(src).file_name().unwrap_or_default();
-        let dest = Path::new(&dest_dir).join(name);
-        let src_path = Path::new(src);
-        if src_path.is_dir() {
-            // Rekursive Ordner-Kopie via walkdir
-            for entry in walkdir::WalkDir::new(src_path).into_iter().filter_map(|e| e.ok()) {
-                let rel = entry.path().strip_prefix(src_path).unwrap_or(entry.path());
-                let target = dest.join(rel);
-                if entry.file_type().is_dir() {
-                    tokio::fs::create_dir_all(&target).await.map_err(|e| e.to_string())?;
-                } else {
-                    if let Some(parent) = target.parent() {
-                        tokio::fs::create_dir_all(parent).await.map_err(|e| e.to_string())?;
-                    }
-                    tokio::fs::copy(entry.path(), &target).await.map_err(|e| e.to_string())?;
-                }
-            }
-        } else {
-            tokio::fs::copy(src, &dest).await.map_err(|e| e.to_string())?;
-        }
+pub async fn resolve_file_conflict(job_id: String, action: String, apply_to_all: Option<bool>) -> Result<Value, String> {
+    let policy = crate::fileops::ConflictPolicy::parse(&action)?;
+    if policy == crate::fileops::ConflictPolicy::Ask {
+        return Err("'ask' ist keine Antwort auf einen Konflikt".to_string());
     }
+    crate::fileops::answer_conflict(&job_id, crate::fileops::ConflictAnswer { policy, apply_to_all: apply_to_all.unwrap_or(false) })?;
     Ok(json!({ "success": true }))
 }
 
+#[tauri::command]
+pub async fn get_file_jobs() -> Result<Value, String> {
+    Ok(crate::fileops::running())
+}
+
 #[tauri::command]
 pub async fn file_properties(file_path: String) -> Result<Value, String> {
     let script = format!(
@@ -205,15 +392,15 @@ pub async fn file_properties(file_path: String) -> Result<Value, String> {
 pub async fn file_properties_general(file_path: String) -> Result<Value, String> {
     use std::os::windows::fs::MetadataExt;
 
-    let path = std::path::Path::new(&file_path);
-    let meta = tokio::fs::metadata(&file_path).await
+    let path = crate::ospath::decode(&file_path);
+    let meta = tokio::fs::metadata(&path).await
         .map_err(|e| format!("Metadaten-Fehler: {}", e))?;
 
     let name = path.file_name()
-        .map(|n| n.to_string_lossy().to_string())
+        .map(crate::ospath::encode)
         .unwrap_or_else(|| file_path.clone());
     let parent_path = path.parent()
-        .map(|p| p.to_string_lossy().to_string())
+        .map(|p| crate::ospath::encode(p.as_os_str()))
         .unwrap_or_default();
     let extension = path.extension()
         .map(|e| format!(".{}", e.to_string_lossy()))
@@ -297,7 +484,7 @@ pub async fn file_properties_general(file_path: String) -> Result<Value, String>
         };
     }
 
-    Ok(json!({
+    Ok(crate::ospath::annotate(json!({
         "name": name,
         "path": file_path,
         "parentPath": parent_path,
@@ -317,7 +504,7 @@ pub async fn file_properties_general(file_path: String) -> Result<Value, String>
         "hidden": hidden,
         "system": system,
         "archive": archive
-    }))
+    })))
 }
 
 #[tauri::command]
@@ -477,35 +664,58 @@ try {{
 }
 
 #[tauri::command]
-pub async fn file_properties_hash(file_path: String, algorithm: String) -> Result<Value, String> {
-    let safe_path = file_path.replace("'", "''");
-    // Whitelist: nur bekannte Hash-Algorithmen
-    let safe_algo = match algorithm.to_uppercase().as_str() {
-        "MD5" => "MD5",
-        "SHA1" => "SHA1",
-        "SHA256" => "SHA256",
-        "SHA384" => "SHA384",
-        "SHA512" => "SHA512",
-        _ => return Err(format!("Unbekannter Algorithmus: {}", algorithm)),
-    };
-    let script = format!(
-        r#"(Get-FileHash -LiteralPath '{}' -Algorithm {}).Hash"#,
-        safe_path, safe_algo
-    );
-    // Hashing large files can take >30s — 120s timeout
-    let hash = crate::ps::run_ps_with_timeout(&script, 120).await?;
-    Ok(json!({ "algorithm": safe_algo, "hash": hash.trim() }))
+pub async fn file_properties_hash(app: tauri::AppHandle, file_path: String, algorithm: String, job_id: Option<String>) -> Result<Value, String> {
+    let algo = crate::hashing::Algorithm::parse(&algorithm)?;
+    let job_id = job_id.unwrap_or_else(crate::hashing::new_job_id);
+    let result = tokio::task::spawn_blocking(move || {
+        crate::hashing::hash_file(Some(&app), &job_id, &crate::ospath::decode(&file_path), &[algo])
+    }).await.map_err(|e| format!("Hash-Task fehlgeschlagen: {}", e))??;
+    // Uppercase like the former Get-FileHash output
+    let hash = result["hashes"][algo.as_str()].as_str().unwrap_or("").to_uppercase();
+    Ok(json!({ "algorithm": algo.as_str(), "hash": hash }))
+}
+
+/// Hash one file with several algorithms in one pass. Progress arrives as `hash-progress`;
+/// pass your own `job_id` to be able to cancel it with `cancel_hash`.
+#[tauri::command]
+pub async fn hash_file(app: tauri::AppHandle, file_path: String, algorithms: Vec<String>, job_id: Option<String>) -> Result<Value, String> {
+    let algos = algorithms.iter().map(|a| crate::hashing::Algorithm::parse(a)).collect::<Result<Vec<_>, _>>()?;
+    if algos.is_empty() {
+        return Err("Kein Algorithmus angegeben".to_string());
+    }
+    let job_id = job_id.unwrap_or_else(crate::hashing::new_job_id);
+    tokio::task::spawn_blocking(move || {
+        crate::hashing::hash_file(Some(&app), &job_id, &crate::ospath::decode(&file_path), &algos)
+    }).await.map_err(|e| format!("Hash-Task fehlgeschlagen: {}", e))?
+}
+
+/// Check the files listed in a sha256sum/md5sum/BSD/SFV-style checksum file
+#[tauri::command]
+pub async fn verify_checksum_file(app: tauri::AppHandle, checksum_path: String, algorithm: Option<String>, job_id: Option<String>) -> Result<Value, String> {
+    let forced = algorithm.as_deref().map(crate::hashing::Algorithm::parse).transpose()?;
+    let job_id = job_id.unwrap_or_else(crate::hashing::new_job_id);
+    tokio::task::spawn_blocking(move || {
+        crate::hashing::verify_checksum_file(Some(&app), &job_id, &crate::ospath::decode(&checksum_path), forced)
+    }).await.map_err(|e| format!("Prüf-Task fehlgeschlagen: {}", e))?
+}
+
+#[tauri::command]
+pub async fn cancel_hash(job_id: String) -> Result<Value, String> {
+    if !crate::hashing::cancel(&job_id) {
+        return Err(format!("Kein laufender Prüfsummen-Auftrag '{}'", job_id));
+    }
+    Ok(json!({ "success": true }))
 }
 
 #[tauri::command]
 pub async fn open_file(file_path: String) -> Result<Value, String> {
-    crate::ps::run_ps(&format!("Start-Process '{}'", file_path.replace("'", "''"))).await?;
+    crate::ps::run_ps_with_path("Start-Process -FilePath $env:SA_TARGET_PATH", &crate::ospath::decode(&file_path)).await?;
     Ok(json!({ "success": true }))
 }
 
 #[tauri::command]
 pub async fn show_in_explorer(file_path: String) -> Result<Value, String> {
-    crate::ps::run_ps(&format!("explorer.exe /select,'{}'", file_path.replace("'", "''"))).await?;
+    crate::ps::run_ps_with_path("explorer.exe \"/select,$env:SA_TARGET_PATH\"", &crate::ospath::decode(&file_path)).await?;
     Ok(json!({ "success": true }))
 }
 
@@ -513,9 +723,9 @@ pub async fn show_in_explorer(file_path: String) -> Result<Value, String> {
 
 #[tauri::command]
 pub async fn run_as_admin(file_path: String) -> Result<Value, String> {
-    let safe_path = file_path.replace("'", "''");
+    let path = crate::ospath::decode(&file_path);
     // Validate: only allow executable files
-    let ext = std::path::Path::new(&file_path)
+    let ext = path
         .extension()
         .and_then(|e| e.to_str())
         .unwrap_or("")
@@ -523,11 +733,10 @@ pub async fn run_as_admin(file_path: String) -> Result<Value, String> {
     if ext != "exe" && ext != "msi" && ext != "bat" && ext != "cmd" && ext != "ps1" {
         return Err(format!("Nur ausführbare Dateien können als Administrator gestartet werden (erhalten: .{})", ext));
     }
-    if !std::path::Path::new(&file_path).exists() {
+    if !path.exists() {
         return Err("Datei existiert nicht".to_string());
     }
-    let script = format!("Start-Process -FilePath '{}' -Verb RunAs", safe_path);
-    match crate::ps::run_ps(&script).await {
+    match crate::ps::run_ps_with_path("Start-Process -FilePath $env:SA_TARGET_PATH -Verb RunAs", &path).await {
         Ok(_) => Ok(json!({ "success": true })),
         Err(e) => Ok(json!({ "success": false, "error": format!("Elevation fehlgeschlagen: {}", e) })),
     }
@@ -535,120 +744,157 @@ pub async fn run_as_admin(file_path: String) -> Result<Value, String> {
 
 // === Archive Extraction ===
 
+/// Extract a zip/tar/tar.gz/tar.xz/tar.zst archive natively. Without `dest_dir` the archive
+/// goes into a folder named after it next to the archive. Options: `conflict` (default
+/// "skip" — existing files stay unless "overwrite" is passed), `allowSymlinks`,
+/// `maxTotalBytes`, `maxEntries`, `maxRatio`, `jobId`.
 #[tauri::command]
-pub async fn extract_archive(archive_path: String, dest_dir: Option<String>) -> Result<Value, String> {
-    let safe_archive = archive_path.replace("'", "''");
-
-    // Validate archive exists
-    if !std::path::Path::new(&archive_path).exists() {
+pub async fn extract_archive(app: tauri::AppHandle, archive_path: String, dest_dir: Option<String>, options: Option<Value>) -> Result<Value, String> {
+    let archive = crate::ospath::decode(&archive_path);
+    if !archive.is_file() {
         return Err("Archiv-Datei existiert nicht".to_string());
     }
-
-    let ext = std::path::Path::new(&archive_path)
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("")
-        .to_lowercase();
-
-    // Determine destination: subfolder next to archive if not specified
-    let destination = if let Some(d) = dest_dir {
-        d.replace("'", "''")
-    } else {
-        let stem = std::path::Path::new(&archive_path)
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("extracted");
-        let parent = std::path::Path::new(&archive_path)
-            .parent()
-            .and_then(|p| p.to_str())
-            .unwrap_or(".");
-        format!("{}\\{}", parent, stem).replace("'", "''")
+    let file_name = archive.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let format = crate::archive::Format::from_name(&file_name)
+        .ok_or_else(|| format!("Archivformat von '{}' wird nicht unterstützt (zip, tar, tar.gz, tar.xz, tar.zst)", file_name))?;
+    let dest = match dest_dir {
+        Some(d) => crate::ospath::decode(&d),
+        None => archive.with_file_name(format.strip_suffix(&file_name)),
     };
+    validate_path(&crate::ospath::encode(dest.as_os_str()))?;
 
-    let script = match ext.as_str() {
-        "zip" => format!(
-            "Expand-Archive -LiteralPath '{}' -DestinationPath '{}' -Force; Write-Output 'OK'",
-            safe_archive, destination
-        ),
-        _ => return Err(format!(
-            "Archivformat '.{}' wird derzeit nicht unterstützt. Nur .zip wird nativ unterstützt.",
-            ext
-        )),
+    let get = |key: &str| options.as_ref().and_then(|o| o.get(key));
+    let mut extract = crate::archive::ExtractOptions {
+        conflict: crate::fileops::ConflictPolicy::parse(get("conflict").and_then(|c| c.as_str()).unwrap_or("skip"))?,
+        ..Default::default()
     };
-
-    match crate::ps::run_ps(&script).await {
-        Ok(output) => {
-            if output.trim().contains("OK") || output.trim().is_empty() {
-                Ok(json!({ "success": true, "destination": destination.replace("''", "'") }))
-            } else {
-                Ok(json!({ "success": false, "error": output.trim() }))
-            }
-        }
-        Err(e) => Err(format!("Entpacken fehlgeschlagen: {}", e)),
+    if let Some(v) = get("allowSymlinks").and_then(|v| v.as_bool()) {
+        extract.allow_symlinks = v;
+    }
+    extract.max_total_bytes = get("maxTotalBytes").and_then(|v| v.as_u64());
+    if let Some(v) = get("maxEntries").and_then(|v| v.as_u64()) {
+        extract.max_entries = v;
+    }
+    if let Some(v) = get("maxRatio").and_then(|v| v.as_u64()) {
+        extract.max_ratio = v;
     }
+    let job_id = get("jobId").and_then(|v| v.as_str()).map(String::from).unwrap_or_else(crate::archive::new_job_id);
+
+    let mut report = tokio::task::spawn_blocking(move || crate::archive::extract(Some(&app), &job_id, &archive, &dest, extract))
+        .await.map_err(|e| format!("Entpacken fehlgeschlagen: {}", e))?;
+    report["success"] = json!(report["status"] == "completed");
+    Ok(report)
 }
 
 // === Archive Creation ===
 
+/// Pack files and folders into a zip/tar/tar.gz/tar.xz/tar.zst archive. Options: `format`
+/// (default zip, or taken from `dest_path`), `level`, `conflict` (default "fail"), `jobId`.
 #[tauri::command]
-pub async fn create_archive(source_paths: Vec<String>, dest_path: Option<String>) -> Result<Value, String> {
+pub async fn create_archive(app: tauri::AppHandle, source_paths: Vec<String>, dest_path: Option<String>, options: Option<Value>) -> Result<Value, String> {
     if source_paths.is_empty() {
         return Err("Keine Dateien zum Komprimieren ausgewählt".to_string());
     }
-
-    // Validate all source paths exist
-    for p in &source_paths {
-        if !std::path::Path::new(p).exists() {
+    let sources: Vec<PathBuf> = source_paths.iter().map(|p| crate::ospath::decode(p)).collect();
+    for (p, src) in source_paths.iter().zip(&sources) {
+        if std::fs::symlink_metadata(src).is_err() {
             return Err(format!("Datei/Ordner existiert nicht: {}", p));
         }
     }
 
-    // Determine destination: name.zip next to first source file, or custom dest_path
-    let destination = if let Some(d) = dest_path {
-        d
-    } else if source_paths.len() == 1 {
-        // Single file/folder: use its name + .zip
-        let source = std::path::Path::new(&source_paths[0]);
-        let stem = source.file_stem().and_then(|s| s.to_str()).unwrap_or("archiv");
-        let parent = source.parent().and_then(|p| p.to_str()).unwrap_or(".");
-        format!("{}\\{}.zip", parent, stem)
+    let get = |key: &str| options.as_ref().and_then(|o| o.get(key));
+    let format = match get("format").and_then(|f| f.as_str()) {
+        Some(f) => crate::archive::Format::parse(f)?,
+        None => dest_path.as_deref().and_then(crate::archive::Format::from_name).unwrap_or(crate::archive::Format::Zip),
+    };
+    let level = format.level(get("level").and_then(|l| l.as_i64()).map(|l| l as i32))?;
+    let conflict = crate::fileops::ConflictPolicy::parse(get("conflict").and_then(|c| c.as_str()).unwrap_or("fail"))?;
+
+    // Default: name.<format> next to a single source, or named after the common parent folder
+    let dest = match dest_path {
+        Some(d) => crate::ospath::decode(&d),
+        None => {
+            let first = &sources[0];
+            let parent = first.parent().unwrap_or(Path::new("."));
+            let stem = if sources.len() == 1 {
+                if first.is_dir() { first.file_name() } else { first.file_stem() }
+            } else {
+                parent.file_name()
+            };
+            let stem = stem.map(|s| s.to_string_lossy().into_owned()).unwrap_or_else(|| "archiv".to_string());
+            parent.join(format!("{}.{}", stem, format.as_str()))
+        }
+    };
+    validate_path(&crate::ospath::encode(dest.as_os_str()))?;
+    let dest = if std::fs::symlink_metadata(&dest).is_ok() {
+        match conflict {
+            crate::fileops::ConflictPolicy::Overwrite | crate::fileops::ConflictPolicy::OverwriteIfNewer => dest,
+            crate::fileops::ConflictPolicy::Rename => crate::fileops::unique_target(&dest, false),
+            _ => return Err(format!("Ziel existiert bereits: {}", dest.display())),
+        }
     } else {
-        // Multiple files: use parent folder name + .zip
-        let first = std::path::Path::new(&source_paths[0]);
-        let parent = first.parent().and_then(|p| p.to_str()).unwrap_or(".");
-        let parent_name = std::path::Path::new(parent)
-            .file_name().and_then(|n| n.to_str()).unwrap_or("archiv");
-        format!("{}\\{}.zip", parent, parent_name)
+        dest
     };
+    let job_id = get("jobId").and_then(|v| v.as_str()).map(String::from).unwrap_or_else(crate::archive::new_job_id);
 
-    let safe_dest = destination.replace("'", "''");
+    let mut report = tokio::task::spawn_blocking(move || crate::archive::create(Some(&app), &job_id, &sources, &dest, format, level))
+        .await.map_err(|e| format!("Archiv-Erstellung fehlgeschlagen: {}", e))?;
+    report["success"] = json!(report["status"] == "completed");
+    Ok(report)
+}
 
-    // Build PowerShell path list
-    let safe_sources: Vec<String> = source_paths.iter()
-        .map(|p| format!("'{}'", p.replace("'", "''")))
-        .collect();
-    let source_list = safe_sources.join(",");
+/// Entry tree of an archive (headers only — nothing is extracted)
+#[tauri::command]
+pub async fn list_archive(archive_path: String, max_entries: Option<u32>) -> Result<Value, String> {
+    let archive = crate::ospath::decode(&archive_path);
+    let limit = max_entries.unwrap_or(100_000) as usize;
+    tokio::task::spawn_blocking(move || {
+        let (format, entries, truncated) = crate::archive::list(&archive, limit)?;
+        let files = entries.iter().filter(|e| e.kind != crate::archive::EntryKind::Dir);
+        let total_size: u64 = files.clone().map(|e| e.size).sum();
+        let compressed: Option<u64> = files.map(|e| e.compressed_size).sum();
+        Ok(json!({
+            "path": archive_path,
+            "format": format.as_str(),
+            "entries": crate::archive::tree(&entries),
+            "totalEntries": entries.len(),
+            "totalSize": total_size,
+            "totalCompressedSize": compressed,
+            "archiveSize": std::fs::metadata(&archive).map(|m| m.len()).unwrap_or(0),
+            "encrypted": entries.iter().any(|e| e.encrypted),
+            "truncated": truncated
+        }))
+    }).await.map_err(|e| format!("Archiv-Task fehlgeschlagen: {}", e))?
+}
 
-    let script = format!(
-        "Compress-Archive -LiteralPath @({}) -DestinationPath '{}' -Force; Write-Output 'OK'",
-        source_list, safe_dest
-    );
+/// Upper bound of one `read_archive_entry` call and of archive previews
+const ARCHIVE_PREVIEW_BYTES: u64 = 16 * 1024 * 1024;
 
-    match crate::ps::run_ps_with_timeout(&script, 120).await {
-        Ok(output) => {
-            if output.trim().contains("OK") || output.trim().is_empty() {
-                let file_size = std::fs::metadata(&destination).map(|m| m.len()).unwrap_or(0);
-                Ok(json!({
-                    "success": true,
-                    "destination": destination,
-                    "size": file_size
-                }))
-            } else {
-                Ok(json!({ "success": false, "error": output.trim() }))
-            }
-        }
-        Err(e) => Err(format!("ZIP-Erstellung fehlgeschlagen: {}", e)),
+/// Bytes of a single archive member as base64 (like `read_file_binary`). `range` is
+/// `{ offset, length }`; without it the member is read from the start up to 16 MiB.
+#[tauri::command]
+pub async fn read_archive_entry(archive_path: String, entry: String, range: Option<Value>) -> Result<Value, String> {
+    let offset = range.as_ref().and_then(|r| r["offset"].as_u64()).unwrap_or(0);
+    let length = range.as_ref().and_then(|r| r["length"].as_u64()).unwrap_or(ARCHIVE_PREVIEW_BYTES).min(ARCHIVE_PREVIEW_BYTES);
+    let archive = crate::ospath::decode(&archive_path);
+    let (bytes, size) = tokio::task::spawn_blocking(move || crate::archive::read_entry(&archive, &entry, offset, length))
+        .await.map_err(|e| format!("Archiv-Task fehlgeschlagen: {}", e))??;
+    use base64::Engine;
+    Ok(json!({
+        "data": base64::engine::general_purpose::STANDARD.encode(&bytes),
+        "offset": offset,
+        "length": bytes.len(),
+        "size": size,
+        "eof": offset + bytes.len() as u64 >= size
+    }))
+}
+
+#[tauri::command]
+pub async fn cancel_archive_job(job_id: String) -> Result<Value, String> {
+    if !crate::archive::cancel(&job_id) {
+        return Err(format!("Kein laufender Archiv-Auftrag '{}'", job_id));
     }
+    Ok(json!({ "success": true }))
 }
 
 // === Context Menu ===
@@ -697,14 +943,40 @@ pub async fn show_confirm_dialog(app: tauri::AppHandle, options: Value) -> Resul
 
 // === Preview / Editor ===
 
+/// With `archive_entry`, `file_path` is an archive and the preview shows that member.
+/// A member larger than the preview limit is read only up to it — `totalLines` is then
+/// null (unknown) and `truncated` is set.
 #[tauri::command]
-pub async fn read_file_preview(file_path: String, max_lines: Option<u32>) -> Result<Value, String> {
+pub async fn read_file_preview(file_path: String, max_lines: Option<u32>, archive_entry: Option<String>) -> Result<Value, String> {
     if validate_path(&file_path).is_err() {
         tracing::warn!(path = %file_path, "Lese-Zugriff auf Systempfad");
     }
-    let content = tokio::fs::read_to_string(&file_path).await.map_err(|e| e.to_string())?;
-    let lines: Vec<&str> = content.lines().take(max_lines.unwrap_or(100) as usize).collect();
-    Ok(json!({ "content": lines.join("\n"), "totalLines": content.lines().count(), "truncated": content.lines().count() > max_lines.unwrap_or(100) as usize }))
+    let max_lines = max_lines.unwrap_or(100) as usize;
+    let (content, cut) = match archive_entry {
+        Some(entry) => {
+            let archive = crate::ospath::decode(&file_path);
+            let (mut bytes, size) = tokio::task::spawn_blocking(move || crate::archive::read_entry(&archive, &entry, 0, ARCHIVE_PREVIEW_BYTES))
+                .await.map_err(|e| format!("Archiv-Task fehlgeschlagen: {}", e))??;
+            let cut = (bytes.len() as u64) < size;
+            if cut {
+                // The byte limit may split the last character — drop that incomplete sequence
+                if let Err(e) = std::str::from_utf8(&bytes) {
+                    if e.error_len().is_none() {
+                        bytes.truncate(e.valid_up_to());
+                    }
+                }
+            }
+            (String::from_utf8(bytes).map_err(|_| "Eintrag ist keine UTF-8-Textdatei".to_string())?, cut)
+        }
+        None => (tokio::fs::read_to_string(crate::ospath::decode(&file_path)).await.map_err(|e| e.to_string())?, false),
+    };
+    let lines: Vec<&str> = content.lines().take(max_lines).collect();
+    let total_lines = content.lines().count();
+    Ok(json!({
+        "content": lines.join("\n"),
+        "totalLines": if cut { Value::Null } else { json!(total_lines) },
+        "truncated": cut || total_lines > max_lines
+    }))
 }
 
 #[tauri::command]
@@ -712,14 +984,20 @@ pub async fn read_file_content(file_path: String) -> Result<Value, String> {
     if validate_path(&file_path).is_err() {
         tracing::warn!(path = %file_path, "Lese-Zugriff auf Systempfad");
     }
-    let content = tokio::fs::read_to_string(&file_path).await.map_err(|e| e.to_string())?;
+    let content = tokio::fs::read_to_string(crate::ospath::decode(&file_path)).await.map_err(|e| e.to_string())?;
     Ok(json!({ "content": content }))
 }
 
 #[tauri::command]
 pub async fn write_file_content(file_path: String, content: String) -> Result<Value, String> {
     validate_path(&file_path)?;
-    tokio::fs::write(&file_path, &content).await.map_err(|e| e.to_string())?;
+    let path = crate::ospath::decode(&file_path);
+    tokio::task::spawn_blocking(move || {
+        // Old and new content are kept so the edit can be undone and redone
+        let state = crate::undo::save_content_backups(&path, content.as_bytes())?;
+        let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        crate::undo::perform("write_file_content", &format!("\"{}\" bearbeitet", name), state)
+    }).await.map_err(|e| format!("Schreib-Task fehlgeschlagen: {}", e))??;
     Ok(json!({ "success": true }))
 }
 
@@ -728,7 +1006,7 @@ pub async fn read_file_binary(file_path: String) -> Result<Value, String> {
     if validate_path(&file_path).is_err() {
         tracing::warn!(path = %file_path, "Binär-Lese-Zugriff auf Systempfad");
     }
-    let bytes = tokio::fs::read(&file_path).await.map_err(|e| e.to_string())?;
+    let bytes = tokio::fs::read(crate::ospath::decode(&file_path)).await.map_err(|e| e.to_string())?;
     use base64::Engine;
     let b64 = base64::engine::general_purpose::STANDARD.encode(&bytes);
     Ok(json!({ "data": b64, "size": bytes.len() }))
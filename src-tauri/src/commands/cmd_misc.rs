@@ -285,9 +285,13 @@ fn preferences_defaults() -> Value {
         "sessionRestore": true,
         "sessionSaveOnClose": true,
         "sessionSaveAfterScan": true,
+        "historyDiskBudgetMb": 200,
+        "historyRetention": { "keepAllDays": 7, "weeklyDays": 90, "monthlyMonths": 24 },
         "energyMode": "auto",
         "batteryThreshold": 20,
         "disableBackgroundOnBattery": true,
+        "scanSchedules": [],
+        "scheduleQuietHours": { "enabled": false, "start": "22:00", "end": "07:00" },
         "minimizeToTray": true,
         "startMinimized": false,
         "showScanNotification": true,
@@ -438,8 +442,54 @@ pub async fn undo_action(id: String) -> Result<Value, String> {
         return Err("Diese Aktion wurde bereits rückgängig gemacht".to_string());
     }
 
+    // Dateiaktionen kehrt das Undo-Modul selbst um (mit Redo)
+    if crate::undo::is_file_action(&entry.action_type) {
+        return tokio::task::spawn_blocking(move || crate::undo::undo_file_action(&id))
+            .await.map_err(|e| format!("Rückgängig-Task fehlgeschlagen: {}", e))?;
+    }
+
     // Rückgängig-Logik je nach Aktionstyp
     match entry.action_type.as_str() {
+        #[cfg(target_os = "linux")]
+        "delete_trash" if entry.before_state.get("items").is_some() => {
+            // Native Papierkorb-Einträge: zurück an den Ursprungsort (überschreibt nie)
+            let items: Vec<Value> = entry.before_state["items"].as_array().cloned().unwrap_or_default();
+            let (restored, remaining, errors) = tokio::task::spawn_blocking(move || {
+                let mut restored = 0usize;
+                let mut remaining: Vec<Value> = Vec::new();
+                let mut errors: Vec<String> = Vec::new();
+                for item in items {
+                    let p = match item.get("trashPath").and_then(|v| v.as_str()) {
+                        Some(p) => p,
+                        None => continue,
+                    };
+                    match crate::trash::restore(&crate::ospath::decode(p)) {
+                        Ok(_) => restored += 1,
+                        Err(e) => {
+                            errors.push(e);
+                            remaining.push(item);
+                        }
+                    }
+                }
+                (restored, remaining, errors)
+            }).await.map_err(|e| format!("Papierkorb-Task fehlgeschlagen: {}", e))?;
+            if restored == 0 && !errors.is_empty() {
+                return Err(errors.join("; "));
+            }
+            if remaining.is_empty() {
+                crate::undo::mark_undone(&id)?;
+            } else {
+                // Nicht wiederhergestellte Elemente bleiben im Eintrag — ein weiteres Undo versucht sie erneut
+                let mut state = entry.before_state.clone();
+                state["items"] = json!(remaining);
+                crate::undo::update_before_state(&id, state)?;
+            }
+            Ok(json!({
+                "success": errors.is_empty(),
+                "message": format!("{} Element(e) aus dem Papierkorb wiederhergestellt.", restored),
+                "errors": errors
+            }))
+        }
         "delete_trash" => {
             // Papierkorb-Elemente können nicht programmatisch wiederhergestellt werden.
             // Wir öffnen den Papierkorb, damit der Nutzer manuell wiederherstellen kann.
@@ -447,24 +497,6 @@ pub async fn undo_action(id: String) -> Result<Value, String> {
             crate::undo::mark_undone(&id)?;
             Ok(json!({ "success": true, "message": "Papierkorb wurde geöffnet. Bitte stelle die Dateien manuell wieder her." }))
         }
-        "file_move" => {
-            // Dateien zurück an den Ursprungsort verschieben
-            if let Some(moves) = entry.before_state.get("moves").and_then(|v| v.as_array()) {
-                for m in moves {
-                    let from = m.get("dest").and_then(|v| v.as_str()).unwrap_or("");
-                    let to_dir = m.get("source_dir").and_then(|v| v.as_str()).unwrap_or("");
-                    if !from.is_empty() && !to_dir.is_empty() {
-                        let name = std::path::Path::new(from).file_name().unwrap_or_default();
-                        let to = std::path::Path::new(to_dir).join(name);
-                        let _ = tokio::fs::rename(from, &to).await;
-                    }
-                }
-                crate::undo::mark_undone(&id)?;
-                Ok(json!({ "success": true, "message": "Dateien wurden zurück verschoben." }))
-            } else {
-                Err("Keine Verschiebungsdaten vorhanden".to_string())
-            }
-        }
         "toggle_autostart" => {
             // Autostart-Eintrag zurücksetzen
             let prev_enabled = entry.before_state.get("was_enabled").and_then(|v| v.as_bool()).unwrap_or(true);
@@ -491,6 +523,18 @@ pub async fn undo_action(id: String) -> Result<Value, String> {
     }
 }
 
+#[tauri::command]
+pub async fn redo_action(id: Option<String>) -> Result<Value, String> {
+    tokio::task::spawn_blocking(move || crate::undo::redo(id.as_deref()))
+        .await.map_err(|e| format!("Wiederholen-Task fehlgeschlagen: {}", e))?
+}
+
+#[tauri::command]
+pub async fn group_undo_entries(ids: Vec<String>, description: String) -> Result<Value, String> {
+    let group = crate::undo::group_entries(&ids, &description)?;
+    Ok(json!(group))
+}
+
 #[tauri::command]
 pub async fn clear_undo_log() -> Result<Value, String> {
     crate::undo::clear_log()?;
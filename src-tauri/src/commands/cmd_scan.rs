@@ -13,6 +13,122 @@ fn scan_cancelled() -> &'static AtomicBool {
     FLAG.get_or_init(|| AtomicBool::new(false))
 }
 
+/// Set while a user-started scan is walking — background scans yield to it
+fn scan_active() -> &'static AtomicBool {
+    static FLAG: OnceLock<AtomicBool> = OnceLock::new();
+    FLAG.get_or_init(|| AtomicBool::new(false))
+}
+
+pub(crate) fn manual_scan_running() -> bool {
+    scan_active().load(Ordering::Relaxed)
+}
+
+/// Live counters passed to the progress callback of `walk_tree`
+pub(crate) struct WalkProgress<'a> {
+    pub current_path: &'a str,
+    pub dirs_scanned: u64,
+    pub files_found: u64,
+    pub total_size: u64,
+    pub errors_count: u64,
+}
+
+pub(crate) struct WalkResult {
+    pub files: Vec<crate::scan::FileEntry>,
+    pub dirs_scanned: u64,
+    pub total_size: u64,
+    pub errors_count: u64,
+}
+
+/// Traversal state between directories — everything needed to continue a walk later
+pub(crate) struct WalkState {
+    /// Directories not yet read (stack, depth-first)
+    pub pending: Vec<String>,
+    pub files: Vec<crate::scan::FileEntry>,
+    pub dirs_scanned: u64,
+    pub total_size: u64,
+    pub errors_count: u64,
+}
+
+impl WalkState {
+    pub fn new(root: &str) -> Self {
+        Self { pending: vec![root.to_string()], files: Vec::new(), dirs_scanned: 0, total_size: 0, errors_count: 0 }
+    }
+}
+
+/// Walk `path` into a flat file list. Returns None if `cancelled` reported true.
+pub(crate) fn walk_tree(path: &str, cancelled: &dyn Fn() -> bool, on_progress: &mut dyn FnMut(&WalkProgress)) -> Option<WalkResult> {
+    walk_tree_resumable(WalkState::new(path), cancelled, on_progress, &mut |_| {}).ok()
+}
+
+/// Directory-by-directory walk. `on_dir_done` sees a consistent state after every
+/// directory (for checkpoints); on cancellation the state is handed back to the caller.
+pub(crate) fn walk_tree_resumable(
+    mut state: WalkState,
+    cancelled: &dyn Fn() -> bool,
+    on_progress: &mut dyn FnMut(&WalkProgress),
+    on_dir_done: &mut dyn FnMut(&WalkState),
+) -> Result<WalkResult, WalkState> {
+    while let Some(dir) = state.pending.pop() {
+        // Check cancellation flag
+        if cancelled() {
+            state.pending.push(dir);
+            tracing::info!(files = state.files.len(), dirs = state.dirs_scanned, pending = state.pending.len(), "Scan abgebrochen");
+            return Err(state);
+        }
+
+        let entries = match std::fs::read_dir(crate::ospath::decode(&dir)) {
+            Ok(entries) => entries,
+            Err(_) => {
+                state.errors_count += 1;
+                continue;
+            }
+        };
+        state.dirs_scanned += 1;
+        let mut subdirs: Vec<String> = Vec::new();
+
+        for entry in entries {
+            let e = match entry {
+                Ok(e) => e,
+                Err(_) => {
+                    state.errors_count += 1;
+                    continue;
+                }
+            };
+            // Symlinks are not followed (file_type does not traverse them)
+            match e.file_type() {
+                Ok(ft) if ft.is_dir() => subdirs.push(crate::ospath::encode(e.path().as_os_str())),
+                Ok(_) => match e.metadata() {
+                    Ok(meta) => {
+                        let file = crate::scan::file_entry(&e.path(), &meta);
+                        state.total_size += file.size;
+                        state.files.push(file);
+                    }
+                    Err(_) => state.errors_count += 1,
+                },
+                Err(_) => state.errors_count += 1,
+            }
+        }
+
+        // Reverse so the stack pops subdirectories in directory order
+        state.pending.extend(subdirs.into_iter().rev());
+        on_progress(&WalkProgress {
+            current_path: &dir,
+            dirs_scanned: state.dirs_scanned,
+            files_found: state.files.len() as u64,
+            total_size: state.total_size,
+            errors_count: state.errors_count,
+        });
+        on_dir_done(&state);
+    }
+
+    Ok(WalkResult {
+        files: state.files,
+        dirs_scanned: state.dirs_scanned,
+        total_size: state.total_size,
+        errors_count: state.errors_count,
+    })
+}
+
 // === Drive & Scan ===
 
 #[tauri::command]
@@ -30,8 +146,10 @@ pub async fn cancel_scan() -> Result<Value, String> {
 }
 
 #[tauri::command]
-pub async fn start_scan(app: tauri::AppHandle, path: String) -> Result<Value, String> {
-    tracing::info!(path = %path, "Scan gestartet");
+pub async fn start_scan(app: tauri::AppHandle, path: String, options: Option<Value>) -> Result<Value, String> {
+    // Optional: read zip/tar/tar.zst headers and show archives as virtual folders
+    let include_archives = options.as_ref().and_then(|o| o.get("archives")).and_then(|v| v.as_bool()).unwrap_or(false);
+    tracing::info!(path = %path, archives = include_archives, "Scan gestartet");
 
     // Validate path exists before starting scan
     if !Path::new(&path).exists() {
@@ -43,155 +161,239 @@ pub async fn start_scan(app: tauri::AppHandle, path: String) -> Result<Value, St
     }
 
     let scan_id = format!("scan_{}", std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis());
-    let sid = scan_id.clone();
-    let scan_id_ret = scan_id.clone();
 
     // Reset cancellation flag before starting
     scan_cancelled().store(false, Ordering::SeqCst);
 
-    // Use spawn_blocking for walkdir (blocking I/O)
-    tokio::task::spawn_blocking(move || {
-        tracing::debug!(scan_id = %sid, "Scan-Thread gestartet");
-        let start = std::time::Instant::now();
-        let mut files: Vec<crate::scan::FileEntry> = Vec::new();
-        let mut dirs_scanned: u64 = 0;
-        let mut files_found: u64 = 0;
-        let mut total_size: u64 = 0;
-        let mut errors_count: u64 = 0;
-        let mut last_progress = std::time::Instant::now();
-        let mut current_path;
-        let mut was_cancelled = false;
-
-        for entry in walkdir::WalkDir::new(&path)
-            .follow_links(false)
-            .into_iter()
-        {
-            // Check cancellation flag
-            if scan_cancelled().load(Ordering::Relaxed) {
-                was_cancelled = true;
-                tracing::info!(scan_id = %sid, files = files_found, dirs = dirs_scanned, "Scan abgebrochen");
-                break;
-            }
+    // Use spawn_blocking for the walk (blocking I/O)
+    let sid = scan_id.clone();
+    let state = WalkState::new(&path);
+    tokio::task::spawn_blocking(move || run_scan(app, sid, path, state, 0.0, include_archives));
 
-            match entry {
-                Ok(e) => {
-                    current_path = e.path().to_string_lossy().to_string();
-                    if e.file_type().is_dir() {
-                        dirs_scanned += 1;
-                    } else {
-                        let size = e.metadata().map(|m| m.len()).unwrap_or(0);
-                        total_size += size;
-                        files_found += 1;
-
-                        let name = e.file_name().to_string_lossy().to_string();
-                        let ext = std::path::Path::new(&name)
-                            .extension()
-                            .map(|x| format!(".{}", x.to_string_lossy().to_lowercase()))
-                            .unwrap_or_default();
-                        let modified_ms = e.metadata()
-                            .ok()
-                            .and_then(|m| m.modified().ok())
-                            .map(|t: std::time::SystemTime| t.duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_millis() as i64)
-                            .unwrap_or(0);
-
-                        files.push(crate::scan::FileEntry {
-                            path: current_path.clone(),
-                            name,
-                            size,
-                            modified_ms,
-                            extension: ext,
-                        });
-                    }
+    tracing::debug!(scan_id = %scan_id, "Scan-ID zurückgegeben");
+    Ok(json!({ "scan_id": scan_id }))
+}
 
-                    // Emit progress every 300ms
-                    if last_progress.elapsed() > std::time::Duration::from_millis(300) {
-                        let _ = app.emit("scan-progress", json!({
-                            "scan_id": &sid,
-                            "status": "scanning",
-                            "current_path": &current_path,
-                            "dirs_scanned": dirs_scanned,
-                            "files_found": files_found,
-                            "total_size": total_size,
-                            "errors_count": errors_count,
-                            "elapsed_seconds": (start.elapsed().as_secs_f64() * 10.0).round() / 10.0
-                        }));
-                        last_progress = std::time::Instant::now();
-                    }
-                }
-                Err(_) => {
-                    errors_count += 1;
-                }
+/// Continue a cancelled or interrupted scan from its last checkpoint
+#[tauri::command]
+pub async fn resume_scan(app: tauri::AppHandle, scan_id: String) -> Result<Value, String> {
+    if manual_scan_running() {
+        return Err("Es läuft bereits ein Scan".to_string());
+    }
+    let data_dir = get_data_dir();
+    let sid = scan_id.clone();
+    let (meta, state) = tokio::task::spawn_blocking(move || crate::scan_checkpoint::load(&data_dir, &sid))
+        .await
+        .map_err(|e| e.to_string())??;
+    if !Path::new(&meta.root_path).exists() {
+        return Err(format!("Pfad existiert nicht: {}", meta.root_path));
+    }
+    tracing::info!(
+        scan_id = %scan_id, path = %meta.root_path,
+        files = meta.files_found, pending = meta.pending_dirs,
+        "Scan wird vom Checkpoint fortgesetzt"
+    );
+
+    scan_cancelled().store(false, Ordering::SeqCst);
+    let sid = scan_id.clone();
+    tokio::task::spawn_blocking(move || run_scan(app, sid, meta.root_path, state, meta.elapsed_seconds, meta.include_archives));
+
+    Ok(json!({ "scan_id": scan_id, "resumed": true }))
+}
+
+#[tauri::command]
+pub async fn get_scan_checkpoints() -> Result<Value, String> {
+    Ok(json!(crate::scan_checkpoint::list(&get_data_dir())))
+}
+
+#[tauri::command]
+pub async fn discard_scan_checkpoint(scan_id: String) -> Result<Value, String> {
+    Ok(json!({ "discarded": crate::scan_checkpoint::remove(&get_data_dir(), &scan_id) }))
+}
+
+/// Scan worker shared by start_scan and resume_scan. `elapsed_before` is the time
+/// already spent in earlier (checkpointed) runs of the same scan.
+fn run_scan(app: tauri::AppHandle, sid: String, path: String, state: WalkState, elapsed_before: f64, include_archives: bool) {
+    tracing::debug!(scan_id = %sid, "Scan-Thread gestartet");
+    scan_active().store(true, Ordering::SeqCst);
+    let data_dir = get_data_dir();
+    let start = std::time::Instant::now();
+    let mut last_progress = std::time::Instant::now();
+    let mut last_checkpoint = std::time::Instant::now();
+    let elapsed_total = |start: &std::time::Instant| elapsed_before + start.elapsed().as_secs_f64();
+
+    let walked = walk_tree_resumable(state, &|| scan_cancelled().load(Ordering::Relaxed), &mut |p| {
+        // Emit progress every 300ms
+        if last_progress.elapsed() > std::time::Duration::from_millis(300) {
+            let _ = app.emit("scan-progress", json!({
+                "scan_id": &sid,
+                "status": "scanning",
+                "current_path": p.current_path,
+                "dirs_scanned": p.dirs_scanned,
+                "files_found": p.files_found,
+                "total_size": p.total_size,
+                "errors_count": p.errors_count,
+                "elapsed_seconds": (elapsed_total(&start) * 10.0).round() / 10.0
+            }));
+            last_progress = std::time::Instant::now();
+        }
+    }, &mut |state| {
+        // Periodic checkpoint — a crash only loses the last interval
+        if last_checkpoint.elapsed() >= crate::scan_checkpoint::INTERVAL {
+            if let Err(e) = crate::scan_checkpoint::save(&data_dir, &sid, &path, state, elapsed_total(&start), include_archives) {
+                tracing::warn!(scan_id = %sid, error = %e, "Scan-Checkpoint konnte nicht geschrieben werden");
             }
+            last_checkpoint = std::time::Instant::now();
         }
-
-        if was_cancelled {
+    });
+    scan_active().store(false, Ordering::SeqCst);
+
+    let walked = match walked {
+        Ok(w) => w,
+        Err(state) => {
+            let resumable = match crate::scan_checkpoint::save(&data_dir, &sid, &path, &state, elapsed_total(&start), include_archives) {
+                Ok(()) => true,
+                Err(e) => {
+                    tracing::warn!(scan_id = %sid, error = %e, "Scan-Checkpoint konnte nicht geschrieben werden");
+                    false
+                }
+            };
             let _ = app.emit("scan-error", json!({
                 "error": "Scan abgebrochen",
                 "scan_id": &sid,
-                "cancelled": true
+                "cancelled": true,
+                "resumable": resumable
             }));
             return;
         }
+    };
+    let WalkResult { files, dirs_scanned, total_size, errors_count } = walked;
+    let files_found = files.len() as u64;
+
+    // Archive mode: read headers of all supported archives (cancel skips the rest)
+    let archives = if include_archives {
+        crate::scan_archive::scan_archives(&files, &|| scan_cancelled().load(Ordering::Relaxed), &mut |done, total, current| {
+            if last_progress.elapsed() > std::time::Duration::from_millis(300) {
+                let _ = app.emit("scan-progress", json!({
+                    "scan_id": &sid,
+                    "status": "archives",
+                    "current_path": current,
+                    "archives_done": done,
+                    "archives_total": total,
+                    "dirs_scanned": dirs_scanned,
+                    "files_found": files_found,
+                    "total_size": total_size,
+                    "errors_count": errors_count,
+                    "elapsed_seconds": (elapsed_total(&start) * 10.0).round() / 10.0
+                }));
+                last_progress = std::time::Instant::now();
+            }
+        })
+    } else {
+        Vec::new()
+    };
+
+    let elapsed = elapsed_total(&start);
+    tracing::info!(files = files_found, dirs = dirs_scanned, errors = errors_count, elapsed_s = format!("{:.1}", elapsed), "Scan abgeschlossen");
+
+    // Store scan data for queries
+    crate::scan::save(crate::scan::ScanData {
+        scan_id: sid.clone(),
+        root_path: path.clone(),
+        files,
+        dirs_scanned,
+        total_size,
+        elapsed_seconds: elapsed,
+        archives,
+    });
+    tracing::debug!(scan_id = %sid, "Scan-Daten im Store gespeichert");
 
-        let elapsed = start.elapsed().as_secs_f64();
-        tracing::info!(files = files_found, dirs = dirs_scanned, errors = errors_count, elapsed_s = format!("{:.1}", elapsed), "Scan abgeschlossen");
-
-        // Store scan data for queries
-        crate::scan::save(crate::scan::ScanData {
-            scan_id: sid.clone(),
-            root_path: path.clone(),
-            files,
-            dirs_scanned,
-            total_size,
-            elapsed_seconds: elapsed,
-        });
-        tracing::debug!(scan_id = %sid, "Scan-Daten im Store gespeichert");
+    // Finished scan supersedes any checkpoint of the same root
+    crate::scan_checkpoint::remove_for_root(&data_dir, &path);
 
-        // Persist to disk for session restore
-        let data_dir = get_data_dir();
-        match crate::scan::save_to_disk(&data_dir) {
-            Ok(_) => tracing::debug!(scan_id = %sid, "Scan-Daten auf Disk persistiert"),
-            Err(e) => tracing::warn!(scan_id = %sid, error = %e, "Scan-Daten konnten nicht persistiert werden"),
-        }
+    // Persist to disk for session restore
+    match crate::scan::save_to_disk(&data_dir) {
+        Ok(_) => tracing::debug!(scan_id = %sid, "Scan-Daten auf Disk persistiert"),
+        Err(e) => tracing::warn!(scan_id = %sid, error = %e, "Scan-Daten konnten nicht persistiert werden"),
+    }
 
-        // Auto-save scan snapshot for history (Issue #7)
-        if let Some(snapshot) = crate::scan_history::create_snapshot(&sid) {
-            match crate::scan_history::save_snapshot(&data_dir, snapshot) {
-                Ok(_) => tracing::debug!(scan_id = %sid, "Scan-Snapshot für Verlauf gespeichert"),
-                Err(e) => tracing::debug!(scan_id = %sid, error = %e, "Scan-Snapshot nicht gespeichert (evtl. zu früh nach letztem)"),
-            }
+    // Auto-save scan snapshot for history (Issue #7)
+    if let Some(snapshot) = crate::scan_history::create_snapshot(&sid) {
+        match crate::scan_history::save_snapshot(&data_dir, snapshot) {
+            Ok(_) => tracing::debug!(scan_id = %sid, "Scan-Snapshot für Verlauf gespeichert"),
+            Err(e) => tracing::debug!(scan_id = %sid, error = %e, "Scan-Snapshot nicht gespeichert (evtl. zu früh nach letztem)"),
         }
+    }
 
-        // Save scan metadata in session.json for frontend restore
-        let mut session = read_json_file("session.json");
-        if let Some(obj) = session.as_object_mut() {
-            obj.insert("sessions".to_string(), json!([{
-                "scan_id": &sid,
-                "current_path": &path,
-                "dirs_scanned": dirs_scanned,
-                "files_found": files_found,
-                "total_size": total_size,
-                "elapsed_seconds": (elapsed * 10.0).round() / 10.0
-            }]));
-        }
-        let _ = write_json_file("session.json", &session);
-
-        // Emit completion
-        let emit_result = app.emit("scan-complete", json!({
+    // Save scan metadata in session.json for frontend restore
+    let mut session = read_json_file("session.json");
+    if let Some(obj) = session.as_object_mut() {
+        obj.insert("sessions".to_string(), json!([{
             "scan_id": &sid,
-            "status": "complete",
             "current_path": &path,
             "dirs_scanned": dirs_scanned,
             "files_found": files_found,
             "total_size": total_size,
-            "errors_count": errors_count,
             "elapsed_seconds": (elapsed * 10.0).round() / 10.0
-        }));
-        tracing::debug!(scan_id = %sid, emit_ok = emit_result.is_ok(), "scan-complete Event gesendet");
-    });
+        }]));
+    }
+    let _ = write_json_file("session.json", &session);
+
+    // Emit completion
+    let emit_result = app.emit("scan-complete", json!({
+        "scan_id": &sid,
+        "status": "complete",
+        "current_path": &path,
+        "dirs_scanned": dirs_scanned,
+        "files_found": files_found,
+        "total_size": total_size,
+        "errors_count": errors_count,
+        "elapsed_seconds": (elapsed * 10.0).round() / 10.0
+    }));
+    tracing::debug!(scan_id = %sid, emit_ok = emit_result.is_ok(), "scan-complete Event gesendet");
+}
+
+// === Estimate Scan ===
+
+#[tauri::command]
+pub async fn start_estimate_scan(path: String, options: Option<Value>) -> Result<Value, String> {
+    tracing::info!(path = %path, "Schätz-Scan gestartet");
+    let mut opts = crate::scan_estimate::EstimateOptions::default();
+    if let Some(o) = options.as_ref() {
+        if let Some(d) = o.get("fullDepth").and_then(|v| v.as_u64()) { opts.full_depth = d.min(4) as usize; }
+        if let Some(ms) = o.get("maxMs").and_then(|v| v.as_u64()) { opts.max_ms = ms; }
+        if let Some(n) = o.get("maxProbes").and_then(|v| v.as_u64()) { opts.max_probes = (n as usize).max(2); }
+    }
+    let (estimate_id, tree) = tokio::task::spawn_blocking(move || crate::scan_estimate::estimate(&path, &opts))
+        .await
+        .map_err(|e| e.to_string())??;
+    Ok(json!({ "estimateId": estimate_id, "tree": tree }))
+}
+
+#[tauri::command]
+pub async fn get_estimate_tree(estimate_id: String, path: Option<String>, depth: Option<u32>) -> Result<Value, String> {
+    crate::scan_estimate::get_tree(&estimate_id, path.as_deref(), depth.unwrap_or(1))
+}
+
+/// Separate from the scan flag — cancelling a scan must not abort a refine and vice versa
+fn refine_cancelled() -> &'static AtomicBool {
+    static FLAG: OnceLock<AtomicBool> = OnceLock::new();
+    FLAG.get_or_init(|| AtomicBool::new(false))
+}
+
+/// Full scan of one branch of an estimate; cancellable via cancel_refine_estimate
+#[tauri::command]
+pub async fn refine_estimate(estimate_id: String, path: String) -> Result<Value, String> {
+    refine_cancelled().store(false, Ordering::SeqCst);
+    tokio::task::spawn_blocking(move || {
+        crate::scan_estimate::refine(&estimate_id, &path, &|| refine_cancelled().load(Ordering::Relaxed))
+    }).await.map_err(|e| e.to_string())?
+}
 
-    tracing::debug!(scan_id = %scan_id_ret, "Scan-ID zurückgegeben");
-    Ok(json!({ "scan_id": scan_id_ret }))
+#[tauri::command]
+pub async fn cancel_refine_estimate() -> Result<Value, String> {
+    refine_cancelled().store(true, Ordering::SeqCst);
+    Ok(json!({ "cancelled": true }))
 }
 
 // === Tree Data ===
@@ -209,27 +411,43 @@ pub async fn get_treemap_data(scan_id: String, path: String, depth: Option<u32>)
 // === File Data (from scan state) ===
 
 #[tauri::command]
-pub async fn get_top_files(scan_id: String, limit: Option<u32>) -> Result<Value, String> {
+pub async fn get_top_files(scan_id: String, limit: Option<u32>, page: Option<Value>) -> Result<Value, String> {
+    if let Some(p) = page {
+        let req = crate::paging::PageRequest::parse(&p, crate::paging::SortKey::Size)?;
+        return crate::scan::top_files_page(&scan_id, &req);
+    }
     Ok(crate::scan::top_files(&scan_id, limit.unwrap_or(100) as usize))
 }
 
 #[tauri::command]
-pub async fn get_file_types(scan_id: String) -> Result<Value, String> {
-    Ok(crate::scan::file_types(&scan_id))
+pub async fn get_file_types(scan_id: String, include_archives: Option<bool>) -> Result<Value, String> {
+    Ok(crate::scan::file_types(&scan_id, include_archives.unwrap_or(false)))
 }
 
 #[tauri::command]
-pub async fn search(scan_id: String, query: String, min_size: Option<u64>) -> Result<Value, String> {
+pub async fn search(scan_id: String, query: String, min_size: Option<u64>, page: Option<Value>) -> Result<Value, String> {
+    if let Some(p) = page {
+        let req = crate::paging::PageRequest::parse(&p, crate::paging::SortKey::Name)?;
+        return crate::scan::search_files_page(&scan_id, &query, min_size.unwrap_or(0), &req);
+    }
     Ok(crate::scan::search_files(&scan_id, &query, min_size.unwrap_or(0)))
 }
 
 #[tauri::command]
-pub async fn get_files_by_extension(scan_id: String, ext: String, limit: Option<u32>) -> Result<Value, String> {
+pub async fn get_files_by_extension(scan_id: String, ext: String, limit: Option<u32>, page: Option<Value>) -> Result<Value, String> {
+    if let Some(p) = page {
+        let req = crate::paging::PageRequest::parse(&p, crate::paging::SortKey::Size)?;
+        return crate::scan::files_by_extension_page(&scan_id, &ext, &req);
+    }
     Ok(crate::scan::files_by_extension(&scan_id, &ext, limit.unwrap_or(500) as usize))
 }
 
 #[tauri::command]
-pub async fn get_files_by_category(scan_id: String, category: String, limit: Option<u32>) -> Result<Value, String> {
+pub async fn get_files_by_category(scan_id: String, category: String, limit: Option<u32>, page: Option<Value>) -> Result<Value, String> {
+    if let Some(p) = page {
+        let req = crate::paging::PageRequest::parse(&p, crate::paging::SortKey::Size)?;
+        return crate::scan::files_by_category_page(&scan_id, &category, &req);
+    }
     Ok(crate::scan::files_by_category(&scan_id, &category, limit.unwrap_or(500) as usize))
 }
 
@@ -341,7 +559,11 @@ pub async fn show_save_dialog(app: tauri::AppHandle, options: Option<Value>) ->
 // === Old Files ===
 
 #[tauri::command]
-pub async fn get_old_files(scan_id: String, threshold_days: Option<u32>, min_size: Option<u64>) -> Result<Value, String> {
+pub async fn get_old_files(scan_id: String, threshold_days: Option<u32>, min_size: Option<u64>, page: Option<Value>) -> Result<Value, String> {
+    if let Some(p) = page {
+        let req = crate::paging::PageRequest::parse(&p, crate::paging::SortKey::Size)?;
+        return crate::scan::old_files_page(&scan_id, threshold_days.unwrap_or(180), min_size.unwrap_or(1_048_576), &req);
+    }
     Ok(crate::scan::old_files(&scan_id, threshold_days.unwrap_or(180), min_size.unwrap_or(1_048_576)))
 }
 
@@ -368,17 +590,17 @@ pub async fn start_duplicate_scan(app: tauri::AppHandle, scan_id: String, option
         use std::collections::HashMap;
 
         // Phase 1: Group files by size from scan data
-        let size_groups: Option<HashMap<u64, Vec<(String, String)>>> = crate::scan::with_scan(&sid, |data| {
+        let size_groups: Option<(String, HashMap<u64, Vec<(String, String)>>)> = crate::scan::with_scan(&sid, |data| {
             let mut groups: HashMap<u64, Vec<(String, String)>> = HashMap::new();
             for f in &data.files {
                 if f.size >= min_size {
                     groups.entry(f.size).or_default().push((f.path.clone(), f.name.clone()));
                 }
             }
-            groups
+            (data.root_path.clone(), groups)
         });
 
-        let size_groups = match size_groups {
+        let (root_path, size_groups) = match size_groups {
             Some(g) => g,
             None => {
                 let _ = app2.emit("duplicate-error", json!({ "scanId": sid, "error": "Scan-Daten nicht gefunden" }));
@@ -400,19 +622,20 @@ pub async fn start_duplicate_scan(app: tauri::AppHandle, scan_id: String, option
         }));
 
         // Phase 2: Hash files within same-size groups to find true duplicates
-        let mut result_groups = Vec::new();
+        let mut result_groups: Vec<crate::duplicates::DuplicateGroup> = Vec::new();
         let mut processed = 0u64;
+        let mut cancelled = false;
 
         for (size, files) in &candidate_groups {
             if DUPLICATE_CANCEL.load(std::sync::atomic::Ordering::Relaxed) {
-                let _ = app2.emit("duplicate-complete", json!({ "scanId": sid, "groups": result_groups, "cancelled": true }));
-                return;
+                cancelled = true;
+                break;
             }
 
             let mut hash_groups: HashMap<String, Vec<&(String, String)>> = HashMap::new();
             for file_info in files {
                 // Compute partial hash (first 8KB + last 8KB) for speed
-                let hash = match compute_partial_hash(&file_info.0) {
+                let hash = match crate::duplicates::compute_partial_hash(&file_info.0) {
                     Some(h) => h,
                     None => { processed += 1; continue; }
                 };
@@ -430,66 +653,181 @@ pub async fn start_duplicate_scan(app: tauri::AppHandle, scan_id: String, option
 
             for (hash, dup_files) in hash_groups {
                 if dup_files.len() >= 2 {
-                    let files_json: Vec<Value> = dup_files.iter().map(|(path, name)| {
-                        json!({ "path": path, "name": name, "size": size })
-                    }).collect();
-                    result_groups.push(json!({
-                        "hash": hash,
-                        "size": size,
-                        "count": dup_files.len(),
-                        "saveable": size * (dup_files.len() as u64 - 1),
-                        "files": files_json
-                    }));
+                    result_groups.push(crate::duplicates::DuplicateGroup {
+                        hash,
+                        size: *size,
+                        count: dup_files.len(),
+                        saveable: size * (dup_files.len() as u64 - 1),
+                        files: dup_files.iter().map(|(path, name)| crate::duplicates::DuplicateFile {
+                            path: path.clone(),
+                            name: name.clone(),
+                            size: *size,
+                        }).collect(),
+                    });
                 }
             }
         }
 
         // Sort by saveable space descending
-        result_groups.sort_by(|a, b| {
-            b["saveable"].as_u64().unwrap_or(0).cmp(&a["saveable"].as_u64().unwrap_or(0))
-        });
+        crate::duplicates::sort_groups(&mut result_groups, "saveable", true);
+
+        // Persist results with the scan so they survive a reload (also partial results on cancel)
+        let results = crate::duplicates::DuplicateResults {
+            scan_id: sid.clone(),
+            root_path,
+            created_ms: chrono::Utc::now().timestamp_millis(),
+            cancelled,
+            groups: result_groups,
+        };
+        if let Err(e) = crate::duplicates::save_results(&get_data_dir(), &results) {
+            tracing::warn!(scan_id = %sid, error = %e, "Duplikat-Ergebnisse konnten nicht gespeichert werden");
+        }
 
-        let _ = app2.emit("duplicate-complete", json!({
+        let mut payload = json!({
             "scanId": sid,
-            "groups": result_groups
-        }));
+            "groups": results.groups
+        });
+        if cancelled {
+            payload["cancelled"] = json!(true);
+        }
+        let _ = app2.emit("duplicate-complete", payload);
     });
 
     Ok(json!({ "started": true }))
 }
 
-fn compute_partial_hash(path: &str) -> Option<String> {
-    use std::io::Read;
-    let mut file = std::fs::File::open(path).ok()?;
-    let meta = file.metadata().ok()?;
-    let size = meta.len();
+#[tauri::command]
+pub async fn cancel_duplicate_scan(_scan_id: String) -> Result<Value, String> {
+    DUPLICATE_CANCEL.store(true, std::sync::atomic::Ordering::Relaxed);
+    Ok(json!({ "cancelled": true }))
+}
 
-    let mut hasher = std::collections::hash_map::DefaultHasher::new();
-    use std::hash::Hasher;
+/// Separate from DUPLICATE_CANCEL — a cross-root comparison and a duplicate scan can run side by side
+static CROSS_DUPLICATE_CANCEL: std::sync::LazyLock<std::sync::atomic::AtomicBool> =
+    std::sync::LazyLock::new(|| std::sync::atomic::AtomicBool::new(false));
 
-    // Hash first 8KB
-    let mut buf = [0u8; 8192];
-    let n = file.read(&mut buf).ok()?;
-    hasher.write(&buf[..n]);
+#[tauri::command]
+pub async fn cancel_cross_duplicate_scan() -> Result<Value, String> {
+    CROSS_DUPLICATE_CANCEL.store(true, std::sync::atomic::Ordering::Relaxed);
+    Ok(json!({ "cancelled": true }))
+}
 
-    // Hash last 8KB if file is large enough
-    if size > 16384 {
-        use std::io::Seek;
-        file.seek(std::io::SeekFrom::End(-8192)).ok()?;
-        let n = file.read(&mut buf).ok()?;
-        hasher.write(&buf[..n]);
+#[tauri::command]
+pub async fn start_cross_duplicate_scan(app: tauri::AppHandle, scan_id_a: String, scan_id_b: String, options: Option<Value>) -> Result<Value, String> {
+    tracing::debug!(scan_a = %scan_id_a, scan_b = %scan_id_b, "Starte Cross-Root-Vergleich");
+    if scan_id_a == scan_id_b {
+        return Err("Bitte zwei verschiedene Scans auswählen".to_string());
     }
+    CROSS_DUPLICATE_CANCEL.store(false, std::sync::atomic::Ordering::Relaxed);
+
+    let min_size = options.as_ref()
+        .and_then(|o| o.get("minSize"))
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+    // Full hash is slower but required before wiping a backup disk — partial hash only on request
+    let full_hash = options.as_ref()
+        .and_then(|o| o.get("fullHash"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+
+    let collect = |data: &crate::scan::ScanData| -> (String, Vec<crate::duplicates::DuplicateFile>) {
+        let files = data.files.iter()
+            .filter(|f| f.size >= min_size)
+            .map(|f| crate::duplicates::DuplicateFile { path: f.path.clone(), name: f.name.clone(), size: f.size })
+            .collect();
+        (data.root_path.clone(), files)
+    };
+    let (root_a, files_a) = crate::scan::with_scan(&scan_id_a, collect)
+        .ok_or("Scan A nicht im Speicher gefunden")?;
+    let (root_b, files_b) = crate::scan::with_scan(&scan_id_b, collect)
+        .ok_or("Scan B nicht im Speicher gefunden")?;
+
+    tokio::task::spawn_blocking(move || {
+        let progress_app = app.clone();
+        let (sa, sb) = (scan_id_a.clone(), scan_id_b.clone());
+        let mut last_progress = std::time::Instant::now();
+        let outcome = crate::duplicates::compare_file_sets(
+            files_a,
+            files_b,
+            full_hash,
+            |processed, total| {
+                if last_progress.elapsed() > std::time::Duration::from_millis(300) {
+                    let _ = progress_app.emit("cross-duplicate-progress", json!({
+                        "scanIdA": sa, "scanIdB": sb, "phase": "hashing",
+                        "totalCandidates": total, "processed": processed
+                    }));
+                    last_progress = std::time::Instant::now();
+                }
+            },
+            || CROSS_DUPLICATE_CANCEL.load(std::sync::atomic::Ordering::Relaxed),
+        );
 
-    // Include file size in hash to reduce collisions
-    hasher.write_u64(size);
+        let (both, only_a, only_b) = match outcome {
+            Some(r) => r,
+            None => {
+                let _ = app.emit("cross-duplicate-complete", json!({ "scanIdA": scan_id_a, "scanIdB": scan_id_b, "cancelled": true }));
+                return;
+            }
+        };
+
+        let results = crate::duplicates::CrossResults {
+            scan_id_a: scan_id_a.clone(),
+            scan_id_b: scan_id_b.clone(),
+            root_a,
+            root_b,
+            created_ms: chrono::Utc::now().timestamp_millis(),
+            full_hash,
+            both,
+            only_a,
+            only_b,
+        };
+        if let Err(e) = crate::duplicates::save_cross_results(&get_data_dir(), &results) {
+            tracing::warn!(error = %e, "Cross-Root-Ergebnisse konnten nicht gespeichert werden");
+        }
+
+        let _ = app.emit("cross-duplicate-complete", crate::duplicates::cross_summary(&results));
+    });
 
-    Some(format!("{:016x}", hasher.finish()))
+    Ok(json!({ "started": true }))
 }
 
 #[tauri::command]
-pub async fn cancel_duplicate_scan(_scan_id: String) -> Result<Value, String> {
-    DUPLICATE_CANCEL.store(true, std::sync::atomic::Ordering::Relaxed);
-    Ok(json!({ "cancelled": true }))
+pub async fn get_cross_duplicate_results(scan_id_a: String, scan_id_b: String, bucket: Option<String>, offset: Option<u32>, limit: Option<u32>) -> Result<Value, String> {
+    let results = crate::duplicates::load_cross_results(&get_data_dir(), &scan_id_a, &scan_id_b)
+        .ok_or("Kein Vergleich für diese beiden Scans vorhanden")?;
+    crate::duplicates::page_cross_results(
+        &results,
+        bucket.as_deref().unwrap_or("both"),
+        offset.unwrap_or(0) as usize,
+        limit.unwrap_or(100) as usize,
+    )
+}
+
+#[tauri::command]
+pub async fn get_duplicate_results(scan_id: String, offset: Option<u32>, limit: Option<u32>, sort_by: Option<String>, sort_dir: Option<String>) -> Result<Value, String> {
+    let results = crate::duplicates::load_results(&get_data_dir(), &scan_id)
+        .ok_or("Keine gespeicherten Duplikat-Ergebnisse für diesen Scan")?;
+    let descending = sort_dir.as_deref() != Some("asc");
+    Ok(crate::duplicates::page_results(
+        &results,
+        sort_by.as_deref().unwrap_or("saveable"),
+        descending,
+        offset.unwrap_or(0) as usize,
+        limit.unwrap_or(100) as usize,
+    ))
+}
+
+#[tauri::command]
+pub async fn export_duplicate_results(scan_id: String, format: Option<String>) -> Result<Value, String> {
+    let results = crate::duplicates::load_results(&get_data_dir(), &scan_id)
+        .ok_or("Keine gespeicherten Duplikat-Ergebnisse für diesen Scan")?;
+    let format = format.unwrap_or_else(|| "csv".to_string()).to_lowercase();
+    let content = match format.as_str() {
+        "csv" => crate::duplicates::export_csv(&results),
+        "json" => crate::duplicates::export_json(&results)?,
+        _ => return Err(format!("Unbekanntes Export-Format: {}", format)),
+    };
+    Ok(json!({ "success": true, "format": format, "content": content }))
 }
 
 #[tauri::command]
@@ -544,6 +882,8 @@ pub async fn release_scan_bulk_data(scan_id: String) -> Result<Value, String> {
     // Actually clear the scan data from memory
     let mut s = crate::scan::store_mut();
     let removed = s.remove(&scan_id).is_some();
+    drop(s);
+    crate::watcher::stop_for_scan(&scan_id);
     Ok(json!({ "released": removed }))
 }
 
@@ -717,7 +1057,7 @@ pub async fn get_scan_history() -> Result<Value, String> {
 }
 
 #[tauri::command]
-pub async fn compare_scans(older_id: String, newer_id: String) -> Result<Value, String> {
+pub async fn compare_scans(older_id: String, newer_id: String, subtree: Option<String>, max_depth: Option<u32>, limit: Option<u32>) -> Result<Value, String> {
     let data_dir = get_data_dir();
     let history = crate::scan_history::load_history(&data_dir);
 
@@ -726,38 +1066,137 @@ pub async fn compare_scans(older_id: String, newer_id: String) -> Result<Value,
     let newer = history.iter().find(|s| s.id == newer_id)
         .ok_or("Neuerer Snapshot nicht gefunden")?;
 
-    let delta = crate::scan_history::compare_snapshots(older, newer);
+    let mut delta = crate::scan_history::compare_snapshots(older, newer);
+
+    // Tag folders/categories whose growth is far outside their own history
+    let baseline: Vec<&crate::scan_history::ScanSnapshot> = history.iter()
+        .filter(|s| s.hostname == older.hostname && crate::pathkey::same_path(&s.root_path, &older.root_path) && s.timestamp_ms <= older.timestamp_ms)
+        .collect();
+    delta.anomalies = crate::scan_history::detect_anomalies(&baseline, older, newer, &Default::default());
+    for fc in delta.folder_changes.iter_mut() {
+        fc.anomaly = delta.anomalies.iter()
+            .find(|a| a.kind == "folder" && a.key == fc.path)
+            .map(|a| a.reason.clone());
+    }
+
+    // Drill-down below level one — only possible if both directory indices are still on disk
+    if let (Some(old_idx), Some(new_idx)) = (
+        crate::scan_history::load_dir_index(&data_dir, &older.id),
+        crate::scan_history::load_dir_index(&data_dir, &newer.id),
+    ) {
+        let subtree = subtree.unwrap_or_else(|| newer.root_path.clone());
+        delta.dir_changes = Some(crate::scan_history::compare_dir_indices(
+            &old_idx,
+            &new_idx,
+            &subtree,
+            max_depth.unwrap_or(u32::MAX),
+            limit.unwrap_or(500) as usize,
+        ));
+    }
+
     Ok(serde_json::to_value(&delta).unwrap_or(json!({})))
 }
 
 #[tauri::command]
-pub async fn get_storage_trend(root_path: String) -> Result<Value, String> {
+pub async fn diff_scans(older_id: String, newer_id: String, change_type: Option<String>, offset: Option<u32>, limit: Option<u32>) -> Result<Value, String> {
+    let data_dir = get_data_dir();
+    tokio::task::spawn_blocking(move || {
+        crate::scan_diff::diff_scans(
+            &data_dir,
+            &older_id,
+            &newer_id,
+            change_type.as_deref().unwrap_or("all"),
+            offset.unwrap_or(0) as usize,
+            limit.unwrap_or(200) as usize,
+        )
+    }).await.map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn get_storage_trend(root_path: String, method: Option<String>) -> Result<Value, String> {
     let data_dir = get_data_dir();
     let history = crate::scan_history::load_history(&data_dir);
-    Ok(crate::scan_history::compute_trend(&history, &root_path))
+    Ok(crate::scan_history::compute_trend(&history, &root_path, method.as_deref().unwrap_or("ols")))
 }
 
 #[tauri::command]
-pub async fn quick_change_check() -> Result<Value, String> {
+pub async fn get_storage_anomalies(root_path: String, options: Option<Value>) -> Result<Value, String> {
     let data_dir = get_data_dir();
-    Ok(crate::scan_history::quick_change_check(&data_dir))
+    let history = crate::scan_history::load_history(&data_dir);
+    let mut opts = crate::scan_history::AnomalyOptions::default();
+    if let Some(o) = options.as_ref() {
+        if let Some(z) = o.get("zThreshold").and_then(|v| v.as_f64()) { opts.z_threshold = z; }
+        if let Some(b) = o.get("minBytes").and_then(|v| v.as_u64()) { opts.min_bytes = b; }
+        if let Some(b) = o.get("minDoublingBase").and_then(|v| v.as_u64()) { opts.min_doubling_base = b; }
+    }
+    Ok(crate::scan_history::storage_anomalies(&history, &root_path, &opts))
+}
+
+#[tauri::command]
+pub async fn quick_change_check(options: Option<Value>) -> Result<Value, String> {
+    let data_dir = get_data_dir();
+    let mut budget = crate::scan_history::ChangeCheckBudget::default();
+    if let Some(o) = options.as_ref() {
+        if let Some(n) = o.get("maxDirs").and_then(|v| v.as_u64()) { budget.max_dirs = n as usize; }
+        if let Some(ms) = o.get("maxMs").and_then(|v| v.as_u64()) { budget.max_ms = ms; }
+    }
+    tokio::task::spawn_blocking(move || crate::scan_history::quick_change_check(&data_dir, &budget))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_scan_schedule_status() -> Result<Value, String> {
+    tokio::task::spawn_blocking(crate::scheduler::status)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn run_scheduled_scan_now(app: tauri::AppHandle, schedule_id: String) -> Result<Value, String> {
+    crate::scheduler::run_now(app, &schedule_id)
+}
+
+#[tauri::command]
+pub async fn start_scan_watch(app: tauri::AppHandle, scan_id: String) -> Result<Value, String> {
+    crate::watcher::start(app, &scan_id)
+}
+
+#[tauri::command]
+pub async fn stop_scan_watch() -> Result<Value, String> {
+    Ok(json!({ "stopped": crate::watcher::stop() }))
+}
+
+#[tauri::command]
+pub async fn get_scan_watch_status() -> Result<Value, String> {
+    Ok(crate::watcher::status())
 }
 
 #[tauri::command]
 pub async fn delete_scan_snapshot(snapshot_id: String) -> Result<Value, String> {
     let data_dir = get_data_dir();
-    let mut history = crate::scan_history::load_history(&data_dir);
-    let before_len = history.len();
-    history.retain(|s| s.id != snapshot_id);
-    if history.len() == before_len {
+    if !crate::scan_history::delete_snapshot(&data_dir, &snapshot_id)? {
         return Err("Snapshot nicht gefunden".to_string());
     }
-    let json = serde_json::to_string_pretty(&history).map_err(|e| e.to_string())?;
-    std::fs::write(data_dir.join("scan-history.json"), json)
-        .map_err(|e| format!("scan-history.json schreiben: {}", e))?;
     Ok(json!({"deleted": true}))
 }
 
+#[tauri::command]
+pub async fn export_scan_history(snapshot_ids: Vec<String>, dest_path: String, include_file_lists: Option<bool>) -> Result<Value, String> {
+    let data_dir = get_data_dir();
+    tokio::task::spawn_blocking(move || {
+        crate::scan_history::export_archive(&data_dir, &snapshot_ids, Path::new(&dest_path), include_file_lists.unwrap_or(false))
+    }).await.map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn import_scan_history(src_path: String) -> Result<Value, String> {
+    let data_dir = get_data_dir();
+    tokio::task::spawn_blocking(move || {
+        crate::scan_history::import_archive(&data_dir, Path::new(&src_path))
+    }).await.map_err(|e| e.to_string())?
+}
+
 #[tauri::command]
 pub async fn clear_scan_history() -> Result<Value, String> {
     let data_dir = get_data_dir();
@@ -765,6 +1204,7 @@ pub async fn clear_scan_history() -> Result<Value, String> {
     if path.exists() {
         std::fs::write(&path, "[]").map_err(|e| e.to_string())?;
     }
+    crate::scan_history::clear_dir_indices(&data_dir);
     Ok(json!({"cleared": true}))
 }
 
@@ -94,8 +94,8 @@ pub(crate) fn validate_ip(ip: &str) -> Result<(), String> {
 /// (z.B. Properties, Preview, Scan).
 pub(crate) fn validate_path(p: &str) -> Result<(), String> {
     // Canonicalize to resolve symlinks/junctions that could bypass the blocklist
-    let canonical = std::fs::canonicalize(p)
-        .unwrap_or_else(|_| std::path::PathBuf::from(p));
+    let canonical = std::fs::canonicalize(crate::ospath::decode(p))
+        .unwrap_or_else(|_| crate::ospath::decode(p));
     let p_lower = canonical.to_string_lossy().to_lowercase().replace('/', "\\");
 
     // Block drive root (e.g. "C:\") — too dangerous for delete/write
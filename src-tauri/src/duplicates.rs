@@ -0,0 +1,352 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// A file that belongs to a duplicate group
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DuplicateFile {
+    pub path: String,
+    pub name: String,
+    pub size: u64,
+}
+
+/// A group of files with identical size + content hash
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DuplicateGroup {
+    pub hash: String,
+    pub size: u64,
+    pub count: usize,
+    pub saveable: u64,
+    pub files: Vec<DuplicateFile>,
+}
+
+/// Persisted result of a duplicate scan, tied to the scan it was computed from
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DuplicateResults {
+    pub scan_id: String,
+    pub root_path: String,
+    pub created_ms: i64,
+    pub cancelled: bool,
+    pub groups: Vec<DuplicateGroup>,
+}
+
+/// Cross-root comparison of two scans (e.g. external backup disk vs. internal disk)
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CrossResults {
+    pub scan_id_a: String,
+    pub scan_id_b: String,
+    pub root_a: String,
+    pub root_b: String,
+    pub created_ms: i64,
+    pub full_hash: bool,
+    /// Content present in both scans (grouped by size + hash)
+    pub both: Vec<CrossGroup>,
+    /// Files whose content exists only in scan A
+    pub only_a: Vec<DuplicateFile>,
+    /// Files whose content exists only in scan B
+    pub only_b: Vec<DuplicateFile>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CrossGroup {
+    pub hash: String,
+    pub size: u64,
+    pub files_a: Vec<DuplicateFile>,
+    pub files_b: Vec<DuplicateFile>,
+}
+
+const RESULTS_DIR: &str = "duplicates";
+
+fn results_path(data_dir: &Path, scan_id: &str) -> PathBuf {
+    // scan_id is generated by us ("scan_<millis>") — strip anything that isn't safe in a filename anyway
+    let safe_id: String = scan_id.chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+        .collect();
+    data_dir.join(RESULTS_DIR).join(format!("{}.json", safe_id))
+}
+
+/// Save duplicate results to disk (one file per scan_id, overwrites previous results of that scan)
+pub fn save_results(data_dir: &Path, results: &DuplicateResults) -> Result<(), String> {
+    let path = results_path(data_dir, &results.scan_id);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Duplikat-Verzeichnis erstellen: {}", e))?;
+    }
+    let json = serde_json::to_string(results).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("Duplikat-Ergebnisse schreiben: {}", e))
+}
+
+/// Load persisted duplicate results for a scan
+pub fn load_results(data_dir: &Path, scan_id: &str) -> Option<DuplicateResults> {
+    let content = std::fs::read_to_string(results_path(data_dir, scan_id)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Sort groups in place. sort_by: "saveable" | "size" | "count" | "path"
+pub fn sort_groups(groups: &mut [DuplicateGroup], sort_by: &str, descending: bool) {
+    match sort_by {
+        "size" => groups.sort_by(|a, b| a.size.cmp(&b.size)),
+        "count" => groups.sort_by(|a, b| a.count.cmp(&b.count)),
+        "path" => groups.sort_by(|a, b| {
+            let pa = a.files.first().map(|f| f.path.to_lowercase()).unwrap_or_default();
+            let pb = b.files.first().map(|f| f.path.to_lowercase()).unwrap_or_default();
+            pa.cmp(&pb)
+        }),
+        _ => groups.sort_by(|a, b| a.saveable.cmp(&b.saveable)),
+    }
+    if descending {
+        groups.reverse();
+    }
+}
+
+/// Paged + sorted view of persisted results — returns {scanId, totalGroups, totalFiles, totalSaveable, offset, groups}
+pub fn page_results(results: &DuplicateResults, sort_by: &str, descending: bool, offset: usize, limit: usize) -> Value {
+    let mut groups = results.groups.clone();
+    sort_groups(&mut groups, sort_by, descending);
+
+    let total_files: usize = groups.iter().map(|g| g.count).sum();
+    let total_saveable: u64 = groups.iter().map(|g| g.saveable).sum();
+    let page: Vec<&DuplicateGroup> = groups.iter().skip(offset).take(limit).collect();
+
+    json!({
+        "scanId": results.scan_id,
+        "rootPath": results.root_path,
+        "createdMs": results.created_ms,
+        "cancelled": results.cancelled,
+        "totalGroups": groups.len(),
+        "totalFiles": total_files,
+        "totalSaveable": total_saveable,
+        "offset": offset,
+        "limit": limit,
+        "hasMore": offset + page.len() < groups.len(),
+        "groups": page
+    })
+}
+
+/// Export as CSV (semicolon-separated, German format — one line per file)
+pub fn export_csv(results: &DuplicateResults) -> String {
+    let mut lines = Vec::with_capacity(results.groups.iter().map(|g| g.files.len()).sum::<usize>() + 1);
+    lines.push("Gruppe;Hash;Größe (Bytes);Anzahl;Einsparbar (Bytes);Pfad;Name".to_string());
+    for (i, g) in results.groups.iter().enumerate() {
+        for f in &g.files {
+            lines.push(format!("{};\"{}\";{};{};{};\"{}\";\"{}\"",
+                i + 1,
+                g.hash,
+                g.size,
+                g.count,
+                g.saveable,
+                f.path.replace('"', "\"\""),
+                f.name.replace('"', "\"\"")
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Export as JSON report (pretty-printed, with summary header)
+pub fn export_json(results: &DuplicateResults) -> Result<String, String> {
+    let total_saveable: u64 = results.groups.iter().map(|g| g.saveable).sum();
+    let report = json!({
+        "scanId": results.scan_id,
+        "rootPath": results.root_path,
+        "createdMs": results.created_ms,
+        "exportedMs": chrono::Utc::now().timestamp_millis(),
+        "totalGroups": results.groups.len(),
+        "totalSaveable": total_saveable,
+        "groups": results.groups
+    });
+    serde_json::to_string_pretty(&report).map_err(|e| e.to_string())
+}
+
+// ============================================================
+// Hashing
+// ============================================================
+
+/// Partial content hash (first 8KB + last 8KB + size) — fast pre-filter for same-size files
+pub fn compute_partial_hash(path: &str) -> Option<String> {
+    use std::io::Read;
+    let mut file = std::fs::File::open(crate::ospath::decode(path)).ok()?;
+    let meta = file.metadata().ok()?;
+    let size = meta.len();
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    use std::hash::Hasher;
+
+    // Hash first 8KB
+    let mut buf = [0u8; 8192];
+    let n = file.read(&mut buf).ok()?;
+    hasher.write(&buf[..n]);
+
+    // Hash last 8KB if file is large enough
+    if size > 16384 {
+        use std::io::Seek;
+        file.seek(std::io::SeekFrom::End(-8192)).ok()?;
+        let n = file.read(&mut buf).ok()?;
+        hasher.write(&buf[..n]);
+    }
+
+    // Include file size in hash to reduce collisions
+    hasher.write_u64(size);
+
+    Some(format!("{:016x}", hasher.finish()))
+}
+
+/// Full content hash (streams the whole file, BLAKE3) — used when a match must be certain,
+/// e.g. before a backup copy is declared identical and safe to delete
+pub fn compute_full_hash(path: &str) -> Option<String> {
+    use std::io::Read;
+    let file = std::fs::File::open(crate::ospath::decode(path)).ok()?;
+    let mut reader = std::io::BufReader::with_capacity(256 * 1024, file);
+    let mut hasher = blake3::Hasher::new();
+    let mut buf = vec![0u8; 256 * 1024];
+    loop {
+        let n = reader.read(&mut buf).ok()?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Some(hasher.finalize().to_hex().to_string())
+}
+
+// ============================================================
+// Cross-Root Comparison (scan A vs. scan B)
+// ============================================================
+
+/// Compare the contents of two file lists by size + hash, ignoring paths.
+/// Files whose size only occurs on one side are never hashed.
+/// `progress(processed, total)` is called periodically, `cancelled()` is polled between files.
+pub fn compare_file_sets(
+    files_a: Vec<DuplicateFile>,
+    files_b: Vec<DuplicateFile>,
+    full_hash: bool,
+    mut progress: impl FnMut(u64, u64),
+    cancelled: impl Fn() -> bool,
+) -> Option<(Vec<CrossGroup>, Vec<DuplicateFile>, Vec<DuplicateFile>)> {
+    let mut sizes_a: HashMap<u64, Vec<DuplicateFile>> = HashMap::new();
+    let mut sizes_b: HashMap<u64, Vec<DuplicateFile>> = HashMap::new();
+    for f in files_a {
+        sizes_a.entry(f.size).or_default().push(f);
+    }
+    for f in files_b {
+        sizes_b.entry(f.size).or_default().push(f);
+    }
+
+    let mut only_a: Vec<DuplicateFile> = Vec::new();
+    let mut only_b: Vec<DuplicateFile> = Vec::new();
+
+    // Sizes present on only one side can't have a match — no hashing needed
+    let shared_sizes: Vec<u64> = sizes_a.keys().filter(|s| sizes_b.contains_key(s)).copied().collect();
+    sizes_a.retain(|size, files| {
+        if sizes_b.contains_key(size) { true } else { only_a.append(files); false }
+    });
+    sizes_b.retain(|size, files| {
+        if sizes_a.contains_key(size) { true } else { only_b.append(files); false }
+    });
+
+    let total: u64 = sizes_a.values().chain(sizes_b.values()).map(|v| v.len() as u64).sum();
+    let mut processed = 0u64;
+    let hash_fn: fn(&str) -> Option<String> = if full_hash { compute_full_hash } else { compute_partial_hash };
+
+    let mut both: Vec<CrossGroup> = Vec::new();
+    for size in shared_sizes {
+        if cancelled() {
+            return None;
+        }
+        let mut by_hash: HashMap<String, (Vec<DuplicateFile>, Vec<DuplicateFile>)> = HashMap::new();
+        for f in sizes_a.remove(&size).unwrap_or_default() {
+            processed += 1;
+            match hash_fn(&f.path) {
+                Some(h) => by_hash.entry(h).or_default().0.push(f),
+                None => only_a.push(f), // unreadable — can't prove it exists on the other side
+            }
+        }
+        for f in sizes_b.remove(&size).unwrap_or_default() {
+            processed += 1;
+            match hash_fn(&f.path) {
+                Some(h) => by_hash.entry(h).or_default().1.push(f),
+                None => only_b.push(f),
+            }
+        }
+        for (hash, (a, b)) in by_hash {
+            if a.is_empty() {
+                only_b.extend(b);
+            } else if b.is_empty() {
+                only_a.extend(a);
+            } else {
+                both.push(CrossGroup { hash, size, files_a: a, files_b: b });
+            }
+        }
+        progress(processed, total);
+    }
+
+    both.sort_by(|a, b| b.size.cmp(&a.size));
+    only_a.sort_by(|a, b| b.size.cmp(&a.size));
+    only_b.sort_by(|a, b| b.size.cmp(&a.size));
+    Some((both, only_a, only_b))
+}
+
+fn cross_results_path(data_dir: &Path, scan_id_a: &str, scan_id_b: &str) -> PathBuf {
+    let safe = |id: &str| -> String {
+        id.chars().filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-').collect()
+    };
+    data_dir.join(RESULTS_DIR).join(format!("cross_{}_{}.json", safe(scan_id_a), safe(scan_id_b)))
+}
+
+/// Save cross-root comparison results (one file per scan pair)
+pub fn save_cross_results(data_dir: &Path, results: &CrossResults) -> Result<(), String> {
+    let path = cross_results_path(data_dir, &results.scan_id_a, &results.scan_id_b);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Duplikat-Verzeichnis erstellen: {}", e))?;
+    }
+    let json = serde_json::to_string(results).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("Vergleichs-Ergebnisse schreiben: {}", e))
+}
+
+/// Load cross-root comparison results for a scan pair
+pub fn load_cross_results(data_dir: &Path, scan_id_a: &str, scan_id_b: &str) -> Option<CrossResults> {
+    let content = std::fs::read_to_string(cross_results_path(data_dir, scan_id_a, scan_id_b)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Summary of a cross-root comparison (counts + bytes per bucket, no file lists)
+pub fn cross_summary(results: &CrossResults) -> Value {
+    let both_files_a: usize = results.both.iter().map(|g| g.files_a.len()).sum();
+    let both_files_b: usize = results.both.iter().map(|g| g.files_b.len()).sum();
+    let both_size_a: u64 = results.both.iter().map(|g| g.size * g.files_a.len() as u64).sum();
+    json!({
+        "scanIdA": results.scan_id_a,
+        "scanIdB": results.scan_id_b,
+        "rootA": results.root_a,
+        "rootB": results.root_b,
+        "createdMs": results.created_ms,
+        "fullHash": results.full_hash,
+        "bothGroups": results.both.len(),
+        "bothFilesA": both_files_a,
+        "bothFilesB": both_files_b,
+        "bothSizeA": both_size_a,
+        "onlyACount": results.only_a.len(),
+        "onlyASize": results.only_a.iter().map(|f| f.size).sum::<u64>(),
+        "onlyBCount": results.only_b.len(),
+        "onlyBSize": results.only_b.iter().map(|f| f.size).sum::<u64>()
+    })
+}
+
+/// Paged view of one bucket ("both" | "onlyA" | "onlyB") of a cross-root comparison
+pub fn page_cross_results(results: &CrossResults, bucket: &str, offset: usize, limit: usize) -> Result<Value, String> {
+    let (total, items): (usize, Value) = match bucket {
+        "both" => (results.both.len(), json!(results.both.iter().skip(offset).take(limit).collect::<Vec<_>>())),
+        "onlyA" => (results.only_a.len(), json!(results.only_a.iter().skip(offset).take(limit).collect::<Vec<_>>())),
+        "onlyB" => (results.only_b.len(), json!(results.only_b.iter().skip(offset).take(limit).collect::<Vec<_>>())),
+        _ => return Err(format!("Unbekannte Ergebnis-Kategorie: {}", bucket)),
+    };
+    Ok(json!({
+        "bucket": bucket,
+        "total": total,
+        "offset": offset,
+        "limit": limit,
+        "hasMore": offset + limit < total,
+        "items": items,
+        "summary": cross_summary(results)
+    }))
+}
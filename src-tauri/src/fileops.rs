@@ -0,0 +1,830 @@
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+/// Copy buffer — also the granularity of progress events and cancellation
+const CHUNK: usize = 1024 * 1024;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+const CANCELLED: &str = "Vorgang abgebrochen";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JobKind {
+    Copy,
+    Move,
+}
+
+impl JobKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Copy => "copy",
+            Self::Move => "move",
+        }
+    }
+}
+
+/// What happens when a target already exists
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Emit `file-job-conflict` and wait for `resolve_file_conflict`
+    Ask,
+    Skip,
+    Overwrite,
+    /// Keep both: "name (2).ext"
+    Rename,
+    OverwriteIfNewer,
+    /// Abort (and roll back) the job at the first existing target
+    Fail,
+}
+
+impl ConflictPolicy {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "ask" => Ok(Self::Ask),
+            "skip" => Ok(Self::Skip),
+            "overwrite" => Ok(Self::Overwrite),
+            "rename" => Ok(Self::Rename),
+            "overwrite_newer" => Ok(Self::OverwriteIfNewer),
+            "fail" => Ok(Self::Fail),
+            _ => Err(format!("Unbekannte Konfliktregel: {} (erlaubt: ask, skip, overwrite, rename, overwrite_newer, fail)", s)),
+        }
+    }
+}
+
+/// Per-job settings
+#[derive(Clone, Copy, Debug)]
+pub struct JobOptions {
+    pub conflict: ConflictPolicy,
+    /// Hash source and written copy (BLAKE3) and compare before the copy is committed
+    pub verify: bool,
+    /// Carry over timestamps, permission bits and extended attributes
+    pub preserve: bool,
+}
+
+impl JobOptions {
+    /// Defaults for a job kind: moves verify, because their source is deleted afterwards
+    pub fn new(kind: JobKind, conflict: ConflictPolicy) -> Self {
+        Self { conflict, verify: kind == JobKind::Move, preserve: true }
+    }
+}
+
+/// Answer to a conflict question; `apply_to_all` turns it into the job's policy
+pub struct ConflictAnswer {
+    pub policy: ConflictPolicy,
+    pub apply_to_all: bool,
+}
+
+struct JobControl {
+    kind: JobKind,
+    dest_dir: String,
+    started_ms: i64,
+    cancel: Arc<AtomicBool>,
+    answers: mpsc::Sender<ConflictAnswer>,
+}
+
+fn jobs() -> &'static Mutex<HashMap<String, JobControl>> {
+    static JOBS: OnceLock<Mutex<HashMap<String, JobControl>>> = OnceLock::new();
+    JOBS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub fn cancel(job_id: &str) -> bool {
+    let jobs = jobs().lock().unwrap_or_else(|e| e.into_inner());
+    match jobs.get(job_id) {
+        Some(j) => {
+            j.cancel.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+pub fn answer_conflict(job_id: &str, answer: ConflictAnswer) -> Result<(), String> {
+    let jobs = jobs().lock().unwrap_or_else(|e| e.into_inner());
+    let job = jobs.get(job_id).ok_or_else(|| format!("Kein laufender Auftrag '{}'", job_id))?;
+    job.answers.send(answer).map_err(|_| "Auftrag wartet nicht auf eine Antwort".to_string())
+}
+
+pub fn running() -> Value {
+    let jobs = jobs().lock().unwrap_or_else(|e| e.into_inner());
+    json!(jobs.iter().map(|(id, j)| json!({
+        "jobId": id,
+        "kind": j.kind.as_str(),
+        "destDir": j.dest_dir,
+        "startedMs": j.started_ms
+    })).collect::<Vec<_>>())
+}
+
+/// Everything the job did to the filesystem, in order — undone in reverse on failure
+enum Step {
+    CreatedDir(PathBuf),
+    CreatedFile(PathBuf),
+    /// Existing target moved aside before being overwritten
+    Backup { target: PathBuf, backup: PathBuf },
+    /// Same-volume move of a whole top-level entry
+    Renamed { from: PathBuf, to: PathBuf },
+}
+
+/// Where a top-level entry ended up
+struct Placed {
+    target: PathBuf,
+    /// Nothing below it was skipped
+    complete: bool,
+    /// The target did not exist before
+    fresh: bool,
+}
+
+enum Resolved {
+    Skip,
+    Write { target: PathBuf, overwrite: bool },
+}
+
+struct Runner<'a> {
+    job_id: &'a str,
+    kind: JobKind,
+    app: Option<&'a tauri::AppHandle>,
+    cancel: &'a AtomicBool,
+    answers: &'a mpsc::Receiver<ConflictAnswer>,
+    policy: ConflictPolicy,
+    verify: bool,
+    preserve: bool,
+    journal: Vec<Step>,
+    /// Move: source files that were copied (deleted once the job succeeded)
+    copied_sources: Vec<PathBuf>,
+    /// Move: source directories to remove afterwards (if empty)
+    source_dirs: Vec<PathBuf>,
+    /// Created folders whose attributes are applied once everything below is written
+    created_dirs: Vec<(PathBuf, PathBuf)>,
+    verified: u64,
+    succeeded: Vec<Value>,
+    skipped: Vec<Value>,
+    bytes_total: u64,
+    bytes_done: u64,
+    files_total: u64,
+    files_done: u64,
+    current: String,
+    last_emit: Instant,
+}
+
+fn enc(p: &Path) -> String {
+    crate::ospath::encode(p.as_os_str())
+}
+
+fn modified(meta: &std::fs::Metadata) -> i64 {
+    meta.modified().ok()
+        .map(|t| t.duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Bytes + files below a path (symlinks are not followed)
+fn measure(path: &Path) -> (u64, u64) {
+    walkdir::WalkDir::new(path).follow_links(false).into_iter()
+        .flatten()
+        .filter(|e| !e.file_type().is_dir())
+        .fold((0, 0), |(bytes, files), e| (bytes + e.metadata().map(|m| m.len()).unwrap_or(0), files + 1))
+}
+
+/// "name (2).ext", "name (3).ext", … — first name that does not exist yet
+pub fn unique_target(target: &Path, is_dir: bool) -> PathBuf {
+    let parent = target.parent().unwrap_or(Path::new(""));
+    let (stem, ext) = if is_dir {
+        (target.file_name().unwrap_or_default().to_os_string(), None)
+    } else {
+        (target.file_stem().unwrap_or_default().to_os_string(), target.extension().map(|e| e.to_os_string()))
+    };
+    for n in 2u32.. {
+        let mut name = stem.clone();
+        name.push(format!(" ({})", n));
+        if let Some(e) = &ext {
+            name.push(".");
+            name.push(e);
+        }
+        let candidate = parent.join(name);
+        if std::fs::symlink_metadata(&candidate).is_err() {
+            return candidate;
+        }
+    }
+    unreachable!("u32-Namensraum erschöpft")
+}
+
+/// Hidden sibling used while a file is written or an existing target is set aside
+pub fn temp_sibling(target: &Path, job_id: &str, tag: &str) -> PathBuf {
+    let mut name = std::ffi::OsString::from(".");
+    name.push(target.file_name().unwrap_or_default());
+    name.push(format!(".{}-{}", tag, job_id));
+    target.with_file_name(name)
+}
+
+fn file_times(meta: &std::fs::Metadata) -> std::fs::FileTimes {
+    let mut times = std::fs::FileTimes::new();
+    if let Ok(t) = meta.accessed() {
+        times = times.set_accessed(t);
+    }
+    if let Ok(t) = meta.modified() {
+        times = times.set_modified(t);
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::FileTimesExt;
+        if let Ok(t) = meta.created() {
+            times = times.set_created(t);
+        }
+    }
+    #[cfg(target_os = "macos")]
+    {
+        use std::os::macos::fs::FileTimesExt;
+        if let Ok(t) = meta.created() {
+            times = times.set_created(t);
+        }
+    }
+    times
+}
+
+fn set_dir_times(dir: &Path, meta: &std::fs::Metadata) {
+    #[cfg(windows)]
+    let handle = {
+        use std::os::windows::fs::OpenOptionsExt;
+        // FILE_FLAG_BACKUP_SEMANTICS — required to open a directory handle
+        std::fs::OpenOptions::new().write(true).custom_flags(0x0200_0000).open(dir)
+    };
+    #[cfg(not(windows))]
+    let handle = std::fs::File::open(dir);
+    if let Ok(f) = handle {
+        let _ = f.set_times(file_times(meta));
+    }
+}
+
+/// Copy extended attributes (user.*, security.* where permitted). Best effort:
+/// filesystems without xattr support or missing privileges just leave them out.
+#[cfg(target_os = "linux")]
+fn copy_xattrs(src: &Path, dst: &Path) {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    let (c_src, c_dst) = match (CString::new(src.as_os_str().as_bytes()), CString::new(dst.as_os_str().as_bytes())) {
+        (Ok(s), Ok(d)) => (s, d),
+        _ => return,
+    };
+    // SAFETY: c_src is a NUL-terminated path that outlives the call; a null buffer of
+    // size 0 only asks for the required length
+    let len = unsafe { libc::llistxattr(c_src.as_ptr(), std::ptr::null_mut(), 0) };
+    if len <= 0 {
+        return;
+    }
+    let mut names = vec![0u8; len as usize];
+    // SAFETY: names is a writable buffer of exactly names.len() bytes; if the list grew
+    // meanwhile the call fails with ERANGE instead of writing past it
+    let len = unsafe { libc::llistxattr(c_src.as_ptr(), names.as_mut_ptr() as *mut libc::c_char, names.len()) };
+    if len <= 0 {
+        return;
+    }
+    for name in names[..len as usize].split(|&b| b == 0).filter(|n| !n.is_empty()) {
+        let c_name = match CString::new(name) {
+            Ok(n) => n,
+            Err(_) => continue,
+        };
+        // SAFETY: both strings are NUL-terminated and live across the call; null/0 is
+        // the size query
+        let size = unsafe { libc::lgetxattr(c_src.as_ptr(), c_name.as_ptr(), std::ptr::null_mut(), 0) };
+        if size < 0 {
+            continue;
+        }
+        let mut value = vec![0u8; size as usize];
+        // SAFETY: value is a writable buffer of value.len() bytes (ERANGE if too small)
+        let size = unsafe { libc::lgetxattr(c_src.as_ptr(), c_name.as_ptr(), value.as_mut_ptr() as *mut libc::c_void, value.len()) };
+        if size < 0 {
+            continue;
+        }
+        // SAFETY: size is non-negative and at most value.len() (returned by the read
+        // above), so only initialised bytes of value are passed
+        let rc = unsafe { libc::lsetxattr(c_dst.as_ptr(), c_name.as_ptr(), value.as_ptr() as *const libc::c_void, size as usize, 0) };
+        if rc != 0 {
+            tracing::debug!(path = %dst.display(), attr = %String::from_utf8_lossy(name), error = %std::io::Error::last_os_error(), "Erweitertes Attribut nicht übernommen");
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn copy_xattrs(_src: &Path, _dst: &Path) {}
+
+impl Runner<'_> {
+    fn check_cancel(&self) -> Result<(), String> {
+        if self.cancel.load(Ordering::Relaxed) { Err(CANCELLED.to_string()) } else { Ok(()) }
+    }
+
+    fn progress(&mut self, force: bool) {
+        if !force && self.last_emit.elapsed() < PROGRESS_INTERVAL {
+            return;
+        }
+        self.last_emit = Instant::now();
+        if let Some(app) = self.app {
+            let _ = app.emit("file-job-progress", json!({
+                "job_id": self.job_id,
+                "kind": self.kind.as_str(),
+                "bytes_done": self.bytes_done,
+                "bytes_total": self.bytes_total,
+                "files_done": self.files_done,
+                "files_total": self.files_total,
+                "current_path": self.current
+            }));
+        }
+    }
+
+    /// Ask the frontend and block until it answers (or the job is cancelled)
+    fn ask(&mut self, src: &Path, src_meta: &std::fs::Metadata, target: &Path, target_meta: &std::fs::Metadata) -> Result<ConflictPolicy, String> {
+        let app = match self.app {
+            Some(a) => a,
+            None => return Err("Konfliktabfrage ohne Oberfläche nicht möglich".to_string()),
+        };
+        // Answers to an earlier question that arrived late are stale
+        while self.answers.try_recv().is_ok() {}
+        let _ = app.emit("file-job-conflict", crate::ospath::annotate(json!({
+            "job_id": self.job_id,
+            "path": enc(target),
+            "name": target.file_name().map(crate::ospath::encode).unwrap_or_default(),
+            "source": enc(src),
+            "sourceSize": src_meta.len(),
+            "sourceModified": modified(src_meta),
+            "sourceIsDir": src_meta.is_dir(),
+            "targetSize": target_meta.len(),
+            "targetModified": modified(target_meta),
+            "targetIsDir": target_meta.is_dir()
+        })));
+        loop {
+            self.check_cancel()?;
+            match self.answers.recv_timeout(Duration::from_millis(200)) {
+                Ok(answer) => {
+                    if answer.policy == ConflictPolicy::Ask {
+                        continue;
+                    }
+                    if answer.apply_to_all {
+                        self.policy = answer.policy;
+                    }
+                    return Ok(answer.policy);
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                Err(mpsc::RecvTimeoutError::Disconnected) => return Err(CANCELLED.to_string()),
+            }
+        }
+    }
+
+    fn resolve(&mut self, src: &Path, src_meta: &std::fs::Metadata, target: &Path) -> Result<Resolved, String> {
+        let target_meta = match std::fs::symlink_metadata(target) {
+            Ok(m) => m,
+            Err(_) => return Ok(Resolved::Write { target: target.to_path_buf(), overwrite: false }),
+        };
+        let policy = match self.policy {
+            ConflictPolicy::Ask => self.ask(src, src_meta, target, &target_meta)?,
+            p => p,
+        };
+        let policy = match policy {
+            ConflictPolicy::OverwriteIfNewer if modified(src_meta) > modified(&target_meta) => ConflictPolicy::Overwrite,
+            ConflictPolicy::OverwriteIfNewer => ConflictPolicy::Skip,
+            p => p,
+        };
+        Ok(match policy {
+            ConflictPolicy::Fail => return Err(format!("Ziel existiert bereits: {}", target.display())),
+            ConflictPolicy::Skip => Resolved::Skip,
+            ConflictPolicy::Rename => Resolved::Write { target: unique_target(target, src_meta.is_dir()), overwrite: false },
+            _ => Resolved::Write { target: target.to_path_buf(), overwrite: true },
+        })
+    }
+
+    fn skip(&mut self, src: &Path, target: &Path, reason: &str) {
+        let (bytes, files) = measure(src);
+        self.bytes_done += bytes;
+        self.files_done += files;
+        self.skipped.push(json!({ "source": enc(src), "target": enc(target), "reason": reason }));
+    }
+
+    /// Move an existing target aside; it is restored on rollback and deleted on success
+    fn set_aside(&mut self, target: &Path) -> Result<(), String> {
+        let backup = temp_sibling(target, self.job_id, "replaced");
+        std::fs::rename(target, &backup)
+            .map_err(|e| format!("Vorhandenes Ziel {} ersetzen: {}", target.display(), e))?;
+        self.journal.push(Step::Backup { target: target.to_path_buf(), backup });
+        Ok(())
+    }
+
+    /// Copy one entry (recursively). None if the entry itself was skipped.
+    fn copy_entry(&mut self, src: &Path, target: &Path) -> Result<Option<Placed>, String> {
+        self.check_cancel()?;
+        let meta = std::fs::symlink_metadata(src).map_err(|e| format!("{}: {}", src.display(), e))?;
+
+        if meta.is_dir() {
+            let (target, overwrite) = match std::fs::symlink_metadata(target) {
+                // Existing folder: merge, conflicts are decided per file
+                Ok(t) if t.is_dir() => (target.to_path_buf(), false),
+                Ok(_) => match self.resolve(src, &meta, target)? {
+                    Resolved::Skip => {
+                        self.skip(src, target, "conflict");
+                        return Ok(None);
+                    }
+                    Resolved::Write { target, overwrite } => {
+                        if overwrite {
+                            self.set_aside(&target)?;
+                        }
+                        (target, overwrite)
+                    }
+                },
+                Err(_) => (target.to_path_buf(), false),
+            };
+            let created = std::fs::symlink_metadata(&target).is_err();
+            if created {
+                std::fs::create_dir(&target).map_err(|e| format!("Ordner {} erstellen: {}", target.display(), e))?;
+                self.journal.push(Step::CreatedDir(target.clone()));
+            }
+            let mut children: Vec<PathBuf> = std::fs::read_dir(src)
+                .map_err(|e| format!("{}: {}", src.display(), e))?
+                .map(|e| e.map(|e| e.path()))
+                .collect::<Result<_, _>>()
+                .map_err(|e| format!("{}: {}", src.display(), e))?;
+            children.sort();
+            let mut complete = true;
+            for child in children {
+                let name = child.file_name().unwrap_or_default().to_os_string();
+                complete &= self.copy_entry(&child, &target.join(name))?.is_some_and(|p| p.complete);
+            }
+            if self.kind == JobKind::Move {
+                self.source_dirs.push(src.to_path_buf());
+            }
+            if created {
+                self.created_dirs.push((src.to_path_buf(), target.clone()));
+            }
+            return Ok(Some(Placed { target, complete, fresh: created && !overwrite }));
+        }
+
+        let (target, overwrite) = match self.resolve(src, &meta, target)? {
+            Resolved::Skip => {
+                self.skip(src, target, "conflict");
+                return Ok(None);
+            }
+            Resolved::Write { target, overwrite } => (target, overwrite),
+        };
+        if overwrite && std::fs::symlink_metadata(&target).map(|m| m.is_dir()).unwrap_or(false) {
+            return Err(format!("Ordner {} kann nicht durch eine Datei ersetzt werden", target.display()));
+        }
+        self.current = enc(src);
+        self.copy_file(src, &meta, &target, overwrite)?;
+        if self.kind == JobKind::Move {
+            self.copied_sources.push(src.to_path_buf());
+        }
+        self.files_done += 1;
+        self.progress(false);
+        Ok(Some(Placed { target, complete: true, fresh: !overwrite }))
+    }
+
+    /// Write into a hidden temp file and rename it into place — a target is never half-written
+    fn copy_file(&mut self, src: &Path, meta: &std::fs::Metadata, target: &Path, overwrite: bool) -> Result<(), String> {
+        let tmp = temp_sibling(target, self.job_id, "part");
+
+        // Symlinks are recreated, not followed
+        #[cfg(unix)]
+        {
+            if meta.file_type().is_symlink() {
+                let link = std::fs::read_link(src).map_err(|e| format!("{}: {}", src.display(), e))?;
+                std::os::unix::fs::symlink(&link, &tmp).map_err(|e| format!("Verknüpfung {} erstellen: {}", target.display(), e))?;
+                if self.verify && std::fs::read_link(&tmp).ok().as_ref() != Some(&link) {
+                    let _ = std::fs::remove_file(&tmp);
+                    return Err(format!("{}: Verknüpfung wurde nicht korrekt angelegt", target.display()));
+                }
+                return self.commit_temp(&tmp, target, overwrite);
+            }
+        }
+
+        let result = (|| -> Result<(), String> {
+            let mut input = std::fs::File::open(src).map_err(|e| format!("{}: {}", src.display(), e))?;
+            let mut output = std::fs::OpenOptions::new().write(true).create_new(true).open(&tmp)
+                .map_err(|e| format!("{} erstellen: {}", target.display(), e))?;
+            let mut hasher = self.verify.then(blake3::Hasher::new);
+            let mut written = 0u64;
+            let mut buf = vec![0u8; CHUNK];
+            loop {
+                self.check_cancel()?;
+                let n = input.read(&mut buf).map_err(|e| format!("{} lesen: {}", src.display(), e))?;
+                if n == 0 {
+                    break;
+                }
+                output.write_all(&buf[..n]).map_err(|e| format!("{} schreiben: {}", target.display(), e))?;
+                if let Some(h) = hasher.as_mut() {
+                    h.update(&buf[..n]);
+                }
+                written += n as u64;
+                self.bytes_done += n as u64;
+                self.progress(false);
+            }
+            // On disk before anything is verified or a source is deleted
+            if self.verify || self.kind == JobKind::Move {
+                output.sync_all().map_err(|e| format!("{} schreiben: {}", target.display(), e))?;
+            } else {
+                output.flush().map_err(|e| format!("{} schreiben: {}", target.display(), e))?;
+            }
+            let size = output.metadata().map(|m| m.len()).unwrap_or(written);
+            if size != written {
+                return Err(format!("{}: Größe der Kopie weicht ab ({} statt {} Bytes)", target.display(), size, written));
+            }
+            if self.preserve {
+                copy_xattrs(src, &tmp);
+            }
+            if let Some(h) = hasher {
+                self.verify_copy(&tmp, target, h.finalize())?;
+            }
+            // Times last: reading and attribute writes above would touch them
+            if self.preserve {
+                let _ = output.set_times(file_times(meta));
+                drop(output);
+                let _ = std::fs::set_permissions(&tmp, meta.permissions());
+            }
+            Ok(())
+        })();
+        if let Err(e) = result {
+            let _ = std::fs::remove_file(&tmp);
+            return Err(e);
+        }
+        self.commit_temp(&tmp, target, overwrite)
+    }
+
+    /// Re-read the written copy and compare its hash with the one taken while copying
+    fn verify_copy(&mut self, tmp: &Path, target: &Path, expected: blake3::Hash) -> Result<(), String> {
+        let mut input = std::fs::File::open(tmp).map_err(|e| format!("{} prüfen: {}", target.display(), e))?;
+        let mut hasher = blake3::Hasher::new();
+        let mut buf = vec![0u8; CHUNK];
+        loop {
+            self.check_cancel()?;
+            let n = input.read(&mut buf).map_err(|e| format!("{} prüfen: {}", target.display(), e))?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+        }
+        if hasher.finalize() != expected {
+            return Err(format!("{}: Prüfsumme der Kopie stimmt nicht mit dem Original überein", target.display()));
+        }
+        self.verified += 1;
+        Ok(())
+    }
+
+    fn commit_temp(&mut self, tmp: &Path, target: &Path, overwrite: bool) -> Result<(), String> {
+        if overwrite && std::fs::symlink_metadata(target).is_ok() {
+            if let Err(e) = self.set_aside(target) {
+                let _ = std::fs::remove_file(tmp);
+                return Err(e);
+            }
+        }
+        if let Err(e) = std::fs::rename(tmp, target) {
+            let _ = std::fs::remove_file(tmp);
+            return Err(format!("{} ablegen: {}", target.display(), e));
+        }
+        self.journal.push(Step::CreatedFile(target.to_path_buf()));
+        Ok(())
+    }
+
+    fn process(&mut self, src: &Path, target: &Path) -> Result<(), String> {
+        self.check_cancel()?;
+        if src.file_name().is_none() {
+            return Err(format!("Ungültiger Quellpfad: {}", src.display()));
+        }
+        let dest_dir = target.parent().unwrap_or(Path::new(""));
+        if !dest_dir.is_dir() {
+            return Err(format!("Zielordner {} existiert nicht", dest_dir.display()));
+        }
+        let meta = std::fs::symlink_metadata(src).map_err(|e| format!("{}: {}", src.display(), e))?;
+        if target == src {
+            // Copy into its own folder: always keep both, moving is a no-op
+            if self.kind == JobKind::Move {
+                self.skip(src, target, "same_location");
+                return Ok(());
+            }
+            let copy_target = unique_target(target, meta.is_dir());
+            if let Some(placed) = self.copy_entry(src, &copy_target)? {
+                self.record(src, &placed);
+            }
+            return Ok(());
+        }
+        if target.starts_with(src) {
+            return Err(format!("{} kann nicht in sich selbst kopiert werden", src.display()));
+        }
+
+        // Same-volume move of a whole entry: one rename, no byte copying
+        if self.kind == JobKind::Move {
+            let resolved = match std::fs::symlink_metadata(target) {
+                Ok(t) if t.is_dir() && meta.is_dir() => None, // merge — entry by entry
+                Ok(_) => Some(self.resolve(src, &meta, target)?),
+                Err(_) => Some(Resolved::Write { target: target.to_path_buf(), overwrite: false }),
+            };
+            match resolved {
+                Some(Resolved::Skip) => {
+                    self.skip(src, target, "conflict");
+                    return Ok(());
+                }
+                Some(Resolved::Write { target: t, overwrite }) => {
+                    if overwrite {
+                        self.set_aside(&t)?;
+                    }
+                    if std::fs::rename(src, &t).is_ok() {
+                        let (bytes, files) = measure(&t);
+                        self.bytes_done += bytes;
+                        self.files_done += files;
+                        self.journal.push(Step::Renamed { from: src.to_path_buf(), to: t.clone() });
+                        self.record(src, &Placed { target: t, complete: true, fresh: !overwrite });
+                        self.progress(true);
+                        return Ok(());
+                    }
+                    // Cross-volume: copy (conflict already decided), sources go after success
+                    self.current = enc(src);
+                    if meta.is_dir() {
+                        std::fs::create_dir(&t).map_err(|e| format!("Ordner {} erstellen: {}", t.display(), e))?;
+                        self.journal.push(Step::CreatedDir(t.clone()));
+                    }
+                    let complete = if meta.is_dir() {
+                        let complete = self.copy_entry(src, &t)?.is_some_and(|p| p.complete);
+                        self.created_dirs.push((src.to_path_buf(), t.clone()));
+                        complete
+                    } else {
+                        self.copy_file(src, &meta, &t, false)?;
+                        self.copied_sources.push(src.to_path_buf());
+                        self.files_done += 1;
+                        true
+                    };
+                    self.record(src, &Placed { target: t, complete, fresh: !overwrite });
+                    return Ok(());
+                }
+                None => {}
+            }
+        }
+
+        if let Some(placed) = self.copy_entry(src, target)? {
+            self.record(src, &placed);
+        }
+        Ok(())
+    }
+
+    /// `created`: the target did not exist before (not merged into, not overwritten)
+    fn record(&mut self, src: &Path, placed: &Placed) {
+        self.succeeded.push(json!({
+            "source": enc(src),
+            "target": enc(&placed.target),
+            "partial": !placed.complete,
+            "created": placed.fresh
+        }));
+    }
+
+    /// Undo every step in reverse order
+    fn rollback(&mut self) -> Vec<String> {
+        let mut errors = Vec::new();
+        while let Some(step) = self.journal.pop() {
+            let result = match &step {
+                Step::CreatedFile(p) => std::fs::remove_file(p),
+                Step::CreatedDir(p) => std::fs::remove_dir(p),
+                Step::Backup { target, backup } => std::fs::rename(backup, target),
+                Step::Renamed { from, to } => std::fs::rename(to, from),
+            };
+            if let Err(e) = result {
+                let path = match &step {
+                    Step::CreatedFile(p) | Step::CreatedDir(p) => p,
+                    Step::Backup { target, .. } => target,
+                    Step::Renamed { from, .. } => from,
+                };
+                errors.push(format!("{}: {}", path.display(), e));
+            }
+        }
+        errors
+    }
+
+    /// Success: drop replaced targets, delete moved sources (files first, then empty folders)
+    fn finish(&mut self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for step in &self.journal {
+            if let Step::Backup { backup, .. } = step {
+                let removed = if backup.is_dir() { std::fs::remove_dir_all(backup) } else { std::fs::remove_file(backup) };
+                if let Err(e) = removed {
+                    errors.push(format!("{}: {}", backup.display(), e));
+                }
+            }
+        }
+        // Folder times only hold once nothing is written below them anymore.
+        // Children were pushed before their parents.
+        if self.preserve {
+            for (src, target) in &self.created_dirs {
+                if let Ok(meta) = std::fs::symlink_metadata(src) {
+                    copy_xattrs(src, target);
+                    set_dir_times(target, &meta);
+                    let _ = std::fs::set_permissions(target, meta.permissions());
+                }
+            }
+        }
+        for src in &self.copied_sources {
+            if let Err(e) = std::fs::remove_file(src) {
+                errors.push(format!("{}: {}", src.display(), e));
+            }
+        }
+        // Children were pushed before their parents — this order removes deepest first
+        for dir in &self.source_dirs {
+            let _ = std::fs::remove_dir(dir);
+        }
+        errors
+    }
+}
+
+/// Run a copy/move job to completion (blocking). Any error or a cancel rolls the
+/// job back; the returned report lists what succeeded, was skipped or failed.
+pub fn run(app: Option<tauri::AppHandle>, job_id: &str, kind: JobKind, sources: Vec<PathBuf>, dest_dir: PathBuf, options: JobOptions) -> Value {
+    let items = sources.into_iter()
+        .map(|src| {
+            let target = dest_dir.join(src.file_name().unwrap_or_default());
+            (src, target)
+        })
+        .collect();
+    run_to(app, job_id, kind, items, options)
+}
+
+/// Like `run`, but every source goes to an explicit target path (undo/redo of moves and copies)
+pub fn run_to(app: Option<tauri::AppHandle>, job_id: &str, kind: JobKind, items: Vec<(PathBuf, PathBuf)>, options: JobOptions) -> Value {
+    let start = Instant::now();
+    let cancel = Arc::new(AtomicBool::new(false));
+    let (tx, rx) = mpsc::channel();
+    jobs().lock().unwrap_or_else(|e| e.into_inner()).insert(job_id.to_string(), JobControl {
+        kind,
+        dest_dir: items.first().and_then(|(_, t)| t.parent()).map(enc).unwrap_or_default(),
+        started_ms: chrono::Utc::now().timestamp_millis(),
+        cancel: cancel.clone(),
+        answers: tx,
+    });
+
+    let (bytes_total, files_total) = items.iter().map(|(s, _)| measure(s)).fold((0, 0), |a, b| (a.0 + b.0, a.1 + b.1));
+    let mut runner = Runner {
+        job_id,
+        kind,
+        app: app.as_ref(),
+        cancel: &cancel,
+        answers: &rx,
+        policy: options.conflict,
+        verify: options.verify,
+        preserve: options.preserve,
+        journal: Vec::new(),
+        copied_sources: Vec::new(),
+        source_dirs: Vec::new(),
+        created_dirs: Vec::new(),
+        verified: 0,
+        succeeded: Vec::new(),
+        skipped: Vec::new(),
+        bytes_total,
+        bytes_done: 0,
+        files_total,
+        files_done: 0,
+        current: String::new(),
+        last_emit: Instant::now(),
+    };
+    tracing::info!(job_id = %job_id, kind = kind.as_str(), sources = items.len(), bytes = bytes_total, verify = options.verify, "Datei-Auftrag gestartet");
+    runner.progress(true);
+
+    let mut failed: Option<(String, String)> = None;
+    for (src, target) in &items {
+        if let Err(e) = runner.process(src, target) {
+            failed = Some((enc(src), e));
+            break;
+        }
+    }
+
+    let (status, rolled_back, cleanup_errors) = match &failed {
+        None => ("completed", Vec::new(), runner.finish()),
+        Some((_, e)) => {
+            let undone = std::mem::take(&mut runner.succeeded);
+            let errors = runner.rollback();
+            let status = if e == CANCELLED { "cancelled" } else { "failed" };
+            tracing::warn!(job_id = %job_id, error = %e, rollback_errors = errors.len(), "Datei-Auftrag zurückgerollt");
+            (status, undone, errors)
+        }
+    };
+    runner.progress(true);
+    jobs().lock().unwrap_or_else(|e| e.into_inner()).remove(job_id);
+
+    let report = json!({
+        "jobId": job_id,
+        "kind": kind.as_str(),
+        "status": status,
+        "succeeded": runner.succeeded,
+        "skipped": runner.skipped,
+        "failed": failed.iter().map(|(path, error)| json!({ "source": path, "error": error })).collect::<Vec<_>>(),
+        "rolledBack": rolled_back,
+        "cleanupErrors": cleanup_errors,
+        "bytesCopied": runner.bytes_done,
+        "filesDone": runner.files_done,
+        "verified": runner.verified,
+        "elapsedMs": start.elapsed().as_millis() as u64
+    });
+    tracing::info!(job_id = %job_id, status = status, elapsed_ms = start.elapsed().as_millis(), "Datei-Auftrag beendet");
+    if let Some(app) = &app {
+        let event = if status == "completed" { "file-job-complete" } else { "file-job-error" };
+        let _ = app.emit(event, &report);
+    }
+    report
+}
+
+/// Counter, not a timestamp — two jobs started in the same millisecond must not share
+/// (and overwrite) one registry slot
+pub fn new_job_id() -> String {
+    crate::jobs::new_id("job")
+}
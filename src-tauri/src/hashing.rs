@@ -0,0 +1,455 @@
+use serde_json::{json, Value};
+use sha2::Digest;
+use std::fmt::Write as _;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::jobs::Job;
+
+/// Read buffer — also the granularity of progress events and cancellation
+const CHUNK: usize = 1024 * 1024;
+pub const CANCELLED: &str = "Prüfsummenberechnung abgebrochen";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    Md5,
+    Sha1,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+    Blake3,
+    Crc32,
+    Xxh32,
+    Xxh64,
+    Xxh3,
+    Xxh128,
+}
+
+impl Algorithm {
+    const ALL: [Algorithm; 12] = [
+        Self::Md5, Self::Sha1, Self::Sha224, Self::Sha256, Self::Sha384, Self::Sha512,
+        Self::Blake3, Self::Crc32, Self::Xxh32, Self::Xxh64, Self::Xxh3, Self::Xxh128,
+    ];
+
+    /// Accepts the usual spellings: "sha256", "SHA-256", "xxh3", "BLAKE3", …
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let norm: String = s.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_ascii_uppercase();
+        match norm.as_str() {
+            "MD5" => Ok(Self::Md5),
+            "SHA1" => Ok(Self::Sha1),
+            "SHA224" => Ok(Self::Sha224),
+            "SHA256" => Ok(Self::Sha256),
+            "SHA384" => Ok(Self::Sha384),
+            "SHA512" => Ok(Self::Sha512),
+            "BLAKE3" | "B3" => Ok(Self::Blake3),
+            "CRC32" | "CRC" => Ok(Self::Crc32),
+            "XXH32" => Ok(Self::Xxh32),
+            "XXH64" | "XXHASH" | "XXHASH64" => Ok(Self::Xxh64),
+            "XXH3" | "XXH364" => Ok(Self::Xxh3),
+            "XXH128" | "XXH3128" => Ok(Self::Xxh128),
+            _ => Err(format!(
+                "Unbekannter Algorithmus: {} (erlaubt: {})",
+                s,
+                Self::ALL.iter().map(|a| a.as_str()).collect::<Vec<_>>().join(", ")
+            )),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Md5 => "MD5",
+            Self::Sha1 => "SHA1",
+            Self::Sha224 => "SHA224",
+            Self::Sha256 => "SHA256",
+            Self::Sha384 => "SHA384",
+            Self::Sha512 => "SHA512",
+            Self::Blake3 => "BLAKE3",
+            Self::Crc32 => "CRC32",
+            Self::Xxh32 => "XXH32",
+            Self::Xxh64 => "XXH64",
+            Self::Xxh3 => "XXH3",
+            Self::Xxh128 => "XXH128",
+        }
+    }
+
+    /// Guess from a bare digest in a checksum file (the common sum tools by length)
+    fn from_hex_len(len: usize) -> Option<Self> {
+        match len {
+            8 => Some(Self::Crc32),
+            16 => Some(Self::Xxh64),
+            32 => Some(Self::Md5),
+            40 => Some(Self::Sha1),
+            56 => Some(Self::Sha224),
+            64 => Some(Self::Sha256),
+            96 => Some(Self::Sha384),
+            128 => Some(Self::Sha512),
+            _ => None,
+        }
+    }
+
+    /// From a checksum file name: "x.sha256", "SHA256SUMS", "md5sum.txt", "x.b3", "x.sfv"
+    fn from_file_name(name: &str) -> Option<Self> {
+        let lower = name.to_ascii_lowercase();
+        let ext = Path::new(&lower).extension().and_then(|e| e.to_str()).unwrap_or("");
+        let by_ext = match ext {
+            "b3" | "blake3" => Some(Self::Blake3),
+            "sfv" | "crc" | "crc32" => Some(Self::Crc32),
+            "xxh" => Some(Self::Xxh64),
+            _ => Self::parse(ext).ok(),
+        };
+        by_ext.or_else(|| {
+            Self::ALL.iter().copied().find(|a| lower.contains(&format!("{}sum", a.as_str().to_ascii_lowercase())))
+        })
+    }
+}
+
+/// Streaming state of one algorithm. The big states are boxed to keep the enum small.
+enum Hasher {
+    Md5(md5::Md5),
+    Sha1(sha1::Sha1),
+    Sha224(sha2::Sha224),
+    Sha256(sha2::Sha256),
+    Sha384(sha2::Sha384),
+    Sha512(sha2::Sha512),
+    Blake3(Box<blake3::Hasher>),
+    Crc32(crc32fast::Hasher),
+    Xxh32(xxhash_rust::xxh32::Xxh32),
+    Xxh64(xxhash_rust::xxh64::Xxh64),
+    Xxh3(Box<xxhash_rust::xxh3::Xxh3>),
+    Xxh128(Box<xxhash_rust::xxh3::Xxh3>),
+}
+
+fn hex(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        let _ = write!(out, "{:02x}", b);
+    }
+    out
+}
+
+impl Hasher {
+    fn new(algorithm: Algorithm) -> Self {
+        match algorithm {
+            Algorithm::Md5 => Self::Md5(md5::Md5::new()),
+            Algorithm::Sha1 => Self::Sha1(sha1::Sha1::new()),
+            Algorithm::Sha224 => Self::Sha224(sha2::Sha224::new()),
+            Algorithm::Sha256 => Self::Sha256(sha2::Sha256::new()),
+            Algorithm::Sha384 => Self::Sha384(sha2::Sha384::new()),
+            Algorithm::Sha512 => Self::Sha512(sha2::Sha512::new()),
+            Algorithm::Blake3 => Self::Blake3(Box::new(blake3::Hasher::new())),
+            Algorithm::Crc32 => Self::Crc32(crc32fast::Hasher::new()),
+            Algorithm::Xxh32 => Self::Xxh32(xxhash_rust::xxh32::Xxh32::new(0)),
+            Algorithm::Xxh64 => Self::Xxh64(xxhash_rust::xxh64::Xxh64::new(0)),
+            Algorithm::Xxh3 => Self::Xxh3(Box::new(xxhash_rust::xxh3::Xxh3::new())),
+            Algorithm::Xxh128 => Self::Xxh128(Box::new(xxhash_rust::xxh3::Xxh3::new())),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Md5(h) => h.update(data),
+            Self::Sha1(h) => h.update(data),
+            Self::Sha224(h) => h.update(data),
+            Self::Sha256(h) => h.update(data),
+            Self::Sha384(h) => h.update(data),
+            Self::Sha512(h) => h.update(data),
+            Self::Blake3(h) => {
+                h.update(data);
+            }
+            Self::Crc32(h) => h.update(data),
+            Self::Xxh32(h) => h.update(data),
+            Self::Xxh64(h) => h.update(data),
+            Self::Xxh3(h) | Self::Xxh128(h) => h.update(data),
+        }
+    }
+
+    /// Lowercase hex as printed by the matching *sum tools (xxhsum: canonical big-endian)
+    fn finish(self) -> String {
+        match self {
+            Self::Md5(h) => hex(&h.finalize()),
+            Self::Sha1(h) => hex(&h.finalize()),
+            Self::Sha224(h) => hex(&h.finalize()),
+            Self::Sha256(h) => hex(&h.finalize()),
+            Self::Sha384(h) => hex(&h.finalize()),
+            Self::Sha512(h) => hex(&h.finalize()),
+            Self::Blake3(h) => h.finalize().to_hex().to_string(),
+            Self::Crc32(h) => format!("{:08x}", h.finalize()),
+            Self::Xxh32(h) => format!("{:08x}", h.digest()),
+            Self::Xxh64(h) => format!("{:016x}", h.digest()),
+            Self::Xxh3(h) => format!("{:016x}", h.digest()),
+            Self::Xxh128(h) => format!("{:032x}", h.digest128()),
+        }
+    }
+}
+
+// === Jobs ===
+
+const FAMILY: &str = "hash";
+
+pub fn new_job_id() -> String {
+    crate::jobs::new_id(FAMILY)
+}
+
+pub fn cancel(job_id: &str) -> bool {
+    crate::jobs::cancel(FAMILY, job_id)
+}
+
+fn start_job<'a>(app: Option<&'a tauri::AppHandle>, job_id: &'a str, bytes_total: u64, files_total: u64) -> Result<Job<'a>, String> {
+    let mut job = Job::start(FAMILY, app, job_id, "hash-progress")?;
+    job.bytes_total = bytes_total;
+    job.files_total = files_total;
+    Ok(job)
+}
+
+/// One pass over the file feeds every requested algorithm
+fn hash_with(job: &mut Job<'_>, path: &Path, algorithms: &[Algorithm]) -> Result<Vec<String>, std::io::Error> {
+    job.current = crate::ospath::encode(path.as_os_str());
+    let mut file = std::fs::File::open(path)?;
+    let mut hashers: Vec<Hasher> = algorithms.iter().map(|&a| Hasher::new(a)).collect();
+    let mut buf = vec![0u8; CHUNK];
+    loop {
+        if job.cancelled() {
+            return Err(std::io::Error::new(std::io::ErrorKind::Interrupted, CANCELLED));
+        }
+        let n = match file.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        for h in &mut hashers {
+            h.update(&buf[..n]);
+        }
+        job.bytes_done += n as u64;
+        job.progress(false);
+    }
+    job.files_done += 1;
+    Ok(hashers.into_iter().map(Hasher::finish).collect())
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> String {
+    if e.kind() == std::io::ErrorKind::Interrupted {
+        return CANCELLED.to_string();
+    }
+    format!("{}: {}", path.display(), e)
+}
+
+/// Hash one file with several algorithms in a single read (blocking).
+/// Emits `hash-progress`; `cancel(job_id)` stops it with `CANCELLED`.
+pub fn hash_file(app: Option<&tauri::AppHandle>, job_id: &str, path: &Path, algorithms: &[Algorithm]) -> Result<Value, String> {
+    let start = Instant::now();
+    let size = std::fs::metadata(path).map_err(|e| io_error(path, e))?.len();
+    let mut job = start_job(app, job_id, size, 1)?;
+    job.progress(true);
+    let digests = hash_with(&mut job, path, algorithms).map_err(|e| io_error(path, e))?;
+    job.progress(true);
+    let hashes: serde_json::Map<String, Value> = algorithms.iter()
+        .zip(digests)
+        .map(|(a, d)| (a.as_str().to_string(), Value::String(d)))
+        .collect();
+    tracing::debug!(path = %path.display(), size = size, elapsed_ms = start.elapsed().as_millis(), "Prüfsummen berechnet");
+    Ok(crate::ospath::annotate(json!({
+        "jobId": job_id,
+        "path": crate::ospath::encode(path.as_os_str()),
+        "size": size,
+        "hashes": hashes,
+        "elapsedMs": start.elapsed().as_millis() as u64
+    })))
+}
+
+// === Checksum files ===
+// GNU style   "<hex>  name" / "<hex> *name" (a leading '\' marks an escaped name),
+// BSD style   "SHA256 (name) = <hex>",
+// SFV         "name <crc32>" (";" comments).
+// The algorithm comes from the BSD tag, the caller, the file name, or the digest length.
+
+struct ChecksumLine {
+    line: usize,
+    name: PathBuf,
+    expected: String,
+    algorithm: Algorithm,
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    use std::os::unix::ffi::OsStrExt;
+    PathBuf::from(std::ffi::OsStr::from_bytes(bytes))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+}
+
+fn is_hex(s: &[u8]) -> bool {
+    !s.is_empty() && s.iter().all(|b| b.is_ascii_hexdigit())
+}
+
+/// sha256sum escapes '\' and newlines in names when the line starts with '\'
+fn unescape_name(name: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(name.len());
+    let mut i = 0;
+    while i < name.len() {
+        match (name[i], name.get(i + 1)) {
+            (b'\\', Some(b'\\')) => {
+                out.push(b'\\');
+                i += 2;
+            }
+            (b'\\', Some(b'n')) => {
+                out.push(b'\n');
+                i += 2;
+            }
+            (b'\\', Some(b'r')) => {
+                out.push(b'\r');
+                i += 2;
+            }
+            (b, _) => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// (tag, name, digest) of one line; None for malformed lines
+fn split_line(line: &[u8], sfv: bool) -> Option<(Option<&[u8]>, Vec<u8>, &[u8])> {
+    if sfv {
+        let pos = line.iter().rposition(|&b| b == b' ' || b == b'\t')?;
+        let (name, digest) = (line[..pos].trim_ascii_end(), &line[pos + 1..]);
+        return (is_hex(digest) && !name.is_empty()).then(|| (None, name.to_vec(), digest));
+    }
+    // BSD: TAG (name) = digest
+    if let (Some(open), Some(close)) = (find(line, b" ("), rfind(line, b") = ")) {
+        if open < close && is_hex(&line[close + 4..]) && line[..open].iter().all(|b| b.is_ascii_alphanumeric() || *b == b'-') {
+            return Some((Some(&line[..open]), line[open + 2..close].to_vec(), &line[close + 4..]));
+        }
+    }
+    // GNU: digest, space, space-or-'*', name
+    let (escaped, line) = match line.strip_prefix(b"\\") {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+    let space = line.iter().position(|&b| b == b' ')?;
+    let digest = &line[..space];
+    let rest = &line[space + 1..];
+    let name = match rest.first() {
+        Some(b' ') | Some(b'*') => &rest[1..],
+        _ => rest,
+    };
+    if !is_hex(digest) || name.is_empty() {
+        return None;
+    }
+    Some((None, if escaped { unescape_name(name) } else { name.to_vec() }, digest))
+}
+
+fn find(hay: &[u8], needle: &[u8]) -> Option<usize> {
+    hay.windows(needle.len()).position(|w| w == needle)
+}
+
+fn rfind(hay: &[u8], needle: &[u8]) -> Option<usize> {
+    hay.windows(needle.len()).rposition(|w| w == needle)
+}
+
+/// Parse a checksum file; returns the entries and the numbers of malformed lines
+fn parse_checksum_file(content: &[u8], file_name: &str, forced: Option<Algorithm>) -> (Vec<ChecksumLine>, Vec<usize>) {
+    let by_name = Algorithm::from_file_name(file_name);
+    let sfv = file_name.to_ascii_lowercase().ends_with(".sfv");
+    let mut entries = Vec::new();
+    let mut malformed = Vec::new();
+    for (i, raw) in content.split(|&b| b == b'\n').enumerate() {
+        let line = raw.strip_suffix(b"\r").unwrap_or(raw);
+        if line.trim_ascii().is_empty() || line.starts_with(b"#") || (sfv && line.starts_with(b";")) {
+            continue;
+        }
+        let parsed = split_line(line, sfv).and_then(|(tag, name, digest)| {
+            let algorithm = match tag {
+                Some(t) => Algorithm::parse(&String::from_utf8_lossy(t)).ok()?,
+                None => forced.or(by_name).or_else(|| Algorithm::from_hex_len(digest.len()))?,
+            };
+            Some(ChecksumLine {
+                line: i + 1,
+                name: path_from_bytes(&name),
+                expected: String::from_utf8_lossy(digest).to_ascii_lowercase(),
+                algorithm,
+            })
+        });
+        match parsed {
+            Some(entry) => entries.push(entry),
+            None => malformed.push(i + 1),
+        }
+    }
+    (entries, malformed)
+}
+
+/// Check every file listed in a checksum file (blocking). Names are relative to the
+/// checksum file's folder. Per entry: OK, FAILED (mismatch or unreadable) or MISSING.
+/// A cancel stops early and returns what was checked so far.
+pub fn verify_checksum_file(app: Option<&tauri::AppHandle>, job_id: &str, checksum_path: &Path, forced: Option<Algorithm>) -> Result<Value, String> {
+    let start = Instant::now();
+    let content = std::fs::read(checksum_path).map_err(|e| io_error(checksum_path, e))?;
+    let file_name = checksum_path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let (entries, malformed) = parse_checksum_file(&content, &file_name, forced);
+    if entries.is_empty() {
+        return Err(format!("{}: keine gültigen Prüfsummen-Einträge gefunden", checksum_path.display()));
+    }
+    let base = checksum_path.parent().unwrap_or(Path::new("")).to_path_buf();
+    let resolve = |name: &Path| if name.is_absolute() { name.to_path_buf() } else { base.join(name) };
+    let bytes_total = entries.iter().filter_map(|e| std::fs::metadata(resolve(&e.name)).ok()).map(|m| m.len()).sum();
+
+    let mut job = start_job(app, job_id, bytes_total, entries.len() as u64)?;
+    job.progress(true);
+    let mut results: Vec<Value> = Vec::with_capacity(entries.len());
+    let (mut ok, mut failed, mut missing) = (0usize, 0usize, 0usize);
+    for entry in &entries {
+        if job.cancelled() {
+            break;
+        }
+        let path = resolve(&entry.name);
+        let listed = crate::ospath::encode(entry.name.as_os_str());
+        let mut result = json!({
+            "path": listed,
+            "line": entry.line,
+            "algorithm": entry.algorithm.as_str(),
+            "expected": entry.expected
+        });
+        match hash_with(&mut job, &path, &[entry.algorithm]) {
+            Ok(digests) => {
+                let actual = digests.into_iter().next().unwrap_or_default();
+                let matches = actual == entry.expected;
+                result["status"] = json!(if matches { "OK" } else { "FAILED" });
+                result["actual"] = json!(actual);
+                if matches { ok += 1 } else { failed += 1 }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => break,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                result["status"] = json!("MISSING");
+                missing += 1;
+            }
+            Err(e) => {
+                result["status"] = json!("FAILED");
+                result["error"] = json!(e.to_string());
+                failed += 1;
+            }
+        }
+        results.push(crate::ospath::annotate(result));
+    }
+    let cancelled = job.cancelled();
+    job.progress(true);
+    tracing::info!(path = %checksum_path.display(), ok = ok, failed = failed, missing = missing, cancelled = cancelled, "Prüfsummendatei geprüft");
+    Ok(json!({
+        "jobId": job_id,
+        "checksumFile": crate::ospath::encode(checksum_path.as_os_str()),
+        "total": entries.len(),
+        "checked": results.len(),
+        "ok": ok,
+        "failed": failed,
+        "missing": missing,
+        "malformedLines": malformed,
+        "cancelled": cancelled,
+        "results": results,
+        "elapsedMs": start.elapsed().as_millis() as u64
+    }))
+}
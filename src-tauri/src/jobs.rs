@@ -0,0 +1,137 @@
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+// Bookkeeping shared by the cancellable background jobs (hashing, archives; file jobs
+// and snapshots use the IDs): monotonic IDs, a cancel registry per job family and
+// throttled progress events.
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// `<prefix>_<n>` from a process-wide counter — timestamps collide when two jobs start
+/// in the same millisecond
+pub fn new_id(prefix: &str) -> String {
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+    format!("{}_{}", prefix, NEXT.fetch_add(1, Ordering::Relaxed))
+}
+
+type Registry = HashMap<(&'static str, String), Arc<AtomicBool>>;
+
+fn registry() -> &'static Mutex<Registry> {
+    static JOBS: OnceLock<Mutex<Registry>> = OnceLock::new();
+    JOBS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Request cancellation of a running job of `family`. False if no such job runs.
+pub fn cancel(family: &'static str, job_id: &str) -> bool {
+    match registry().lock().unwrap_or_else(|e| e.into_inner()).get(&(family, job_id.to_string())) {
+        Some(flag) => {
+            flag.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Progress + cancel state of a running job; unregisters itself when dropped
+pub struct Job<'a> {
+    family: &'static str,
+    pub job_id: &'a str,
+    app: Option<&'a tauri::AppHandle>,
+    /// Progress event name ("hash-progress", "archive-progress")
+    event: &'static str,
+    /// Sent along as `operation` if set
+    operation: Option<&'static str>,
+    cancel: Arc<AtomicBool>,
+    pub bytes_total: u64,
+    pub bytes_done: u64,
+    pub files_total: u64,
+    pub files_done: u64,
+    pub current: String,
+    last_emit: Instant,
+}
+
+impl<'a> Job<'a> {
+    /// Register a job. Fails if a job of the same family already runs under `job_id`
+    /// (IDs may be chosen by the caller).
+    pub fn start(family: &'static str, app: Option<&'a tauri::AppHandle>, job_id: &'a str, event: &'static str) -> Result<Self, String> {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let mut jobs = registry().lock().unwrap_or_else(|e| e.into_inner());
+        let key = (family, job_id.to_string());
+        if jobs.contains_key(&key) {
+            return Err(format!("Auftrags-ID '{}' wird bereits verwendet", job_id));
+        }
+        jobs.insert(key, cancel.clone());
+        Ok(Self {
+            family,
+            job_id,
+            app,
+            event,
+            operation: None,
+            cancel,
+            bytes_total: 0,
+            bytes_done: 0,
+            files_total: 0,
+            files_done: 0,
+            current: String::new(),
+            last_emit: Instant::now(),
+        })
+    }
+
+    pub fn with_operation(mut self, operation: &'static str) -> Self {
+        self.operation = Some(operation);
+        self
+    }
+
+    pub fn cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    /// `Err(message)` once cancellation was requested
+    pub fn check_cancel(&self, message: &str) -> Result<(), String> {
+        if self.cancelled() { Err(message.to_string()) } else { Ok(()) }
+    }
+
+    /// Emit the progress event — at most every 100 ms unless `force`
+    pub fn progress(&mut self, force: bool) {
+        if !force && self.last_emit.elapsed() < PROGRESS_INTERVAL {
+            return;
+        }
+        self.last_emit = Instant::now();
+        if let Some(app) = self.app {
+            let mut payload = json!({
+                "job_id": self.job_id,
+                "bytes_done": self.bytes_done,
+                "bytes_total": self.bytes_total,
+                "files_done": self.files_done,
+                "files_total": self.files_total,
+                "current_path": self.current
+            });
+            if let Some(op) = self.operation {
+                payload["operation"] = json!(op);
+            }
+            let _ = app.emit(self.event, payload);
+        }
+    }
+
+    /// Emit any other event of the job (completion, errors)
+    pub fn emit(&self, event: &str, payload: &Value) {
+        if let Some(app) = self.app {
+            let _ = app.emit(event, payload);
+        }
+    }
+}
+
+impl Drop for Job<'_> {
+    fn drop(&mut self) {
+        let mut jobs = registry().lock().unwrap_or_else(|e| e.into_inner());
+        let key = (self.family, self.job_id.to_string());
+        // Only our own registration — never a job that reused the ID later
+        if jobs.get(&key).is_some_and(|flag| Arc::ptr_eq(flag, &self.cancel)) {
+            jobs.remove(&key);
+        }
+    }
+}
@@ -1,9 +1,25 @@
+mod archive;
 mod commands;
+mod duplicates;
+mod fileops;
+mod hashing;
+mod jobs;
+mod ospath;
 mod oui;
+mod paging;
+mod pathkey;
 mod ps;
 mod scan;
+mod scan_archive;
+mod scan_checkpoint;
+mod scan_diff;
+mod scan_estimate;
 mod scan_history;
+mod scheduler;
+#[cfg(target_os = "linux")]
+mod trash;
 mod undo;
+mod watcher;
 
 use tauri::{Emitter, Manager};
 
@@ -146,6 +162,9 @@ pub fn run() {
 
             tracing::info!("App gestartet, Menüleiste erstellt");
 
+            // Geplante Hintergrund-Scans (Zeitpläne aus den Einstellungen)
+            scheduler::start(app.handle().clone());
+
             // Gespeicherten Global Hotkey beim Start registrieren
             let appdata = std::env::var("APPDATA").unwrap_or_else(|_| ".".to_string());
             let data_dir = std::path::PathBuf::from(appdata).join("speicher-analyse");
@@ -210,6 +229,13 @@ pub fn run() {
             commands::get_drives,
             commands::start_scan,
             commands::cancel_scan,
+            commands::resume_scan,
+            commands::get_scan_checkpoints,
+            commands::discard_scan_checkpoint,
+            commands::start_estimate_scan,
+            commands::get_estimate_tree,
+            commands::refine_estimate,
+            commands::cancel_refine_estimate,
             // Tree Data
             commands::get_tree_node,
             commands::get_treemap_data,
@@ -224,22 +250,35 @@ pub fn run() {
             commands::show_save_dialog,
             // File Management
             commands::delete_to_trash,
+            commands::list_trash,
+            commands::restore_from_trash,
+            commands::empty_trash,
             commands::delete_permanent,
             commands::create_folder,
             commands::file_rename,
             commands::file_move,
             commands::file_copy,
+            commands::start_file_job,
+            commands::cancel_file_job,
+            commands::resolve_file_conflict,
+            commands::get_file_jobs,
             commands::file_properties,
             commands::file_properties_general,
             commands::file_properties_security,
             commands::file_properties_details,
             commands::file_properties_versions,
             commands::file_properties_hash,
+            commands::hash_file,
+            commands::verify_checksum_file,
+            commands::cancel_hash,
             commands::open_file,
             commands::show_in_explorer,
             commands::run_as_admin,
             commands::extract_archive,
             commands::create_archive,
+            commands::cancel_archive_job,
+            commands::list_archive,
+            commands::read_archive_entry,
             // Context Menu
             commands::show_context_menu,
             // Dialog
@@ -250,6 +289,11 @@ pub fn run() {
             commands::start_duplicate_scan,
             commands::cancel_duplicate_scan,
             commands::get_size_duplicates,
+            commands::get_duplicate_results,
+            commands::export_duplicate_results,
+            commands::start_cross_duplicate_scan,
+            commands::cancel_cross_duplicate_scan,
+            commands::get_cross_duplicate_results,
             // Memory
             commands::release_scan_bulk_data,
             // Cleanup
@@ -388,6 +432,8 @@ pub fn run() {
             // Undo-Log
             commands::get_undo_log,
             commands::undo_action,
+            commands::redo_action,
+            commands::group_undo_entries,
             commands::clear_undo_log,
             // PDF Editor
             commands::pdf_get_info,
@@ -408,10 +454,19 @@ pub fn run() {
             commands::save_scan_snapshot,
             commands::get_scan_history,
             commands::compare_scans,
+            commands::diff_scans,
             commands::get_storage_trend,
+            commands::get_storage_anomalies,
             commands::quick_change_check,
+            commands::get_scan_schedule_status,
+            commands::run_scheduled_scan_now,
+            commands::start_scan_watch,
+            commands::stop_scan_watch,
+            commands::get_scan_watch_status,
             commands::delete_scan_snapshot,
             commands::clear_scan_history,
+            commands::export_scan_history,
+            commands::import_scan_history,
         ])
         .run(tauri::generate_context!())
         .expect("Fehler beim Starten der Anwendung");
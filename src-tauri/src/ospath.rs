@@ -0,0 +1,176 @@
+use serde_json::Value;
+use std::borrow::Cow;
+use std::ffi::{OsStr, OsString};
+use std::fmt::Write;
+use std::path::PathBuf;
+
+// Lossless path strings.
+// File names are OS strings: arbitrary bytes on Unix, UTF-16 with possibly unpaired
+// surrogates on Windows. Paths are carried as `String` everywhere (scan data, JSON,
+// undo log), so the parts that are not valid Unicode are escaped:
+//   Unix:    invalid byte       → NUL + 2 hex digits   ("\0E9")
+//   Windows: unpaired surrogate → NUL + 'u' + 4 hex    ("\0uD800")
+// NUL cannot occur in a file name on any platform, so the escape is unambiguous and
+// valid UTF-8 names (the normal case) stay exactly as they are. Separators are never
+// escaped — `std::path` helpers keep working on the encoded form.
+
+const ESC: char = '\0';
+
+/// Exact string form of an OS path (see module comment)
+pub fn encode(os: &OsStr) -> String {
+    if let Some(s) = os.to_str() {
+        return s.to_string();
+    }
+    encode_lossless(os)
+}
+
+#[cfg(unix)]
+fn encode_lossless(os: &OsStr) -> String {
+    use std::os::unix::ffi::OsStrExt;
+    let mut rest = os.as_bytes();
+    let mut out = String::with_capacity(rest.len() + 8);
+    loop {
+        match std::str::from_utf8(rest) {
+            Ok(s) => {
+                out.push_str(s);
+                return out;
+            }
+            Err(e) => {
+                let (valid, after) = rest.split_at(e.valid_up_to());
+                out.push_str(std::str::from_utf8(valid).unwrap_or_default());
+                let bad = e.error_len().unwrap_or(after.len());
+                for b in &after[..bad] {
+                    let _ = write!(out, "{}{:02X}", ESC, b);
+                }
+                rest = &after[bad..];
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+fn encode_lossless(os: &OsStr) -> String {
+    use std::os::windows::ffi::OsStrExt;
+    let mut out = String::new();
+    for r in char::decode_utf16(os.encode_wide()) {
+        match r {
+            Ok(c) => out.push(c),
+            Err(e) => {
+                let _ = write!(out, "{}u{:04X}", ESC, e.unpaired_surrogate());
+            }
+        }
+    }
+    out
+}
+
+/// True if `s` carries escaped (non-Unicode) parts
+pub fn is_escaped(s: &str) -> bool {
+    s.contains(ESC)
+}
+
+fn hex(s: &str) -> Option<u16> {
+    if s.bytes().all(|b| b.is_ascii_hexdigit()) { u16::from_str_radix(s, 16).ok() } else { None }
+}
+
+/// Back to the exact OS path. Plain strings are taken as they are.
+pub fn decode(s: &str) -> PathBuf {
+    if !is_escaped(s) {
+        return PathBuf::from(s);
+    }
+    PathBuf::from(decode_os(s))
+}
+
+#[cfg(unix)]
+fn decode_os(s: &str) -> OsString {
+    use std::os::unix::ffi::OsStringExt;
+    let mut out: Vec<u8> = Vec::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(pos) = rest.find(ESC) {
+        out.extend_from_slice(rest[..pos].as_bytes());
+        let seq = &rest[pos + 1..];
+        match seq.get(..2).and_then(hex) {
+            Some(b) => {
+                out.push(b as u8);
+                rest = &seq[2..];
+            }
+            // Not one of ours — keep it literally
+            None => {
+                out.push(0);
+                rest = seq;
+            }
+        }
+    }
+    out.extend_from_slice(rest.as_bytes());
+    OsString::from_vec(out)
+}
+
+#[cfg(windows)]
+fn decode_os(s: &str) -> OsString {
+    use std::os::windows::ffi::OsStringExt;
+    let mut out: Vec<u16> = Vec::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(pos) = rest.find(ESC) {
+        out.extend(rest[..pos].encode_utf16());
+        let seq = &rest[pos + 1..];
+        match seq.strip_prefix('u').and_then(|h| h.get(..4)).and_then(hex) {
+            Some(unit) => {
+                out.push(unit);
+                rest = &seq[5..];
+            }
+            None => {
+                out.push(0);
+                rest = seq;
+            }
+        }
+    }
+    out.extend(rest.encode_utf16());
+    OsString::from_wide(&out)
+}
+
+/// The OS bytes of a path string: the bytes as they are on Unix, UTF-16LE on Windows
+pub fn raw_bytes(s: &str) -> Vec<u8> {
+    let path = decode(s);
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        path.as_os_str().as_bytes().to_vec()
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::OsStrExt;
+        path.as_os_str().encode_wide().flat_map(u16::to_le_bytes).collect()
+    }
+}
+
+/// Display-safe form: escaped parts become U+FFFD (like `to_string_lossy`)
+pub fn display(s: &str) -> Cow<'_, str> {
+    if !is_escaped(s) {
+        return Cow::Borrowed(s);
+    }
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(pos) = rest.find(ESC) {
+        out.push_str(&rest[..pos]);
+        let seq = &rest[pos + 1..];
+        let len = if seq.starts_with('u') { 5 } else { 2 };
+        out.push(char::REPLACEMENT_CHARACTER);
+        rest = seq.get(len..).unwrap_or("");
+    }
+    out.push_str(rest);
+    Cow::Owned(out)
+}
+
+/// Add `displayPath` / `displayName` next to `path` / `name` of a result object
+/// when they carry escaped parts — the exact form stays in `path` for follow-up commands.
+pub fn annotate(mut v: Value) -> Value {
+    if let Some(obj) = v.as_object_mut() {
+        for (key, display_key) in [("path", "displayPath"), ("name", "displayName")] {
+            let shown = match obj.get(key).and_then(|p| p.as_str()) {
+                Some(p) if is_escaped(p) => display(p).into_owned(),
+                _ => continue,
+            };
+            obj.insert(display_key.to_string(), Value::String(shown));
+        }
+    }
+    v
+}
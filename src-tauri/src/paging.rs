@@ -0,0 +1,179 @@
+use serde_json::{json, Value};
+use std::cmp::Ordering;
+
+/// Upper bound for one page — the UI scrolls, it never needs 80 000 rows at once
+const MAX_PAGE_SIZE: usize = 5000;
+const DEFAULT_PAGE_SIZE: usize = 200;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortKey {
+    Size,
+    Name,
+    Modified,
+    Path,
+}
+
+impl SortKey {
+    fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "size" => Ok(Self::Size),
+            "name" => Ok(Self::Name),
+            "modified" => Ok(Self::Modified),
+            "path" => Ok(Self::Path),
+            _ => Err(format!("Unbekannte Sortierung: {} (erlaubt: size, name, modified, path)", s)),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Size => "size",
+            Self::Name => "name",
+            Self::Modified => "modified",
+            Self::Path => "path",
+        }
+    }
+}
+
+/// Shared paging contract of all file list endpoints:
+/// `{ sortBy, sortDir, offset | cursor, limit }`
+#[derive(Clone, Debug)]
+pub struct PageRequest {
+    pub sort: SortKey,
+    pub descending: bool,
+    pub offset: usize,
+    pub limit: usize,
+    /// Opaque position from a previous page (`nextCursor`) — wins over `offset`
+    pub cursor: Option<String>,
+}
+
+impl PageRequest {
+    pub fn parse(v: &Value, default_sort: SortKey) -> Result<Self, String> {
+        let sort = match v.get("sortBy").and_then(|s| s.as_str()) {
+            Some(s) => SortKey::parse(s)?,
+            None => default_sort,
+        };
+        // Numbers: biggest/newest first; text: A–Z
+        let descending = match v.get("sortDir").and_then(|s| s.as_str()) {
+            Some("asc") => false,
+            Some("desc") => true,
+            Some(other) => return Err(format!("Unbekannte Sortierrichtung: {}", other)),
+            None => matches!(sort, SortKey::Size | SortKey::Modified),
+        };
+        Ok(Self {
+            sort,
+            descending,
+            offset: v.get("offset").and_then(|o| o.as_u64()).unwrap_or(0) as usize,
+            limit: (v.get("limit").and_then(|l| l.as_u64()).unwrap_or(DEFAULT_PAGE_SIZE as u64) as usize).clamp(1, MAX_PAGE_SIZE),
+            cursor: v.get("cursor").and_then(|c| c.as_str()).filter(|c| !c.is_empty()).map(|c| c.to_string()),
+        })
+    }
+}
+
+/// Common view on scanned files and files inside archives
+pub struct ListItem<'a> {
+    pub path: &'a str,
+    pub name: &'a str,
+    pub size: u64,
+    pub modified_ms: i64,
+    pub extension: &'a str,
+}
+
+impl<'a> From<&'a crate::scan::FileEntry> for ListItem<'a> {
+    fn from(f: &'a crate::scan::FileEntry) -> Self {
+        Self { path: &f.path, name: &f.name, size: f.size, modified_ms: f.modified_ms, extension: &f.extension }
+    }
+}
+
+impl<'a> From<&'a crate::scan_archive::ArchiveEntry> for ListItem<'a> {
+    fn from(f: &'a crate::scan_archive::ArchiveEntry) -> Self {
+        Self { path: &f.path, name: &f.name, size: f.size, modified_ms: f.modified_ms, extension: &f.extension }
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
+enum SortValue {
+    Num(i64),
+    Text(String),
+}
+
+fn sort_value(item: &ListItem, key: SortKey) -> SortValue {
+    match key {
+        SortKey::Size => SortValue::Num(item.size as i64),
+        SortKey::Modified => SortValue::Num(item.modified_ms),
+        // Case-insensitive like the Explorer
+        SortKey::Name => SortValue::Text(item.name.to_lowercase()),
+        SortKey::Path => SortValue::Text(item.path.to_lowercase()),
+    }
+}
+
+/// Total order: sort value in the requested direction, path as tie-breaker
+/// (always ascending) so a cursor position is unambiguous
+fn compare(a: (&SortValue, &str), b: (&SortValue, &str), descending: bool) -> Ordering {
+    let o = a.0.cmp(b.0);
+    let o = if descending { o.reverse() } else { o };
+    o.then_with(|| a.1.cmp(b.1))
+}
+
+fn encode_cursor(key: SortKey, value: &SortValue, path: &str) -> String {
+    use base64::Engine;
+    let v = match value {
+        SortValue::Num(n) => json!(n),
+        SortValue::Text(t) => json!(t),
+    };
+    let raw = json!({ "k": key.as_str(), "v": v, "p": path }).to_string();
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(raw)
+}
+
+fn decode_cursor(cursor: &str, key: SortKey) -> Result<(SortValue, String), String> {
+    use base64::Engine;
+    let invalid = || "Ungültiger Cursor".to_string();
+    let raw = base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+    let v: Value = serde_json::from_slice(&raw).map_err(|_| invalid())?;
+    if v.get("k").and_then(|k| k.as_str()) != Some(key.as_str()) {
+        return Err("Cursor gehört zu einer anderen Sortierung".to_string());
+    }
+    let value = match (key, v.get("v")) {
+        (SortKey::Size | SortKey::Modified, Some(n)) => SortValue::Num(n.as_i64().ok_or_else(invalid)?),
+        (_, Some(t)) => SortValue::Text(t.as_str().ok_or_else(invalid)?.to_string()),
+        (_, None) => return Err(invalid()),
+    };
+    let path = v.get("p").and_then(|p| p.as_str()).ok_or_else(invalid)?.to_string();
+    Ok((value, path))
+}
+
+/// Sort all matches, cut out one page and report the exact total.
+/// Returns `{ items, total, offset, limit, hasMore, nextCursor, sortBy, sortDir }`.
+pub fn page_items<'a>(items: Vec<ListItem<'a>>, req: &PageRequest, to_json: impl Fn(&ListItem<'a>) -> Value) -> Result<Value, String> {
+    let mut keyed: Vec<(SortValue, ListItem<'a>)> = items.into_iter()
+        .map(|i| (sort_value(&i, req.sort), i))
+        .collect();
+    keyed.sort_unstable_by(|a, b| compare((&a.0, a.1.path), (&b.0, b.1.path), req.descending));
+
+    let total = keyed.len();
+    let start = match &req.cursor {
+        Some(c) => {
+            let (value, path) = decode_cursor(c, req.sort)?;
+            // First item strictly after the cursor — stable even if files were added/removed
+            keyed.partition_point(|x| compare((&x.0, x.1.path), (&value, path.as_str()), req.descending) != Ordering::Greater)
+        }
+        None => req.offset.min(total),
+    };
+    let end = (start + req.limit).min(total);
+    let page = &keyed[start..end];
+    let next_cursor = if end < total {
+        page.last().map(|(v, i)| encode_cursor(req.sort, v, i.path))
+    } else {
+        None
+    };
+
+    Ok(json!({
+        "items": page.iter().map(|(_, i)| to_json(i)).collect::<Vec<_>>(),
+        "total": total,
+        "offset": start,
+        "limit": req.limit,
+        "hasMore": end < total,
+        "nextCursor": next_cursor,
+        "sortBy": req.sort.as_str(),
+        "sortDir": if req.descending { "desc" } else { "asc" }
+    }))
+}
@@ -0,0 +1,170 @@
+// Index keys and path helpers of the scan index.
+// Separators come from `std::path` (`\` and `/` on Windows, `/` elsewhere);
+// whether names are case-folded is decided per filesystem of the scanned root.
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+/// Separator used when building virtual paths (archive contents)
+pub const SEP: char = std::path::MAIN_SEPARATOR;
+
+const RULE_UNKNOWN: u8 = 0;
+const RULE_SENSITIVE: u8 = 1;
+const RULE_INSENSITIVE: u8 = 2;
+
+/// Case rule of the active scan (the one the directory index belongs to)
+static ACTIVE_RULE: AtomicU8 = AtomicU8::new(RULE_UNKNOWN);
+
+/// Default when the filesystem cannot be probed: NTFS/FAT and APFS/HFS+ (default setup)
+/// are case-insensitive, Linux filesystems are not
+fn platform_default() -> bool {
+    cfg!(any(windows, target_os = "macos"))
+}
+
+fn rule_cache() -> &'static Mutex<HashMap<String, bool>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, bool>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Whether names below `root` are case-insensitive. Probed once per root and cached.
+pub fn case_insensitive(root: &str) -> bool {
+    if let Some(&rule) = rule_cache().lock().unwrap_or_else(|e| e.into_inner()).get(root) {
+        return rule;
+    }
+    let rule = probe_case_insensitive(Path::new(root)).unwrap_or_else(platform_default);
+    tracing::debug!(root = %root, case_insensitive = rule, "Groß-/Kleinschreibung des Dateisystems ermittelt");
+    rule_cache().lock().unwrap_or_else(|e| e.into_inner()).insert(root.to_string(), rule);
+    rule
+}
+
+/// Make `root`'s case rule the one used for index keys (called when the index is rebuilt)
+pub fn set_active_root(root: &str) {
+    let rule = if case_insensitive(root) { RULE_INSENSITIVE } else { RULE_SENSITIVE };
+    ACTIVE_RULE.store(rule, Ordering::Relaxed);
+}
+
+pub fn active_case_insensitive() -> bool {
+    match ACTIVE_RULE.load(Ordering::Relaxed) {
+        RULE_SENSITIVE => false,
+        RULE_INSENSITIVE => true,
+        _ => platform_default(),
+    }
+}
+
+fn flip_case(name: &str) -> Option<String> {
+    let upper = name.to_uppercase();
+    let flipped = if upper != name { upper } else { name.to_lowercase() };
+    (flipped != name).then_some(flipped)
+}
+
+/// Look up an entry under a case-flipped name: if it resolves to the same file,
+/// the directory it lives in folds case.
+fn probe_case_insensitive(root: &Path) -> Option<bool> {
+    // Entries inside root are governed by root's filesystem — try those first,
+    // then fall back to root itself and its ancestors.
+    let inside = std::fs::read_dir(root).ok()
+        .into_iter()
+        .flatten()
+        .flatten()
+        .take(64)
+        .map(|e| e.path());
+    for candidate in inside.chain(root.ancestors().map(Path::to_path_buf)) {
+        let name = match candidate.file_name().and_then(|n| n.to_str()) {
+            Some(n) => n,
+            None => continue,
+        };
+        let flipped = match flip_case(name) {
+            Some(f) => candidate.with_file_name(f),
+            None => continue,
+        };
+        let original = match std::fs::symlink_metadata(&candidate) {
+            Ok(m) => m,
+            Err(_) => continue,
+        };
+        return Some(match std::fs::symlink_metadata(&flipped) {
+            Ok(other) => same_file(&candidate, &original, &flipped, &other),
+            Err(_) => false,
+        });
+    }
+    None
+}
+
+#[cfg(unix)]
+fn same_file(_a: &Path, a: &std::fs::Metadata, _b: &Path, b: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    a.dev() == b.dev() && a.ino() == b.ino()
+}
+
+#[cfg(not(unix))]
+fn same_file(a: &Path, _: &std::fs::Metadata, b: &Path, _: &std::fs::Metadata) -> bool {
+    // Canonical paths carry the on-disk spelling — equal means one and the same entry
+    // (directories with per-directory case sensitivity hold two distinct entries)
+    match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        (Ok(x), Ok(y)) => x == y,
+        _ => false,
+    }
+}
+
+/// Whether two existing paths name one and the same entry (hard links, case variants)
+pub fn same_entry(a: &Path, b: &Path) -> bool {
+    match (std::fs::symlink_metadata(a), std::fs::symlink_metadata(b)) {
+        (Ok(x), Ok(y)) => same_file(a, &x, b, &y),
+        _ => false,
+    }
+}
+
+/// Strip trailing separators, but keep a bare root (`/`) intact
+fn trim_sep(s: &str) -> &str {
+    let trimmed = s.trim_end_matches(std::path::is_separator);
+    if trimmed.is_empty() { s } else { trimmed }
+}
+
+/// Index key of a path: components re-joined with the platform separator (redundant
+/// separators and `.` dropped), no trailing separator, case-folded if `fold_case`.
+/// `C:\Users\` → `c:\users` on Windows, `/home/a/` → `/home/a` on Linux.
+pub fn key_with(path: &str, fold_case: bool) -> String {
+    let mut buf = PathBuf::new();
+    for c in Path::new(path).components() {
+        if c != Component::CurDir {
+            buf.push(c.as_os_str());
+        }
+    }
+    let joined = buf.to_string_lossy();
+    let key = trim_sep(&joined);
+    if fold_case { key.to_lowercase() } else { key.to_string() }
+}
+
+/// Index key using the active scan's case rule
+pub fn key(path: &str) -> String {
+    key_with(path, active_case_insensitive())
+}
+
+/// Parent directory in key form (no trailing separator); None for a root
+pub fn parent(path: &str) -> Option<&str> {
+    let p = Path::new(path).parent()?.to_str()?;
+    if p.is_empty() { None } else { Some(trim_sep(p)) }
+}
+
+/// Last path component (empty for a root)
+pub fn file_name(path: &str) -> &str {
+    Path::new(path).file_name().and_then(|n| n.to_str()).unwrap_or("")
+}
+
+/// Number of named components (`C:\a\b` and `/a/b` both have depth 2)
+pub fn depth(path: &str) -> usize {
+    Path::new(path).components().filter(|c| matches!(c, Component::Normal(_))).count()
+}
+
+/// `path` lies strictly below `ancestor` (whole components, not string prefixes)
+pub fn is_below(path: &str, ancestor: &str) -> bool {
+    let (p, a) = (Path::new(path), Path::new(ancestor));
+    p != a && p.starts_with(a)
+}
+
+/// Whether two paths name the same location under `a`'s case rule
+pub fn same_path(a: &str, b: &str) -> bool {
+    let fold = case_insensitive(a);
+    key_with(a, fold) == key_with(b, fold)
+}
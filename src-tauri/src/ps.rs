@@ -29,6 +29,16 @@ fn safe_truncate(s: &str, max_bytes: usize) -> &str {
 /// Run a PowerShell script with configurable timeout and return stdout as String.
 /// Uses UTF-8 output encoding. CREATE_NO_WINDOW prevents visible console windows.
 pub async fn run_ps_with_timeout(script: &str, timeout_secs: u64) -> Result<String, String> {
+    run_ps_inner(script, timeout_secs, None).await
+}
+
+/// Run a PowerShell script that refers to `path` as `$env:SA_TARGET_PATH`. The variable
+/// carries the exact OS string — script text cannot hold names that are not valid Unicode.
+pub async fn run_ps_with_path(script: &str, path: &std::path::Path) -> Result<String, String> {
+    run_ps_inner(script, 30, Some(path)).await
+}
+
+async fn run_ps_inner(script: &str, timeout_secs: u64, path: Option<&std::path::Path>) -> Result<String, String> {
     let preview = script_preview(script);
     tracing::debug!(script = %preview, timeout_s = timeout_secs, "PowerShell starte");
 
@@ -46,6 +56,9 @@ pub async fn run_ps_with_timeout(script: &str, timeout_secs: u64) -> Result<Stri
         .stdin(Stdio::null())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
+    if let Some(path) = path {
+        cmd.env("SA_TARGET_PATH", path);
+    }
 
     #[cfg(windows)]
     cmd.creation_flags(CREATE_NO_WINDOW);
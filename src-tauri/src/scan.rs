@@ -2,7 +2,7 @@ use std::sync::{Mutex, OnceLock};
 use std::collections::HashMap;
 use serde_json::{json, Value};
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct FileEntry {
     pub path: String,
     pub name: String,
@@ -18,6 +18,8 @@ pub struct ScanData {
     pub dirs_scanned: u64,
     pub total_size: u64,
     pub elapsed_seconds: f64,
+    /// Archive listings (only filled in archive scan mode)
+    pub archives: Vec<crate::scan_archive::ArchiveListing>,
 }
 
 fn store() -> &'static Mutex<HashMap<String, ScanData>> {
@@ -44,7 +46,8 @@ pub fn save(data: ScanData) {
         );
     }
 
-    build_dir_index(&data.files);
+    build_dir_index(&data.scan_id, &data.root_path, &data.files);
+    crate::scan_archive::build_index(&data.archives);
     let mut s = store().lock().unwrap_or_else(|e| e.into_inner());
     // Vorherige Scans desselben Root-Pfads entfernen (statt s.clear())
     let root = data.root_path.clone();
@@ -65,6 +68,86 @@ where
     s.get(scan_id).map(f)
 }
 
+/// Mutable access to a single scan (watcher updates)
+pub fn with_scan_mut<F, R>(scan_id: &str, f: F) -> Option<R>
+where
+    F: FnOnce(&mut ScanData) -> R,
+{
+    let mut s = store().lock().unwrap_or_else(|e| e.into_inner());
+    s.get_mut(scan_id).map(f)
+}
+
+/// Build a FileEntry from a path + its metadata (same fields as start_scan collects)
+pub fn file_entry(path: &std::path::Path, meta: &std::fs::Metadata) -> FileEntry {
+    // Exact (lossless) strings — non-UTF-8 names must still address the right file
+    let name = path.file_name().map(crate::ospath::encode).unwrap_or_default();
+    let extension = std::path::Path::new(&name)
+        .extension()
+        .map(|x| format!(".{}", x.to_string_lossy().to_lowercase()))
+        .unwrap_or_default();
+    let modified_ms = meta.modified().ok()
+        .map(|t| t.duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_millis() as i64)
+        .unwrap_or(0);
+    FileEntry {
+        path: crate::ospath::encode(path.as_os_str()),
+        name,
+        size: meta.len(),
+        modified_ms,
+        extension,
+    }
+}
+
+/// Counts of an incremental update
+#[derive(Default, Debug)]
+pub struct ApplyStats {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+}
+
+/// Apply filesystem changes to a scan in place: `removed` are file or directory paths
+/// (directories remove everything below them), `upserts` are new or changed files.
+/// The directory index is updated incrementally instead of being rebuilt — but only if it
+/// was built from `scan_id`; another scan's index must not pick up these files.
+pub fn apply_changes(scan_id: &str, removed: &[String], upserts: Vec<FileEntry>) -> Option<ApplyStats> {
+    with_scan_mut(scan_id, |data| {
+        let mut stats = ApplyStats::default();
+        let removed_keys: Vec<String> = removed.iter().map(|p| normalize_dir_key(p)).collect();
+        let upsert_keys: std::collections::HashSet<String> = upserts.iter().map(|f| normalize_dir_key(&f.path)).collect();
+
+        // Owner checked under the index lock — build_dir_index swaps both in that order
+        let mut idx_guard = dir_index().lock().unwrap_or_else(|e| e.into_inner());
+        let mut idx = dir_index_belongs_to(scan_id).then_some(&mut *idx_guard);
+        let mut kept: Vec<FileEntry> = Vec::with_capacity(data.files.len() + upserts.len());
+        for f in data.files.drain(..) {
+            let key = normalize_dir_key(&f.path);
+            let is_removed = removed_keys.iter().any(|r| key == *r || crate::pathkey::is_below(&key, r));
+            if is_removed || upsert_keys.contains(&key) {
+                if let Some(idx) = idx.as_deref_mut() {
+                    index_remove_file(idx, &f.path, f.size);
+                }
+                data.total_size = data.total_size.saturating_sub(f.size);
+                if upsert_keys.contains(&key) {
+                    stats.updated += 1;
+                } else {
+                    stats.removed += 1;
+                }
+            } else {
+                kept.push(f);
+            }
+        }
+        stats.added = upserts.len() - stats.updated;
+        for f in upserts {
+            if let Some(idx) = idx.as_deref_mut() {
+                index_add_file(idx, &f.path, f.size);
+            }
+            data.total_size += f.size;
+            kept.push(f);
+        }
+        data.files = kept;
+        stats
+    })
+}
 /// File category from extension
 pub fn file_category(ext: &str) -> &'static str {
     match ext {
@@ -95,7 +178,7 @@ pub fn top_files(scan_id: &str, limit: usize) -> Value {
         indices.sort_by(|&a, &b| data.files[b].size.cmp(&data.files[a].size));
         let files: Vec<Value> = indices.iter().take(limit).map(|&i| {
             let f = &data.files[i];
-            json!({"path": f.path, "name": f.name, "size": f.size, "modified": f.modified_ms, "extension": f.extension})
+            crate::ospath::annotate(json!({"path": f.path, "name": f.name, "size": f.size, "modified": f.modified_ms, "extension": f.extension}))
         }).collect();
         json!(files)
     }).unwrap_or(json!([]))
@@ -103,17 +186,34 @@ pub fn top_files(scan_id: &str, limit: usize) -> Value {
 
 /// Get file type statistics — returns flat array [{extension, category, count, total_size}]
 /// Frontend (charts.js) iterates this directly and groups by category
-pub fn file_types(scan_id: &str) -> Value {
+/// With `include_archives`, files inside archives are counted too (uncompressed size)
+/// and reported separately as archive_count / archive_size per extension.
+pub fn file_types(scan_id: &str, include_archives: bool) -> Value {
     with_scan(scan_id, |data| {
-        let mut exts: HashMap<&str, (u64, u64)> = HashMap::new();
+        let mut exts: HashMap<&str, (u64, u64, u64, u64)> = HashMap::new();
         for f in &data.files {
             let ext = if f.extension.is_empty() { "(keine)" } else { &f.extension };
-            let e = exts.entry(ext).or_insert((0, 0));
+            let e = exts.entry(ext).or_insert((0, 0, 0, 0));
             e.0 += 1;
             e.1 += f.size;
         }
-        let mut ext_list: Vec<Value> = exts.iter().map(|(ext, (count, size))| {
-            json!({"extension": ext, "category": file_category(ext), "count": count, "total_size": size})
+        if include_archives {
+            for f in data.archives.iter().flat_map(|a| a.entries.iter()) {
+                let ext = if f.extension.is_empty() { "(keine)" } else { &f.extension };
+                let e = exts.entry(ext).or_insert((0, 0, 0, 0));
+                e.0 += 1;
+                e.1 += f.size;
+                e.2 += 1;
+                e.3 += f.size;
+            }
+        }
+        let mut ext_list: Vec<Value> = exts.iter().map(|(ext, (count, size, archive_count, archive_size))| {
+            if include_archives {
+                json!({"extension": ext, "category": file_category(ext), "count": count, "total_size": size,
+                       "archive_count": archive_count, "archive_size": archive_size})
+            } else {
+                json!({"extension": ext, "category": file_category(ext), "count": count, "total_size": size})
+            }
         }).collect();
         ext_list.sort_by(|a, b| b["total_size"].as_u64().unwrap_or(0).cmp(&a["total_size"].as_u64().unwrap_or(0)));
         json!(ext_list)
@@ -124,18 +224,33 @@ pub fn file_types(scan_id: &str) -> Value {
 pub fn search_files(scan_id: &str, query: &str, min_size: u64) -> Value {
     let query_lower = query.to_lowercase();
     with_scan(scan_id, |data| {
-        let results: Vec<Value> = data.files.iter()
+        let mut results: Vec<Value> = data.files.iter()
             .filter(|f| f.name.to_lowercase().contains(&query_lower) && f.size >= min_size)
             .take(500)
             .map(|f| {
-                let dir_path = f.path.rfind('\\').map(|i| &f.path[..i]).unwrap_or("");
-                json!({
+                let dir_path = crate::pathkey::parent(&f.path).unwrap_or("");
+                crate::ospath::annotate(json!({
                     "name": f.name, "dirPath": dir_path, "path": f.path,
                     "isDir": false, "matchQuality": 1.0,
                     "size": f.size, "modified": f.modified_ms, "extension": f.extension
-                })
+                }))
             })
             .collect();
+        // Files inside archives (archive scan mode) fill up the remaining slots
+        for a in &data.archives {
+            for f in a.entries.iter().filter(|f| f.name.to_lowercase().contains(&query_lower) && f.size >= min_size) {
+                if results.len() >= 500 {
+                    break;
+                }
+                let dir_path = crate::pathkey::parent(&f.path).unwrap_or("");
+                results.push(json!({
+                    "name": f.name, "dirPath": dir_path, "path": f.path,
+                    "isDir": false, "matchQuality": 1.0,
+                    "size": f.size, "modified": f.modified_ms, "extension": f.extension,
+                    "inArchive": a.archive_path, "compressedSize": f.compressed_size
+                }));
+            }
+        }
         json!(results)
     }).unwrap_or(json!([]))
 }
@@ -149,7 +264,7 @@ pub fn files_by_extension(scan_id: &str, ext: &str, limit: usize) -> Value {
             .collect();
         files.sort_by(|a, b| b.size.cmp(&a.size));
         let results: Vec<Value> = files.iter().take(limit).map(|f| {
-            json!({"path": f.path, "name": f.name, "size": f.size, "modified": f.modified_ms, "extension": f.extension})
+            crate::ospath::annotate(json!({"path": f.path, "name": f.name, "size": f.size, "modified": f.modified_ms, "extension": f.extension}))
         }).collect();
         json!(results)
     }).unwrap_or(json!([]))
@@ -163,7 +278,7 @@ pub fn files_by_category(scan_id: &str, category: &str, limit: usize) -> Value {
             .collect();
         files.sort_by(|a, b| b.size.cmp(&a.size));
         let results: Vec<Value> = files.iter().take(limit).map(|f| {
-            json!({"path": f.path, "name": f.name, "size": f.size, "modified": f.modified_ms, "extension": f.extension})
+            crate::ospath::annotate(json!({"path": f.path, "name": f.name, "size": f.size, "modified": f.modified_ms, "extension": f.extension}))
         }).collect();
         json!(results)
     }).unwrap_or(json!([]))
@@ -182,16 +297,98 @@ pub fn old_files(scan_id: &str, threshold_days: u32, min_size: u64) -> Value {
         let total_count = files.len();
         let results: Vec<Value> = files.iter().take(500).map(|f| {
             let age_days = ((now_ms - f.modified_ms) as f64 / 86_400_000.0).round() as i64;
-            json!({
+            crate::ospath::annotate(json!({
                 "path": f.path, "name": f.name, "size": f.size,
                 "modified": f.modified_ms, "extension": f.extension,
                 "ageDays": age_days, "context": "", "contextIcon": ""
-            })
+            }))
         }).collect();
         json!({"totalCount": total_count, "totalSize": total_size, "files": results})
     }).unwrap_or(json!({"totalCount": 0, "totalSize": 0, "files": []}))
 }
 
+// === Paged list endpoints (shared contract, see paging.rs) ===
+
+fn file_json(f: &crate::paging::ListItem) -> Value {
+    crate::ospath::annotate(json!({"path": f.path, "name": f.name, "size": f.size, "modified": f.modified_ms, "extension": f.extension}))
+}
+
+/// All files, one page at a time
+pub fn top_files_page(scan_id: &str, req: &crate::paging::PageRequest) -> Result<Value, String> {
+    with_scan(scan_id, |data| {
+        let items = data.files.iter().map(Into::into).collect();
+        crate::paging::page_items(items, req, file_json)
+    }).unwrap_or_else(|| Err("Scan-Daten nicht gefunden".to_string()))
+}
+
+/// Name search over files and (archive scan mode) files inside archives
+pub fn search_files_page(scan_id: &str, query: &str, min_size: u64, req: &crate::paging::PageRequest) -> Result<Value, String> {
+    let query_lower = query.to_lowercase();
+    with_scan(scan_id, |data| {
+        let matches = |name: &str, size: u64| size >= min_size && name.to_lowercase().contains(&query_lower);
+        let mut items: Vec<crate::paging::ListItem> = data.files.iter()
+            .filter(|f| matches(&f.name, f.size))
+            .map(Into::into)
+            .collect();
+        items.extend(data.archives.iter()
+            .flat_map(|a| a.entries.iter())
+            .filter(|f| matches(&f.name, f.size))
+            .map(Into::into));
+        crate::paging::page_items(items, req, |f| {
+            let dir_path = crate::pathkey::parent(&f.path).unwrap_or("");
+            let in_archive = data.archives.iter()
+                .find(|a| crate::pathkey::is_below(f.path, &a.archive_path))
+                .map(|a| a.archive_path.as_str());
+            crate::ospath::annotate(json!({
+                "name": f.name, "dirPath": dir_path, "path": f.path,
+                "isDir": false, "matchQuality": 1.0,
+                "size": f.size, "modified": f.modified_ms, "extension": f.extension,
+                "inArchive": in_archive
+            }))
+        })
+    }).unwrap_or_else(|| Err("Scan-Daten nicht gefunden".to_string()))
+}
+
+pub fn files_by_extension_page(scan_id: &str, ext: &str, req: &crate::paging::PageRequest) -> Result<Value, String> {
+    let ext_lower = if ext.starts_with('.') { ext.to_lowercase() } else { format!(".{}", ext.to_lowercase()) };
+    with_scan(scan_id, |data| {
+        let items = data.files.iter().filter(|f| f.extension == ext_lower).map(Into::into).collect();
+        crate::paging::page_items(items, req, file_json)
+    }).unwrap_or_else(|| Err("Scan-Daten nicht gefunden".to_string()))
+}
+
+pub fn files_by_category_page(scan_id: &str, category: &str, req: &crate::paging::PageRequest) -> Result<Value, String> {
+    with_scan(scan_id, |data| {
+        let items = data.files.iter().filter(|f| file_category(&f.extension) == category).map(Into::into).collect();
+        crate::paging::page_items(items, req, file_json)
+    }).unwrap_or_else(|| Err("Scan-Daten nicht gefunden".to_string()))
+}
+
+/// Old files page — additionally reports totalSize of all matches
+pub fn old_files_page(scan_id: &str, threshold_days: u32, min_size: u64, req: &crate::paging::PageRequest) -> Result<Value, String> {
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let threshold_ms = threshold_days as i64 * 86_400_000;
+    with_scan(scan_id, |data| {
+        let items: Vec<crate::paging::ListItem> = data.files.iter()
+            .filter(|f| f.modified_ms > 0 && (now_ms - f.modified_ms) > threshold_ms && f.size >= min_size)
+            .map(Into::into)
+            .collect();
+        let total_size: u64 = items.iter().map(|f| f.size).sum();
+        let mut page = crate::paging::page_items(items, req, |f| {
+            let age_days = ((now_ms - f.modified_ms) as f64 / 86_400_000.0).round() as i64;
+            crate::ospath::annotate(json!({
+                "path": f.path, "name": f.name, "size": f.size,
+                "modified": f.modified_ms, "extension": f.extension,
+                "ageDays": age_days, "context": "", "contextIcon": ""
+            }))
+        })?;
+        if let Some(obj) = page.as_object_mut() {
+            obj.insert("totalSize".to_string(), json!(total_size));
+        }
+        Ok(page)
+    }).unwrap_or_else(|| Err("Scan-Daten nicht gefunden".to_string()))
+}
+
 /// Get folder sizes bulk — O(1) per folder via directory index
 pub fn folder_sizes_bulk(_scan_id: &str, folder_paths: &[String]) -> Value {
     let idx = dir_index().lock().unwrap_or_else(|e| e.into_inner());
@@ -208,17 +405,26 @@ pub fn folder_sizes_bulk(_scan_id: &str, folder_paths: &[String]) -> Value {
 pub fn export_csv(scan_id: &str) -> String {
     with_scan(scan_id, |data| {
         let mut lines = Vec::with_capacity(data.files.len() + 1);
-        lines.push("Pfad;Name;Größe (Bytes);Extension;Kategorie".to_string());
+        // Path/name in display form; non-Unicode names additionally exact in "Rohpfad"
+        // (Base64 of the OS bytes — UTF-16LE on Windows, see ospath::raw_bytes)
+        lines.push("Pfad;Name;Größe (Bytes);Extension;Kategorie;Rohpfad".to_string());
         let mut sorted: Vec<&FileEntry> = data.files.iter().collect();
         sorted.sort_by(|a, b| b.size.cmp(&a.size));
         for f in sorted {
             let cat = file_category(&f.extension);
-            lines.push(format!("\"{}\";\"{}\";\"{}\";\"{}\";\"{}\"",
-                f.path.replace('"', "\"\""),
-                f.name.replace('"', "\"\""),
+            let raw = if crate::ospath::is_escaped(&f.path) {
+                use base64::Engine;
+                base64::engine::general_purpose::STANDARD.encode(crate::ospath::raw_bytes(&f.path))
+            } else {
+                String::new()
+            };
+            lines.push(format!("\"{}\";\"{}\";\"{}\";\"{}\";\"{}\";\"{}\"",
+                crate::ospath::display(&f.path).replace('"', "\"\""),
+                crate::ospath::display(&f.name).replace('"', "\"\""),
                 f.size,
                 f.extension,
-                cat
+                cat,
+                raw
             ));
         }
         lines.join("\n")
@@ -234,33 +440,37 @@ pub fn tree_node(_scan_id: &str, path: &str, _depth: u32) -> Value {
         Some(entry) => {
             let mut children: Vec<Value> = entry.children.iter().filter_map(|child_path| {
                 let child = idx.get(child_path)?;
-                let name = child_path.rsplit('\\').next().unwrap_or(child_path);
-                Some(json!({
+                let name = crate::pathkey::file_name(child_path);
+                Some(crate::ospath::annotate(json!({
                     "path": child_path,
                     "name": name,
                     "size": child.total_size,
                     "isDir": true,
                     "dir_count": child.children.len(),
                     "file_count": child.total_file_count
-                }))
+                })))
             }).collect();
 
+            // Archives are listed as (virtual) folders — not as own files of this folder
+            let (archive_children, archive_bytes) = crate::scan_archive::archive_children(path);
+            children.extend(archive_children);
             children.sort_unstable_by(|a, b| {
                 b["size"].as_u64().unwrap_or(0).cmp(&a["size"].as_u64().unwrap_or(0))
             });
 
             json!({
                 "path": path,
-                "name": path.rsplit('\\').next().unwrap_or(path),
+                "name": crate::pathkey::file_name(path),
                 "size": entry.total_size,
-                "own_size": entry.own_size,
+                "own_size": entry.own_size.saturating_sub(archive_bytes),
                 "dir_count": entry.children.len(),
                 "file_count": entry.total_file_count,
                 "is_own_files": false,
                 "children": children
             })
         }
-        None => json!({"path": path, "name": "", "size": 0, "dir_count": 0, "file_count": 0, "children": []})
+        None => crate::scan_archive::tree_node(path)
+            .unwrap_or_else(|| json!({"path": path, "name": "", "size": 0, "dir_count": 0, "file_count": 0, "children": []}))
     }
 }
 
@@ -293,15 +503,15 @@ pub fn save_to_disk(data_dir: &std::path::Path) -> Result<Value, String> {
 
     // Write file entries as zstd-compressed bincode (~5-8x smaller than raw bincode)
     let zst_path = data_dir.join("scan-data.zst");
-    let file = std::fs::File::create(&zst_path).map_err(|e| format!("scan-data.zst erstellen: {}", e))?;
-    let zst_writer = zstd::Encoder::new(file, 3)
-        .map_err(|e| format!("zstd-Encoder erstellen: {}", e))?;
-    let mut buf_writer = std::io::BufWriter::with_capacity(512 * 1024, zst_writer);
-    bincode::serialize_into(&mut buf_writer, &data.files)
-        .map_err(|e| format!("scan-data.zst schreiben: {}", e))?;
-    // Finish zstd stream (flush + write footer)
-    let zst_writer = buf_writer.into_inner().map_err(|e| format!("BufWriter flush: {}", e))?;
-    zst_writer.finish().map_err(|e| format!("zstd finalize: {}", e))?;
+    write_file_list(&zst_path, &data.files)?;
+
+    // Archive listings (archive scan mode only)
+    let archives_path = data_dir.join("scan-archives.zst");
+    if data.archives.is_empty() {
+        let _ = std::fs::remove_file(&archives_path);
+    } else if let Err(e) = crate::scan_archive::write_listings(&archives_path, &data.archives) {
+        tracing::warn!(error = %e, "Archiv-Inhalte konnten nicht persistiert werden");
+    }
 
     // Remove old formats (migration cleanup)
     let _ = std::fs::remove_file(data_dir.join("scan-data.bin"));
@@ -320,6 +530,33 @@ pub fn save_to_disk(data_dir: &std::path::Path) -> Result<Value, String> {
     Ok(meta)
 }
 
+/// Write a file list as zstd-compressed bincode (format of scan-data.zst)
+pub fn write_file_list(path: &std::path::Path, files: &[FileEntry]) -> Result<(), String> {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let file = std::fs::File::create(path).map_err(|e| format!("{} erstellen: {}", name, e))?;
+    let zst_writer = zstd::Encoder::new(file, 3)
+        .map_err(|e| format!("zstd-Encoder erstellen: {}", e))?;
+    let mut buf_writer = std::io::BufWriter::with_capacity(512 * 1024, zst_writer);
+    bincode::serialize_into(&mut buf_writer, files)
+        .map_err(|e| format!("{} schreiben: {}", name, e))?;
+    // Finish zstd stream (flush + write footer)
+    let zst_writer = buf_writer.into_inner().map_err(|e| format!("BufWriter flush: {}", e))?;
+    zst_writer.finish().map_err(|e| format!("zstd finalize: {}", e))?;
+    Ok(())
+}
+
+/// Read a file list written by `write_file_list`
+pub fn read_file_list(path: &std::path::Path) -> Result<Vec<FileEntry>, String> {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let file = std::fs::File::open(path)
+        .map_err(|e| format!("{} öffnen: {}", name, e))?;
+    let zst_reader = zstd::Decoder::new(file)
+        .map_err(|e| format!("zstd-Decoder: {}", e))?;
+    let buf_reader = std::io::BufReader::with_capacity(512 * 1024, zst_reader);
+    bincode::deserialize_from(buf_reader)
+        .map_err(|e| format!("{} parsen: {}", name, e))
+}
+
 /// Load scan data from disk into memory store (zstd → bincode → JSON fallback)
 pub fn load_from_disk(data_dir: &std::path::Path) -> Result<Value, String> {
     let start = std::time::Instant::now();
@@ -340,14 +577,7 @@ pub fn load_from_disk(data_dir: &std::path::Path) -> Result<Value, String> {
     let json_path = data_dir.join("scan-data.json");
 
     let (files, format): (Vec<FileEntry>, &str) = if zst_path.exists() {
-        let file = std::fs::File::open(&zst_path)
-            .map_err(|e| format!("scan-data.zst öffnen: {}", e))?;
-        let zst_reader = zstd::Decoder::new(file)
-            .map_err(|e| format!("zstd-Decoder: {}", e))?;
-        let buf_reader = std::io::BufReader::with_capacity(512 * 1024, zst_reader);
-        let loaded = bincode::deserialize_from(buf_reader)
-            .map_err(|e| format!("scan-data.zst parsen: {}", e))?;
-        (loaded, "zstd")
+        (read_file_list(&zst_path)?, "zstd")
     } else if bin_path.exists() {
         let file = std::fs::File::open(&bin_path)
             .map_err(|e| format!("scan-data.bin öffnen: {}", e))?;
@@ -370,6 +600,16 @@ pub fn load_from_disk(data_dir: &std::path::Path) -> Result<Value, String> {
     let scan_id = meta["scan_id"].as_str().unwrap_or("restored").to_string();
     let root_path = meta["root_path"].as_str().unwrap_or("").to_string();
 
+    let archives_path = data_dir.join("scan-archives.zst");
+    let archives = if archives_path.exists() {
+        crate::scan_archive::read_listings(&archives_path).unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "Archiv-Inhalte konnten nicht geladen werden");
+            Vec::new()
+        })
+    } else {
+        Vec::new()
+    };
+
     // save() also builds the directory index
     save(ScanData {
         scan_id: scan_id.clone(),
@@ -378,6 +618,7 @@ pub fn load_from_disk(data_dir: &std::path::Path) -> Result<Value, String> {
         dirs_scanned: meta["dirs_scanned"].as_u64().unwrap_or(0),
         total_size: meta["total_size"].as_u64().unwrap_or(0),
         elapsed_seconds: meta["elapsed_seconds"].as_f64().unwrap_or(0.0),
+        archives,
     });
 
     let total_ms = start.elapsed().as_millis();
@@ -418,23 +659,139 @@ struct DirIndexEntry {
     children: Vec<String>, // direct subdirectory paths
 }
 
+impl DirIndexEntry {
+    fn empty() -> Self {
+        Self { total_size: 0, own_size: 0, own_file_count: 0, total_file_count: 0, children: Vec::new() }
+    }
+}
+
 fn dir_index() -> &'static Mutex<HashMap<String, DirIndexEntry>> {
     static INDEX: OnceLock<Mutex<HashMap<String, DirIndexEntry>>> = OnceLock::new();
     INDEX.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
-fn normalize_dir_key(path: &str) -> String {
-    let p = path.replace('/', "\\");
-    let trimmed = p.trim_end_matches('\\');
-    // Windows is case-insensitive — normalize to lowercase for consistent lookups
-    if trimmed.is_empty() { p.to_lowercase() } else { trimmed.to_lowercase() }
+/// Scan the active directory index was built from — the index is global, the store
+/// may hold several scans
+fn index_owner() -> &'static Mutex<Option<String>> {
+    static OWNER: OnceLock<Mutex<Option<String>>> = OnceLock::new();
+    OWNER.get_or_init(|| Mutex::new(None))
 }
 
-/// Build directory index from flat file list. Single pass O(N) to accumulate sizes,
-/// then bottom-up pass to compute totals. Called automatically by save().
-fn build_dir_index(files: &[FileEntry]) {
-    use std::collections::HashSet;
+/// Whether the active directory index describes `scan_id`
+pub fn dir_index_belongs_to(scan_id: &str) -> bool {
+    index_owner().lock().unwrap_or_else(|e| e.into_inner()).as_deref() == Some(scan_id)
+}
+
+/// Flat copy of the directory index: (normalized path, total_size, total_file_count) per directory.
+/// Used by scan history to keep a compact per-directory snapshot.
+pub fn dir_index_totals() -> Vec<(String, u64, u64)> {
+    let idx = dir_index().lock().unwrap_or_else(|e| e.into_inner());
+    idx.iter()
+        .map(|(path, e)| (path.clone(), e.total_size, e.total_file_count as u64))
+        .collect()
+}
+
+/// Index key of a directory — separators and case folding follow the active scan's
+/// filesystem (see pathkey.rs)
+pub fn normalize_dir_key(path: &str) -> String {
+    crate::pathkey::key(path)
+}
+
+/// Incremental index update: add one file — creates missing directories up to the root
+fn index_add_file(idx: &mut HashMap<String, DirIndexEntry>, file_path: &str, size: u64) {
+    let parent = match crate::pathkey::parent(file_path) {
+        Some(p) => normalize_dir_key(p),
+        None => return,
+    };
+    let own = idx.entry(parent.clone()).or_insert_with(DirIndexEntry::empty);
+    own.own_size += size;
+    own.own_file_count += 1;
+
+    let mut path = parent;
+    loop {
+        let entry = idx.entry(path.clone()).or_insert_with(DirIndexEntry::empty);
+        entry.total_size += size;
+        entry.total_file_count += 1;
+        match crate::pathkey::parent(&path) {
+            Some(p) => {
+                let parent_path = p.to_string();
+                let parent_entry = idx.entry(parent_path.clone()).or_insert_with(DirIndexEntry::empty);
+                if let Err(i) = parent_entry.children.binary_search(&path) {
+                    parent_entry.children.insert(i, path.clone());
+                }
+                path = parent_path;
+            }
+            _ => break,
+        }
+    }
+}
+
+/// Incremental index update: remove one file — drops directories that become empty
+fn index_remove_file(idx: &mut HashMap<String, DirIndexEntry>, file_path: &str, size: u64) {
+    let parent = match crate::pathkey::parent(file_path) {
+        Some(p) => normalize_dir_key(p),
+        None => return,
+    };
+    if let Some(own) = idx.get_mut(&parent) {
+        own.own_size = own.own_size.saturating_sub(size);
+        own.own_file_count = own.own_file_count.saturating_sub(1);
+    }
+
+    let mut path = parent;
+    loop {
+        let now_empty = match idx.get_mut(&path) {
+            Some(entry) => {
+                entry.total_size = entry.total_size.saturating_sub(size);
+                entry.total_file_count = entry.total_file_count.saturating_sub(1);
+                entry.total_file_count == 0 && entry.children.is_empty()
+            }
+            None => false,
+        };
+        match crate::pathkey::parent(&path) {
+            Some(p) => {
+                let parent_path = p.to_string();
+                if now_empty {
+                    idx.remove(&path);
+                    if let Some(p) = idx.get_mut(&parent_path) {
+                        p.children.retain(|c| c != &path);
+                    }
+                }
+                path = parent_path;
+            }
+            _ => break,
+        }
+    }
+}
+
+/// Build directory index from flat file list and make it the active index.
+/// Called automatically by save().
+fn build_dir_index(scan_id: &str, root_path: &str, files: &[FileEntry]) {
     let start = std::time::Instant::now();
+    crate::pathkey::set_active_root(root_path);
+    let entries = compute_dir_index(files, crate::pathkey::active_case_insensitive());
+    let count = entries.len();
+    let mut idx = dir_index().lock().unwrap_or_else(|e| e.into_inner());
+    *idx = entries;
+    *index_owner().lock().unwrap_or_else(|e| e.into_inner()) = Some(scan_id.to_string());
+
+    tracing::debug!(
+        directories = count,
+        elapsed_ms = start.elapsed().as_millis(),
+        "Verzeichnis-Index erstellt"
+    );
+}
+
+/// (path, total_size, total_files) per directory of a file list that is not the active
+/// scan — background scans must not replace the index the UI is browsing
+pub fn dir_totals_of(root_path: &str, files: &[FileEntry]) -> Vec<(String, u64, u64)> {
+    compute_dir_index(files, crate::pathkey::case_insensitive(root_path)).into_iter()
+        .map(|(path, e)| (path, e.total_size, e.total_file_count as u64))
+        .collect()
+}
+
+/// Single pass O(N) to accumulate sizes, then bottom-up pass to compute totals
+fn compute_dir_index(files: &[FileEntry], fold_case: bool) -> HashMap<String, DirIndexEntry> {
+    use std::collections::HashSet;
 
     let estimated_dirs = files.len() / 5;
     let mut own_sizes: HashMap<String, u64> = HashMap::with_capacity(estimated_dirs);
@@ -442,10 +799,10 @@ fn build_dir_index(files: &[FileEntry]) {
     let mut child_sets: HashMap<String, HashSet<String>> = HashMap::with_capacity(estimated_dirs);
 
     // Phase 1: Accumulate own_size per directory + register parent→child
-    // All keys are lowercased for case-insensitive lookups (Windows filesystem)
+    // Keys are case-folded only on case-insensitive filesystems (see pathkey.rs)
     for file in files {
-        let parent = match file.path.rfind('\\') {
-            Some(pos) => file.path[..pos].to_lowercase(),
+        let parent = match crate::pathkey::parent(&file.path) {
+            Some(p) => crate::pathkey::key_with(p, fold_case),
             None => continue,
         };
 
@@ -455,9 +812,9 @@ fn build_dir_index(files: &[FileEntry]) {
         // Walk up to register parent→child relationships (early exit on duplicate)
         let mut child_path = parent;
         loop {
-            match child_path.rfind('\\') {
-                Some(pos) if pos >= 2 => {
-                    let parent_path = child_path[..pos].to_string();
+            match crate::pathkey::parent(&child_path) {
+                Some(p) => {
+                    let parent_path = p.to_string();
                     let set = child_sets.entry(parent_path.clone()).or_default();
                     if !set.insert(child_path.clone()) {
                         break; // Already registered — all ancestors are too
@@ -491,7 +848,7 @@ fn build_dir_index(files: &[FileEntry]) {
     // Phase 3: Bottom-up — compute total_size and total_file_count (deepest dirs first)
     let mut sorted_paths: Vec<String> = entries.keys().cloned().collect();
     sorted_paths.sort_unstable_by(|a, b| {
-        b.matches('\\').count().cmp(&a.matches('\\').count())
+        crate::pathkey::depth(b).cmp(&crate::pathkey::depth(a))
     });
 
     let mut totals: HashMap<String, (u64, usize)> = HashMap::with_capacity(sorted_paths.len());
@@ -515,14 +872,5 @@ fn build_dir_index(files: &[FileEntry]) {
         }
     }
 
-    let count = entries.len();
-    let elapsed = start.elapsed();
-    let mut idx = dir_index().lock().unwrap_or_else(|e| e.into_inner());
-    *idx = entries;
-
-    tracing::debug!(
-        directories = count,
-        elapsed_ms = elapsed.as_millis(),
-        "Verzeichnis-Index erstellt"
-    );
+    entries
 }
@@ -0,0 +1,304 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use crate::pathkey::SEP;
+use crate::scan::{normalize_dir_key, FileEntry};
+
+/// Listings stop here — a pathological archive must not exhaust memory
+const MAX_ENTRIES_PER_ARCHIVE: usize = 1_000_000;
+
+/// A file inside an archive. `path` is virtual: `<archive path>\<entry path>`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ArchiveEntry {
+    pub path: String,
+    pub name: String,
+    pub extension: String,
+    pub size: u64,
+    /// Exact for zip, equal to size for tar, proportional share of the archive for compressed tars
+    pub compressed_size: u64,
+    pub modified_ms: i64,
+}
+
+/// Headers of one archive read during the scan (no extraction)
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ArchiveListing {
+    pub archive_path: String,
+    /// "zip" | "tar" | "tar.gz" | "tar.xz" | "tar.zst"
+    pub format: String,
+    pub size_on_disk: u64,
+    pub entries: Vec<ArchiveEntry>,
+    pub truncated: bool,
+    pub error: Option<String>,
+}
+
+impl ArchiveListing {
+    pub fn total_size(&self) -> u64 {
+        self.entries.iter().map(|e| e.size).sum()
+    }
+}
+
+/// Supported archive format by file name
+pub fn archive_format(name: &str) -> Option<&'static str> {
+    crate::archive::Format::from_name(name).map(|f| f.as_str())
+}
+
+fn entry_for(archive_path: &str, inner: &str, size: u64, compressed_size: u64, modified_ms: i64) -> Option<ArchiveEntry> {
+    // Archive member names use `/` (some Windows tools write `\`) — map to the platform separator
+    let inner: String = inner.chars().map(|c| if c == '/' || c == '\\' { SEP } else { c }).collect();
+    let inner = inner.trim_matches(SEP);
+    if inner.is_empty() {
+        return None;
+    }
+    let name = inner.rsplit(SEP).next().unwrap_or(inner).to_string();
+    let extension = std::path::Path::new(&name)
+        .extension()
+        .map(|x| format!(".{}", x.to_string_lossy().to_lowercase()))
+        .unwrap_or_default();
+    Some(ArchiveEntry {
+        path: format!("{}{}{}", archive_path.trim_end_matches(SEP), SEP, inner),
+        name,
+        extension,
+        size,
+        compressed_size,
+        modified_ms,
+    })
+}
+
+/// Read the listing of one archive. Errors are kept in the listing (the scan goes on).
+pub fn read_listing(path: &str, size_on_disk: u64) -> Option<ArchiveListing> {
+    let format = archive_format(path)?;
+    // Headers only, via the archive engine — sizes come from the zip directory / tar headers
+    let result = crate::archive::list(&crate::ospath::decode(path), MAX_ENTRIES_PER_ARCHIVE).map(|(_, members, truncated)| {
+        let entries = members.iter()
+            .filter(|m| m.kind == crate::archive::EntryKind::File)
+            .filter_map(|m| entry_for(path, &m.name, m.size, m.compressed_size.unwrap_or(m.size), m.modified_ms))
+            .collect::<Vec<_>>();
+        (entries, truncated)
+    });
+
+    let (mut entries, truncated, error) = match result {
+        Ok((e, t)) => (e, t, None),
+        Err(e) => {
+            tracing::debug!(archive = %path, error = %e, "Archiv konnte nicht gelesen werden");
+            (Vec::new(), false, Some(e))
+        }
+    };
+    if !matches!(format, "zip" | "tar") {
+        // No per-entry compressed size in a compressed stream — distribute the archive size
+        let total: u64 = entries.iter().map(|e| e.size).sum();
+        if total > 0 {
+            for e in &mut entries {
+                e.compressed_size = (e.size as f64 / total as f64 * size_on_disk as f64).round() as u64;
+            }
+        }
+    }
+    Some(ArchiveListing {
+        archive_path: path.to_string(),
+        format: format.to_string(),
+        size_on_disk,
+        entries,
+        truncated,
+        error,
+    })
+}
+
+/// Read all supported archives of a finished scan. `on_progress(done, total, path)`;
+/// `cancelled` skips the remaining archives.
+pub fn scan_archives(files: &[FileEntry], cancelled: &dyn Fn() -> bool, on_progress: &mut dyn FnMut(usize, usize, &str)) -> Vec<ArchiveListing> {
+    let start = std::time::Instant::now();
+    let candidates: Vec<&FileEntry> = files.iter().filter(|f| archive_format(&f.name).is_some()).collect();
+    let mut listings = Vec::with_capacity(candidates.len());
+    for (i, f) in candidates.iter().enumerate() {
+        if cancelled() {
+            tracing::info!(done = i, total = candidates.len(), "Archiv-Auswertung abgebrochen");
+            break;
+        }
+        on_progress(i, candidates.len(), &f.path);
+        if let Some(listing) = read_listing(&f.path, f.size) {
+            listings.push(listing);
+        }
+    }
+    tracing::info!(
+        archives = listings.len(),
+        entries = listings.iter().map(|l| l.entries.len()).sum::<usize>(),
+        elapsed_ms = start.elapsed().as_millis(),
+        "Archive als virtuelle Ordner eingelesen"
+    );
+    listings
+}
+
+/// Persist listings next to scan-data.zst (zstd-compressed bincode)
+pub fn write_listings(path: &std::path::Path, listings: &[ArchiveListing]) -> Result<(), String> {
+    let file = std::fs::File::create(path).map_err(|e| format!("scan-archives.zst erstellen: {}", e))?;
+    let zst_writer = zstd::Encoder::new(file, 3).map_err(|e| format!("zstd-Encoder erstellen: {}", e))?;
+    let mut buf_writer = std::io::BufWriter::with_capacity(512 * 1024, zst_writer);
+    bincode::serialize_into(&mut buf_writer, listings).map_err(|e| format!("scan-archives.zst schreiben: {}", e))?;
+    let zst_writer = buf_writer.into_inner().map_err(|e| format!("BufWriter flush: {}", e))?;
+    zst_writer.finish().map_err(|e| format!("zstd finalize: {}", e))?;
+    Ok(())
+}
+
+pub fn read_listings(path: &std::path::Path) -> Result<Vec<ArchiveListing>, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("scan-archives.zst öffnen: {}", e))?;
+    let zst_reader = zstd::Decoder::new(file).map_err(|e| format!("zstd-Decoder: {}", e))?;
+    let buf_reader = std::io::BufReader::with_capacity(512 * 1024, zst_reader);
+    bincode::deserialize_from(buf_reader).map_err(|e| format!("scan-archives.zst parsen: {}", e))
+}
+
+// ============================================================
+// Virtual directory index — archives as folders in tree_node
+// ============================================================
+
+#[derive(Default)]
+struct VirtualDir {
+    total_size: u64,
+    total_compressed: u64,
+    own_size: u64,
+    total_file_count: usize,
+    children: Vec<String>,
+}
+
+struct ArchiveSummary {
+    path: String,
+    format: String,
+    size_on_disk: u64,
+    total_size: u64,
+    file_count: usize,
+    truncated: bool,
+    error: Option<String>,
+}
+
+#[derive(Default)]
+struct ArchiveIndex {
+    /// Virtual directories (archive roots + folders inside), keyed like the directory index
+    dirs: HashMap<String, VirtualDir>,
+    /// Real directory key → archives directly inside it
+    by_parent: HashMap<String, Vec<ArchiveSummary>>,
+}
+
+fn archive_index() -> &'static Mutex<ArchiveIndex> {
+    static INDEX: OnceLock<Mutex<ArchiveIndex>> = OnceLock::new();
+    INDEX.get_or_init(|| Mutex::new(ArchiveIndex::default()))
+}
+
+fn parent_key(path: &str) -> Option<String> {
+    crate::pathkey::parent(path).map(normalize_dir_key)
+}
+
+/// Rebuild the virtual index for the active scan (called by scan::save)
+pub fn build_index(listings: &[ArchiveListing]) {
+    let mut index = ArchiveIndex::default();
+    for l in listings {
+        let root_key = normalize_dir_key(&l.archive_path);
+        index.dirs.entry(root_key.clone()).or_default();
+
+        for e in &l.entries {
+            let parent = match parent_key(&e.path) {
+                Some(p) => p,
+                None => continue,
+            };
+            let own = index.dirs.entry(parent.clone()).or_default();
+            own.own_size += e.size;
+            // Totals up to (and including) the archive root, children links on the way
+            let mut dir = parent;
+            loop {
+                let d = index.dirs.entry(dir.clone()).or_default();
+                d.total_size += e.size;
+                d.total_compressed += e.compressed_size;
+                d.total_file_count += 1;
+                if dir == root_key {
+                    break;
+                }
+                let up = match crate::pathkey::parent(&dir) {
+                    Some(p) => p.to_string(),
+                    None => break,
+                };
+                let parent_dir = index.dirs.entry(up.clone()).or_default();
+                if let Err(i) = parent_dir.children.binary_search(&dir) {
+                    parent_dir.children.insert(i, dir.clone());
+                }
+                dir = up;
+            }
+        }
+
+        if let Some(p) = parent_key(&l.archive_path) {
+            index.by_parent.entry(p).or_default().push(ArchiveSummary {
+                path: l.archive_path.clone(),
+                format: l.format.clone(),
+                size_on_disk: l.size_on_disk,
+                total_size: l.total_size(),
+                file_count: l.entries.len(),
+                truncated: l.truncated,
+                error: l.error.clone(),
+            });
+        }
+    }
+    *archive_index().lock().unwrap_or_else(|e| e.into_inner()) = index;
+}
+
+fn ratio(compressed: u64, size: u64) -> f64 {
+    if size == 0 { 1.0 } else { ((compressed as f64 / size as f64) * 1000.0).round() / 1000.0 }
+}
+
+/// Archives inside a real directory as tree children, plus their on-disk total
+pub fn archive_children(dir_path: &str) -> (Vec<Value>, u64) {
+    let index = archive_index().lock().unwrap_or_else(|e| e.into_inner());
+    let archives = match index.by_parent.get(&normalize_dir_key(dir_path)) {
+        Some(a) => a,
+        None => return (Vec::new(), 0),
+    };
+    let on_disk: u64 = archives.iter().map(|a| a.size_on_disk).sum();
+    let children = archives.iter().map(|a| json!({
+        "path": a.path,
+        "name": crate::pathkey::file_name(&a.path),
+        "size": a.size_on_disk,
+        "isDir": true,
+        "isArchive": true,
+        "format": a.format,
+        "uncompressed_size": a.total_size,
+        "compression_ratio": ratio(a.size_on_disk, a.total_size),
+        "dir_count": index.dirs.get(&normalize_dir_key(&a.path)).map(|d| d.children.len()).unwrap_or(0),
+        "file_count": a.file_count,
+        "truncated": a.truncated,
+        "error": a.error
+    })).collect();
+    (children, on_disk)
+}
+
+/// Tree node for a path inside an archive (None if it is not virtual)
+pub fn tree_node(path: &str) -> Option<Value> {
+    let index = archive_index().lock().unwrap_or_else(|e| e.into_inner());
+    let entry = index.dirs.get(&normalize_dir_key(path))?;
+    let mut children: Vec<Value> = entry.children.iter().filter_map(|child_path| {
+        let child = index.dirs.get(child_path)?;
+        Some(json!({
+            "path": child_path,
+            "name": crate::pathkey::file_name(child_path),
+            "size": child.total_size,
+            "compressed_size": child.total_compressed,
+            "compression_ratio": ratio(child.total_compressed, child.total_size),
+            "isDir": true,
+            "isVirtual": true,
+            "dir_count": child.children.len(),
+            "file_count": child.total_file_count
+        }))
+    }).collect();
+    children.sort_unstable_by(|a, b| {
+        b["size"].as_u64().unwrap_or(0).cmp(&a["size"].as_u64().unwrap_or(0))
+    });
+    Some(json!({
+        "path": path,
+        "name": crate::pathkey::file_name(path),
+        "size": entry.total_size,
+        "compressed_size": entry.total_compressed,
+        "compression_ratio": ratio(entry.total_compressed, entry.total_size),
+        "own_size": entry.own_size,
+        "dir_count": entry.children.len(),
+        "file_count": entry.total_file_count,
+        "is_own_files": false,
+        "isVirtual": true,
+        "children": children
+    }))
+}
@@ -0,0 +1,198 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::commands::WalkState;
+use crate::scan::FileEntry;
+
+/// Checkpoints live next to the scan data: <id>.json (summary for listing only) +
+/// <id>.zst (queue, partial file list and the counters that belong to it)
+const CHECKPOINT_DIR: &str = "scan-checkpoints";
+/// How often a running scan writes its traversal state
+pub const INTERVAL: Duration = Duration::from_secs(60);
+
+/// Summary of a resumable scan (listed without loading the partial file list)
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointMeta {
+    pub scan_id: String,
+    pub root_path: String,
+    pub dirs_scanned: u64,
+    pub files_found: u64,
+    pub total_size: u64,
+    pub errors_count: u64,
+    pub pending_dirs: usize,
+    pub elapsed_seconds: f64,
+    pub updated_ms: i64,
+    /// Scan option carried over to the resumed run
+    #[serde(default)]
+    pub include_archives: bool,
+}
+
+/// Counters travel with the file list they describe — the meta file is written by a
+/// second rename and may be one checkpoint behind after a crash
+#[derive(Serialize)]
+struct CheckpointDataRef<'a> {
+    pending: &'a [String],
+    files: &'a [FileEntry],
+    dirs_scanned: u64,
+    total_size: u64,
+    errors_count: u64,
+}
+
+#[derive(Deserialize)]
+struct CheckpointData {
+    pending: Vec<String>,
+    files: Vec<FileEntry>,
+    dirs_scanned: u64,
+    total_size: u64,
+    errors_count: u64,
+}
+
+fn checkpoint_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(CHECKPOINT_DIR)
+}
+
+/// Scan IDs come from the frontend and become file names
+fn check_id(scan_id: &str) -> Result<(), String> {
+    if crate::scan_history::is_safe_id(scan_id) {
+        Ok(())
+    } else {
+        Err(format!("Ungültige Scan-ID: {}", scan_id))
+    }
+}
+
+fn meta_path(data_dir: &Path, scan_id: &str) -> PathBuf {
+    checkpoint_dir(data_dir).join(format!("{}.json", scan_id))
+}
+
+fn data_path(data_dir: &Path, scan_id: &str) -> PathBuf {
+    checkpoint_dir(data_dir).join(format!("{}.zst", scan_id))
+}
+
+/// Write the traversal state atomically (tmp + rename) — a crash while writing
+/// must leave the previous checkpoint intact.
+pub fn save(data_dir: &Path, scan_id: &str, root_path: &str, state: &WalkState, elapsed_seconds: f64, include_archives: bool) -> Result<(), String> {
+    check_id(scan_id)?;
+    let start = std::time::Instant::now();
+    let dir = checkpoint_dir(data_dir);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Checkpoint-Verzeichnis erstellen: {}", e))?;
+
+    let data_file = data_path(data_dir, scan_id);
+    let tmp = data_file.with_extension("zst.tmp");
+    {
+        let file = std::fs::File::create(&tmp).map_err(|e| format!("Checkpoint erstellen: {}", e))?;
+        let zst_writer = zstd::Encoder::new(file, 1)
+            .map_err(|e| format!("zstd-Encoder erstellen: {}", e))?;
+        let mut buf_writer = std::io::BufWriter::with_capacity(512 * 1024, zst_writer);
+        bincode::serialize_into(&mut buf_writer, &CheckpointDataRef {
+            pending: &state.pending,
+            files: &state.files,
+            dirs_scanned: state.dirs_scanned,
+            total_size: state.total_size,
+            errors_count: state.errors_count,
+        })
+            .map_err(|e| format!("Checkpoint schreiben: {}", e))?;
+        let zst_writer = buf_writer.into_inner().map_err(|e| format!("BufWriter flush: {}", e))?;
+        let file = zst_writer.finish().map_err(|e| format!("zstd finalize: {}", e))?;
+        file.sync_all().map_err(|e| format!("Checkpoint sync: {}", e))?;
+    }
+    std::fs::rename(&tmp, &data_file).map_err(|e| format!("Checkpoint ersetzen: {}", e))?;
+
+    let meta = CheckpointMeta {
+        scan_id: scan_id.to_string(),
+        root_path: root_path.to_string(),
+        dirs_scanned: state.dirs_scanned,
+        files_found: state.files.len() as u64,
+        total_size: state.total_size,
+        errors_count: state.errors_count,
+        pending_dirs: state.pending.len(),
+        elapsed_seconds,
+        updated_ms: chrono::Utc::now().timestamp_millis(),
+        include_archives,
+    };
+    let json = serde_json::to_string_pretty(&meta).map_err(|e| e.to_string())?;
+    let meta_file = meta_path(data_dir, scan_id);
+    let meta_tmp = meta_file.with_extension("json.tmp");
+    std::fs::write(&meta_tmp, json).map_err(|e| format!("Checkpoint-Info schreiben: {}", e))?;
+    std::fs::rename(&meta_tmp, &meta_file).map_err(|e| format!("Checkpoint-Info ersetzen: {}", e))?;
+
+    tracing::debug!(
+        scan_id = %scan_id,
+        files = state.files.len(),
+        pending = state.pending.len(),
+        elapsed_ms = start.elapsed().as_millis(),
+        "Scan-Checkpoint geschrieben"
+    );
+    Ok(())
+}
+
+/// Load a checkpoint back into a walk state
+pub fn load(data_dir: &Path, scan_id: &str) -> Result<(CheckpointMeta, WalkState), String> {
+    check_id(scan_id)?;
+    let meta_str = std::fs::read_to_string(meta_path(data_dir, scan_id))
+        .map_err(|_| "Kein Checkpoint für diesen Scan vorhanden".to_string())?;
+    let meta: CheckpointMeta = serde_json::from_str(&meta_str)
+        .map_err(|e| format!("Checkpoint-Info parsen: {}", e))?;
+
+    let file = std::fs::File::open(data_path(data_dir, scan_id))
+        .map_err(|e| format!("Checkpoint öffnen: {}", e))?;
+    let zst_reader = zstd::Decoder::new(file)
+        .map_err(|e| format!("zstd-Decoder: {}", e))?;
+    let buf_reader = std::io::BufReader::with_capacity(512 * 1024, zst_reader);
+    let data: CheckpointData = bincode::deserialize_from(buf_reader)
+        .map_err(|e| format!("Checkpoint beschädigt: {}", e))?;
+
+    let state = WalkState {
+        pending: data.pending,
+        files: data.files,
+        dirs_scanned: data.dirs_scanned,
+        total_size: data.total_size,
+        errors_count: data.errors_count,
+    };
+    // The data file is authoritative — report its numbers, not a possibly stale summary
+    let meta = CheckpointMeta {
+        dirs_scanned: state.dirs_scanned,
+        files_found: state.files.len() as u64,
+        total_size: state.total_size,
+        errors_count: state.errors_count,
+        pending_dirs: state.pending.len(),
+        ..meta
+    };
+    Ok((meta, state))
+}
+
+/// All resumable scans, newest first
+pub fn list(data_dir: &Path) -> Vec<CheckpointMeta> {
+    let entries = match std::fs::read_dir(checkpoint_dir(data_dir)) {
+        Ok(e) => e,
+        Err(_) => return Vec::new(),
+    };
+    let mut metas: Vec<CheckpointMeta> = entries.flatten()
+        .filter(|e| e.path().extension().map(|x| x == "json").unwrap_or(false))
+        .filter_map(|e| std::fs::read_to_string(e.path()).ok())
+        .filter_map(|s| serde_json::from_str::<CheckpointMeta>(&s).ok())
+        .filter(|m| data_path(data_dir, &m.scan_id).exists())
+        .collect();
+    metas.sort_by(|a, b| b.updated_ms.cmp(&a.updated_ms));
+    metas
+}
+
+pub fn remove(data_dir: &Path, scan_id: &str) -> bool {
+    if check_id(scan_id).is_err() {
+        return false;
+    }
+    let had_meta = std::fs::remove_file(meta_path(data_dir, scan_id)).is_ok();
+    let had_data = std::fs::remove_file(data_path(data_dir, scan_id)).is_ok();
+    had_meta || had_data
+}
+
+/// Drop every checkpoint of `root_path` (a completed scan makes them obsolete)
+pub fn remove_for_root(data_dir: &Path, root_path: &str) {
+    for meta in list(data_dir) {
+        if crate::pathkey::same_path(&meta.root_path, root_path) {
+            remove(data_dir, &meta.scan_id);
+            tracing::debug!(scan_id = %meta.scan_id, "Scan-Checkpoint entfernt");
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use crate::scan::FileEntry;
+
+/// A single file-level change between two persisted scans
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChange {
+    pub path: String,
+    pub name: String,
+    pub extension: String,
+    /// "added" | "deleted" | "grown" | "shrunk" | "modified" (same size, newer mtime)
+    pub change_type: &'static str,
+    pub old_size: u64,
+    pub new_size: u64,
+    pub change: i64,
+    pub old_modified: i64,
+    pub new_modified: i64,
+}
+
+/// Aggregated changes per extension or directory
+#[derive(Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSummary {
+    pub key: String,
+    pub added: u64,
+    pub deleted: u64,
+    pub grown: u64,
+    pub shrunk: u64,
+    pub modified: u64,
+    pub added_bytes: u64,
+    pub deleted_bytes: u64,
+    pub net_change: i64,
+}
+
+impl ChangeSummary {
+    fn add(&mut self, c: &FileChange) {
+        match c.change_type {
+            "added" => { self.added += 1; self.added_bytes += c.new_size; }
+            "deleted" => { self.deleted += 1; self.deleted_bytes += c.old_size; }
+            "grown" => self.grown += 1,
+            "shrunk" => self.shrunk += 1,
+            _ => self.modified += 1,
+        }
+        self.net_change += c.change;
+    }
+}
+
+pub struct ScanDiff {
+    pub older_id: String,
+    pub newer_id: String,
+    pub changes: Vec<FileChange>,
+    pub by_type: HashMap<&'static str, ChangeSummary>,
+    pub by_extension: Vec<ChangeSummary>,
+    pub by_directory: Vec<ChangeSummary>,
+}
+
+/// Last computed diff — paging through the same pair must not reload both file lists
+fn diff_cache() -> &'static Mutex<Option<ScanDiff>> {
+    static CACHE: OnceLock<Mutex<Option<ScanDiff>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+fn path_key(path: &str) -> String {
+    // Same normalization as the directory index
+    crate::scan::normalize_dir_key(path)
+}
+
+fn parent_dir(path: &str) -> &str {
+    crate::pathkey::parent(path).unwrap_or("")
+}
+
+/// Compute the file-level diff between two file lists
+pub fn diff_file_lists(older_id: &str, newer_id: &str, older: &[FileEntry], newer: &[FileEntry]) -> ScanDiff {
+    let start = std::time::Instant::now();
+    let old_map: HashMap<String, &FileEntry> = older.iter().map(|f| (path_key(&f.path), f)).collect();
+    let mut changes: Vec<FileChange> = Vec::new();
+    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::with_capacity(newer.len());
+
+    for f in newer {
+        let key = path_key(&f.path);
+        match old_map.get(&key) {
+            Some(old) => {
+                let change_type = if f.size > old.size {
+                    "grown"
+                } else if f.size < old.size {
+                    "shrunk"
+                } else if f.modified_ms > old.modified_ms {
+                    "modified"
+                } else {
+                    seen.insert(key);
+                    continue;
+                };
+                changes.push(FileChange {
+                    path: f.path.clone(), name: f.name.clone(), extension: f.extension.clone(),
+                    change_type,
+                    old_size: old.size, new_size: f.size,
+                    change: f.size as i64 - old.size as i64,
+                    old_modified: old.modified_ms, new_modified: f.modified_ms,
+                });
+            }
+            None => changes.push(FileChange {
+                path: f.path.clone(), name: f.name.clone(), extension: f.extension.clone(),
+                change_type: "added",
+                old_size: 0, new_size: f.size,
+                change: f.size as i64,
+                old_modified: 0, new_modified: f.modified_ms,
+            }),
+        }
+        seen.insert(key);
+    }
+
+    for (key, old) in &old_map {
+        if !seen.contains(key) {
+            changes.push(FileChange {
+                path: old.path.clone(), name: old.name.clone(), extension: old.extension.clone(),
+                change_type: "deleted",
+                old_size: old.size, new_size: 0,
+                change: -(old.size as i64),
+                old_modified: old.modified_ms, new_modified: 0,
+            });
+        }
+    }
+
+    changes.sort_by(|a, b| b.change.abs().cmp(&a.change.abs()));
+
+    let mut by_type: HashMap<&'static str, ChangeSummary> = HashMap::new();
+    let mut by_ext: HashMap<String, ChangeSummary> = HashMap::new();
+    let mut by_dir: HashMap<String, ChangeSummary> = HashMap::new();
+    for c in &changes {
+        by_type.entry(c.change_type).or_insert_with(|| ChangeSummary { key: c.change_type.to_string(), ..Default::default() }).add(c);
+        let ext = if c.extension.is_empty() { "(keine)".to_string() } else { c.extension.clone() };
+        by_ext.entry(ext.clone()).or_insert_with(|| ChangeSummary { key: ext, ..Default::default() }).add(c);
+        let dir = parent_dir(&c.path).to_string();
+        by_dir.entry(dir.clone()).or_insert_with(|| ChangeSummary { key: dir, ..Default::default() }).add(c);
+    }
+    let mut by_extension: Vec<ChangeSummary> = by_ext.into_values().collect();
+    by_extension.sort_by(|a, b| b.net_change.abs().cmp(&a.net_change.abs()));
+    let mut by_directory: Vec<ChangeSummary> = by_dir.into_values().collect();
+    by_directory.sort_by(|a, b| b.net_change.abs().cmp(&a.net_change.abs()));
+
+    tracing::debug!(
+        changes = changes.len(),
+        elapsed_ms = start.elapsed().as_millis(),
+        "Datei-Diff zwischen Scans berechnet"
+    );
+
+    ScanDiff {
+        older_id: older_id.to_string(),
+        newer_id: newer_id.to_string(),
+        changes,
+        by_type,
+        by_extension,
+        by_directory,
+    }
+}
+
+/// Diff two snapshots from the history by their full file lists (cached for paging).
+/// change_type: "all" | "added" | "deleted" | "grown" | "shrunk" | "modified"
+pub fn diff_scans(data_dir: &std::path::Path, older_id: &str, newer_id: &str, change_type: &str, offset: usize, limit: usize) -> Result<Value, String> {
+    if !["all", "added", "deleted", "grown", "shrunk", "modified"].contains(&change_type) {
+        return Err(format!("Unbekannter Änderungstyp: {}", change_type));
+    }
+    let mut cache = diff_cache().lock().unwrap_or_else(|e| e.into_inner());
+    let cached = cache.as_ref().map(|d| d.older_id == older_id && d.newer_id == newer_id).unwrap_or(false);
+    if !cached {
+        let older = crate::scan_history::load_file_list(data_dir, older_id)
+            .ok_or("Dateiliste des älteren Scans nicht vorhanden (evtl. durch Speicherlimit entfernt)")?;
+        let newer = crate::scan_history::load_file_list(data_dir, newer_id)
+            .ok_or("Dateiliste des neueren Scans nicht vorhanden (evtl. durch Speicherlimit entfernt)")?;
+        *cache = Some(diff_file_lists(older_id, newer_id, &older, &newer));
+    }
+    let diff = cache.as_ref().ok_or("Diff nicht verfügbar")?;
+
+    let filtered: Vec<&FileChange> = diff.changes.iter()
+        .filter(|c| change_type == "all" || c.change_type == change_type)
+        .collect();
+    let items: Vec<&FileChange> = filtered.iter().skip(offset).take(limit).copied().collect();
+
+    let summary: HashMap<&str, Value> = ["added", "deleted", "grown", "shrunk", "modified"].iter().map(|t| {
+        let s = diff.by_type.get(t);
+        (*t, json!({
+            "count": s.map(|s| s.added + s.deleted + s.grown + s.shrunk + s.modified).unwrap_or(0),
+            "netChange": s.map(|s| s.net_change).unwrap_or(0)
+        }))
+    }).collect();
+
+    Ok(json!({
+        "olderId": diff.older_id,
+        "newerId": diff.newer_id,
+        "summary": summary,
+        "byExtension": diff.by_extension.iter().take(100).collect::<Vec<_>>(),
+        "byDirectory": diff.by_directory.iter().take(100).collect::<Vec<_>>(),
+        "changeType": change_type,
+        "total": filtered.len(),
+        "offset": offset,
+        "limit": limit,
+        "hasMore": offset + items.len() < filtered.len(),
+        "items": items
+    }))
+}
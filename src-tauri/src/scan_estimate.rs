@@ -0,0 +1,453 @@
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+/// z-value for the 95% confidence interval of the extrapolated sizes
+const Z_95: f64 = 1.96;
+/// Subtrees up to this many directories are enumerated instead of sampled
+const SMALL_SUBTREE_DIRS: usize = 32;
+/// Random descents never go deeper than this (symlink loops, pathological trees)
+const MAX_PROBE_DEPTH: usize = 64;
+/// Fewer descents give no usable variance — such subtrees are flagged low-confidence
+const MIN_PROBES: usize = 2;
+
+#[derive(Clone, Debug)]
+pub struct EstimateOptions {
+    /// Levels below the root that are read completely
+    pub full_depth: usize,
+    /// Time budget for sampling
+    pub max_ms: u64,
+    /// Upper bound of random descents per sampled subtree
+    pub max_probes: usize,
+}
+
+impl Default for EstimateOptions {
+    fn default() -> Self {
+        Self { full_depth: 1, max_ms: 5000, max_probes: 64 }
+    }
+}
+
+/// Extrapolated subtree totals (Knuth's random-descent estimator)
+#[derive(Clone, Debug)]
+struct Estimate {
+    size_mean: f64,
+    size_var: f64,
+    files_mean: f64,
+    files_var: f64,
+    /// Bytes actually seen below the subtree root — a hard lower bound
+    size_seen: u64,
+    probes: usize,
+}
+
+#[derive(Clone, Debug)]
+enum NodeKind {
+    /// Own files read; children complete (each child has its own node)
+    Enumerated,
+    /// Subtree extrapolated from samples; children not listed
+    Estimated(Estimate),
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    own_size: u64,
+    own_files: u64,
+    children: Vec<String>,
+    kind: NodeKind,
+}
+
+struct EstimateTree {
+    root_path: String,
+    created_ms: i64,
+    nodes: HashMap<String, Node>,
+}
+
+fn estimates() -> &'static Mutex<HashMap<String, EstimateTree>> {
+    static ESTIMATES: OnceLock<Mutex<HashMap<String, EstimateTree>>> = OnceLock::new();
+    ESTIMATES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Tiny xorshift PRNG — sampling does not need cryptographic randomness
+struct XorShift(u64);
+
+impl XorShift {
+    fn seeded() -> Self {
+        let seed = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_nanos() as u64;
+        Self(seed | 1)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+}
+
+/// One directory read: own file sizes + subdirectories. Cached for the whole run —
+/// repeated descents through the same upper levels cost no I/O.
+#[derive(Clone, Default)]
+struct DirRead {
+    own_size: u64,
+    own_files: u64,
+    children: Vec<String>,
+}
+
+struct Reader {
+    cache: HashMap<String, DirRead>,
+    dirs_read: u64,
+}
+
+impl Reader {
+    fn read(&mut self, path: &str) -> &DirRead {
+        if !self.cache.contains_key(path) {
+            let mut r = DirRead::default();
+            if let Ok(entries) = std::fs::read_dir(crate::ospath::decode(path)) {
+                for e in entries.flatten() {
+                    match e.file_type() {
+                        Ok(ft) if ft.is_dir() => r.children.push(crate::ospath::encode(e.path().as_os_str())),
+                        Ok(_) => {
+                            r.own_size += e.metadata().map(|m| m.len()).unwrap_or(0);
+                            r.own_files += 1;
+                        }
+                        Err(_) => {}
+                    }
+                }
+            }
+            r.children.sort_unstable();
+            self.dirs_read += 1;
+            self.cache.insert(path.to_string(), r);
+        }
+        &self.cache[path]
+    }
+
+    /// Read the whole subtree if it has at most `cap` directories
+    fn try_enumerate(&mut self, root: &str, cap: usize) -> Option<Vec<String>> {
+        let mut seen = vec![root.to_string()];
+        let mut i = 0;
+        while i < seen.len() {
+            let children = self.read(&seen[i]).children.clone();
+            seen.extend(children);
+            if seen.len() > cap {
+                return None;
+            }
+            i += 1;
+        }
+        Some(seen)
+    }
+
+    /// Bytes of all cached directories below (and including) `root`
+    fn seen_size(&self, root: &str) -> u64 {
+        let mut total = 0;
+        let mut stack = vec![root];
+        while let Some(p) = stack.pop() {
+            if let Some(r) = self.cache.get(p) {
+                total += r.own_size;
+                stack.extend(r.children.iter().map(|c| c.as_str()));
+            }
+        }
+        total
+    }
+
+    /// One random root-to-leaf descent: sum of own sizes weighted by the product
+    /// of branching factors along the way (unbiased estimate of the subtree total)
+    fn probe(&mut self, root: &str, rng: &mut XorShift) -> (f64, f64) {
+        let mut size = 0.0;
+        let mut files = 0.0;
+        let mut weight = 1.0;
+        let mut path = root.to_string();
+        for _ in 0..MAX_PROBE_DEPTH {
+            let r = self.read(&path);
+            size += weight * r.own_size as f64;
+            files += weight * r.own_files as f64;
+            if r.children.is_empty() {
+                break;
+            }
+            weight *= r.children.len() as f64;
+            path = r.children[rng.below(r.children.len())].clone();
+        }
+        (size, files)
+    }
+}
+
+fn mean_var(samples: &[f64]) -> (f64, f64) {
+    let n = samples.len() as f64;
+    if samples.is_empty() {
+        return (0.0, 0.0);
+    }
+    let mean = samples.iter().sum::<f64>() / n;
+    if samples.len() < 2 {
+        return (mean, mean * mean);
+    }
+    let var = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    // Variance of the mean
+    (mean, var / n)
+}
+
+/// Run an estimate scan of `root_path`. Returns (estimate_id, tree JSON).
+pub fn estimate(root_path: &str, options: &EstimateOptions) -> Result<(String, Value), String> {
+    if !std::path::Path::new(root_path).is_dir() {
+        return Err(format!("Pfad existiert nicht: {}", root_path));
+    }
+    let start = Instant::now();
+    let mut reader = Reader { cache: HashMap::new(), dirs_read: 0 };
+    let mut rng = XorShift::seeded();
+    let mut nodes: HashMap<String, Node> = HashMap::new();
+
+    // Phase 1: read the top levels completely
+    let mut level = vec![root_path.to_string()];
+    let mut sample_roots: Vec<String> = Vec::new();
+    for depth in 0..=options.full_depth {
+        let mut next = Vec::new();
+        for path in level {
+            let r = reader.read(&path).clone();
+            if depth == options.full_depth {
+                sample_roots.extend(r.children.iter().cloned());
+            } else {
+                next.extend(r.children.iter().cloned());
+            }
+            nodes.insert(path, Node { own_size: r.own_size, own_files: r.own_files, children: r.children, kind: NodeKind::Enumerated });
+        }
+        level = next;
+    }
+
+    // Phase 2: small subtrees are enumerated exactly
+    let mut to_sample: Vec<String> = Vec::new();
+    for root in sample_roots {
+        match reader.try_enumerate(&root, SMALL_SUBTREE_DIRS) {
+            Some(paths) => {
+                for p in paths {
+                    let r = reader.cache[&p].clone();
+                    nodes.insert(p, Node { own_size: r.own_size, own_files: r.own_files, children: r.children, kind: NodeKind::Enumerated });
+                }
+            }
+            None => to_sample.push(root),
+        }
+    }
+
+    // Phase 3: random descents round-robin over the big subtrees until the budget is spent.
+    // The deadline is checked before every probe — one round over many large subtrees on a
+    // slow share can take longer than the whole budget. Subtrees left with fewer than
+    // MIN_PROBES samples are reported as low-confidence.
+    let mut samples: Vec<(Vec<f64>, Vec<f64>)> = vec![(Vec::new(), Vec::new()); to_sample.len()];
+    let mut round = 0;
+    'rounds: while round < options.max_probes && !to_sample.is_empty() {
+        for (i, root) in to_sample.iter().enumerate() {
+            if start.elapsed().as_millis() as u64 >= options.max_ms {
+                break 'rounds;
+            }
+            let (s, f) = reader.probe(root, &mut rng);
+            samples[i].0.push(s);
+            samples[i].1.push(f);
+        }
+        round += 1;
+    }
+    let min_probes = samples.iter().map(|s| s.0.len()).min().unwrap_or(0);
+    let low_confidence = samples.iter().filter(|s| s.0.len() < MIN_PROBES).count();
+
+    for (root, (size_samples, file_samples)) in to_sample.iter().zip(samples) {
+        let (size_mean, size_var) = mean_var(&size_samples);
+        let (files_mean, files_var) = mean_var(&file_samples);
+        let r = reader.cache.get(root).cloned().unwrap_or_default();
+        let size_seen = reader.seen_size(root);
+        nodes.insert(root.clone(), Node {
+            own_size: r.own_size,
+            own_files: r.own_files,
+            children: Vec::new(),
+            kind: NodeKind::Estimated(Estimate {
+                size_mean: size_mean.max(size_seen as f64),
+                size_var,
+                files_mean,
+                files_var,
+                size_seen,
+                probes: size_samples.len(),
+            }),
+        });
+    }
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let estimate_id = format!("est_{}", now);
+    tracing::info!(
+        path = %root_path,
+        dirs_read = reader.dirs_read,
+        sampled_subtrees = to_sample.len(),
+        min_probes = min_probes,
+        low_confidence = low_confidence,
+        elapsed_ms = start.elapsed().as_millis(),
+        "Schätz-Scan abgeschlossen"
+    );
+
+    let tree = EstimateTree { root_path: root_path.to_string(), created_ms: now, nodes };
+    let result = tree_json(&tree, root_path, 2, Some(json!({
+        "dirsRead": reader.dirs_read,
+        "sampledSubtrees": to_sample.len(),
+        "probesPerSubtree": min_probes,
+        "lowConfidenceSubtrees": low_confidence,
+        "elapsedMs": start.elapsed().as_millis() as u64
+    })));
+    let mut store = estimates().lock().unwrap_or_else(|e| e.into_inner());
+    // Only the latest estimate per root is kept
+    store.retain(|_, t| t.root_path != root_path);
+    store.insert(estimate_id.clone(), tree);
+    Ok((estimate_id, result))
+}
+
+/// (size, size variance, files, files variance, size lower bound, exact)
+fn totals(tree: &EstimateTree, path: &str) -> (f64, f64, f64, f64, u64, bool) {
+    let node = match tree.nodes.get(path) {
+        Some(n) => n,
+        None => return (0.0, 0.0, 0.0, 0.0, 0, true),
+    };
+    match &node.kind {
+        NodeKind::Estimated(e) => (e.size_mean, e.size_var, e.files_mean, e.files_var, e.size_seen, false),
+        NodeKind::Enumerated => {
+            let mut acc = (node.own_size as f64, 0.0, node.own_files as f64, 0.0, node.own_size, true);
+            for child in &node.children {
+                let c = totals(tree, child);
+                acc.0 += c.0;
+                acc.1 += c.1;
+                acc.2 += c.2;
+                acc.3 += c.3;
+                acc.4 += c.4;
+                acc.5 &= c.5;
+            }
+            acc
+        }
+    }
+}
+
+/// Whether a sampled subtree at or below `path` got fewer than MIN_PROBES descents
+fn low_confidence(tree: &EstimateTree, path: &str) -> bool {
+    let node = match tree.nodes.get(path) {
+        Some(n) => n,
+        None => return false,
+    };
+    match &node.kind {
+        NodeKind::Estimated(e) => e.probes < MIN_PROBES,
+        NodeKind::Enumerated => node.children.iter().any(|c| low_confidence(tree, c)),
+    }
+}
+
+fn node_json(tree: &EstimateTree, path: &str, depth: u32) -> Value {
+    let (size, size_var, files, files_var, seen, exact) = totals(tree, path);
+    let size_margin = Z_95 * size_var.sqrt();
+    let files_margin = Z_95 * files_var.sqrt();
+    let node = tree.nodes.get(path);
+    let probes = match node.map(|n| &n.kind) {
+        Some(NodeKind::Estimated(e)) => Some(e.probes),
+        _ => None,
+    };
+    let mut children: Vec<Value> = if depth > 0 {
+        node.map(|n| n.children.iter().map(|c| node_json(tree, c, depth - 1)).collect()).unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    children.sort_unstable_by(|a, b| {
+        b["size"].as_u64().unwrap_or(0).cmp(&a["size"].as_u64().unwrap_or(0))
+    });
+    let name = std::path::Path::new(path).file_name().map(crate::ospath::encode).unwrap_or_else(|| path.to_string());
+    crate::ospath::annotate(json!({
+        "path": path,
+        "name": name,
+        "size": size.round() as u64,
+        "size_low": ((size - size_margin).max(seen as f64)).round() as u64,
+        "size_high": (size + size_margin).round() as u64,
+        "file_count": files.round() as u64,
+        "file_count_low": (files - files_margin).max(0.0).round() as u64,
+        "file_count_high": (files + files_margin).round() as u64,
+        "exact": exact,
+        // Sampled subtree — children unknown until refined
+        "estimated": probes.is_some(),
+        "probes": probes.unwrap_or(0),
+        // Too few descents in time (slow share, tight budget) — the interval is not reliable
+        "low_confidence": low_confidence(tree, path),
+        "dir_count": node.map(|n| n.children.len()).unwrap_or(0),
+        "children": children
+    }))
+}
+
+fn tree_json(tree: &EstimateTree, path: &str, depth: u32, stats: Option<Value>) -> Value {
+    let mut v = node_json(tree, path, depth);
+    if let Some(obj) = v.as_object_mut() {
+        obj.insert("root_path".to_string(), json!(&tree.root_path));
+        obj.insert("created_ms".to_string(), json!(tree.created_ms));
+        if let Some(stats) = stats {
+            obj.insert("stats".to_string(), stats);
+        }
+    }
+    v
+}
+
+/// Subtree of a stored estimate (for expanding nodes in the UI)
+pub fn get_tree(estimate_id: &str, path: Option<&str>, depth: u32) -> Result<Value, String> {
+    let store = estimates().lock().unwrap_or_else(|e| e.into_inner());
+    let tree = store.get(estimate_id).ok_or("Schätzung nicht gefunden")?;
+    let path = path.unwrap_or(&tree.root_path);
+    if !tree.nodes.contains_key(path) {
+        return Err(format!("Ordner ist nicht Teil der Schätzung: {}", path));
+    }
+    Ok(tree_json(tree, path, depth, None))
+}
+
+/// Replace the estimate of `branch` by exact numbers from a full scan of it.
+/// `cancelled` aborts the walk (the estimate stays unchanged).
+pub fn refine(estimate_id: &str, branch: &str, cancelled: &dyn Fn() -> bool) -> Result<Value, String> {
+    {
+        let store = estimates().lock().unwrap_or_else(|e| e.into_inner());
+        let tree = store.get(estimate_id).ok_or("Schätzung nicht gefunden")?;
+        if !tree.nodes.contains_key(branch) {
+            return Err(format!("Ordner ist nicht Teil der Schätzung: {}", branch));
+        }
+    }
+    let start = Instant::now();
+    // Full walk outside the lock — can take a while for big branches
+    let walked = crate::commands::walk_tree(branch, cancelled, &mut |_| {})
+        .ok_or("Verfeinerung abgebrochen")?;
+
+    // Own sizes per directory + parent→child links up to the branch root
+    let mut own: HashMap<String, (u64, u64)> = HashMap::new();
+    let mut children: HashMap<String, std::collections::BTreeSet<String>> = HashMap::new();
+    own.insert(branch.to_string(), (0, 0));
+    for f in &walked.files {
+        let parent = match std::path::Path::new(&f.path).parent() {
+            Some(p) => crate::ospath::encode(p.as_os_str()),
+            None => continue,
+        };
+        let e = own.entry(parent.clone()).or_default();
+        e.0 += f.size;
+        e.1 += 1;
+        let mut dir = parent;
+        while dir != branch && dir.len() > branch.len() {
+            let up = match std::path::Path::new(&dir).parent() {
+                Some(p) => crate::ospath::encode(p.as_os_str()),
+                None => break,
+            };
+            if !children.entry(up.clone()).or_default().insert(dir.clone()) {
+                break; // Already linked — all ancestors are too
+            }
+            own.entry(up.clone()).or_default();
+            dir = up;
+        }
+    }
+
+    let mut store = estimates().lock().unwrap_or_else(|e| e.into_inner());
+    let tree = store.get_mut(estimate_id).ok_or("Schätzung nicht gefunden")?;
+    // Drop the old nodes of the branch before inserting the exact ones
+    let mut stack = vec![branch.to_string()];
+    while let Some(p) = stack.pop() {
+        if let Some(n) = tree.nodes.remove(&p) {
+            stack.extend(n.children);
+        }
+    }
+    for (path, (size, files)) in own {
+        let kids = children.remove(&path).map(|s| s.into_iter().collect()).unwrap_or_default();
+        tree.nodes.insert(path, Node { own_size: size, own_files: files, children: kids, kind: NodeKind::Enumerated });
+    }
+    tracing::info!(
+        branch = %branch,
+        files = walked.files.len(),
+        elapsed_ms = start.elapsed().as_millis(),
+        "Schätzung für Ordner durch vollständigen Scan ersetzt"
+    );
+    Ok(tree_json(tree, &tree.root_path.clone(), 2, None))
+}
@@ -7,6 +7,12 @@ use std::path::Path;
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ScanSnapshot {
     pub id: String,
+    /// Scan this snapshot was taken from
+    #[serde(default)]
+    pub scan_id: String,
+    /// Machine the snapshot was imported from ("" = taken on this machine)
+    #[serde(default)]
+    pub hostname: String,
     pub timestamp_ms: i64,
     pub root_path: String,
     pub total_size: u64,
@@ -18,6 +24,37 @@ pub struct ScanSnapshot {
     pub category_counts: HashMap<String, u64>,
     /// Top-level folder sizes (direct children of root): { "C:\\Users": 12345, ... }
     pub folder_sizes: HashMap<String, u64>,
+    /// Capacity of the filesystem holding root_path (0 = unknown, e.g. old snapshots)
+    #[serde(default)]
+    pub disk_total: u64,
+    /// Free space available to the user on that filesystem at snapshot time
+    #[serde(default)]
+    pub disk_free: u64,
+    /// Size of the stored directory index on disk (0 = no index or evicted by disk budget)
+    #[serde(default)]
+    pub dir_index_bytes: u64,
+    /// Size of the stored full file list on disk (0 = not stored or evicted by disk budget)
+    #[serde(default)]
+    pub file_list_bytes: u64,
+    /// Full directory index (every directory) — not part of scan-history.json,
+    /// persisted separately as zstd-compressed bincode in scan-history/<id>.zst
+    #[serde(skip)]
+    pub dir_index: Option<Vec<DirSize>>,
+}
+
+impl ScanSnapshot {
+    /// Taken on this machine (not imported from another one)
+    pub fn is_local(&self) -> bool {
+        self.hostname.is_empty()
+    }
+}
+
+/// Per-directory totals as stored in a snapshot's directory index
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DirSize {
+    pub path: String,
+    pub size: u64,
+    pub files: u64,
 }
 
 /// Delta between two snapshots
@@ -34,6 +71,63 @@ pub struct ScanDelta {
     pub category_changes: HashMap<String, i64>,
     /// Per-folder size changes (only folders present in either snapshot)
     pub folder_changes: Vec<FolderChange>,
+    /// Per-directory changes at every depth below `subtree` (only if both snapshots have a directory index)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dir_changes: Option<Vec<DirChange>>,
+    /// Folders/categories whose growth deviates strongly from their own history
+    #[serde(default)]
+    pub anomalies: Vec<Anomaly>,
+}
+
+/// A folder or category whose growth deviates strongly from its own baseline
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Anomaly {
+    /// "folder" | "category"
+    pub kind: String,
+    pub key: String,
+    pub old_size: u64,
+    pub new_size: u64,
+    pub change: i64,
+    pub growth_per_day: i64,
+    /// Median growth per day over the earlier history (None = not enough history)
+    pub baseline_per_day: Option<i64>,
+    /// Robust z-score (median/MAD) of the current growth rate
+    pub score: Option<f64>,
+    /// "spike" (far above baseline) | "doubled" (size at least doubled)
+    pub reason: String,
+}
+
+/// Thresholds for anomaly detection
+pub struct AnomalyOptions {
+    /// Robust z-score above which growth counts as a spike
+    pub z_threshold: f64,
+    /// Minimum absolute growth in bytes before a spike is flagged
+    pub min_bytes: u64,
+    /// Minimum old size for the "doubled" rule (avoids flagging 1 KB → 2 KB)
+    pub min_doubling_base: u64,
+}
+
+impl Default for AnomalyOptions {
+    fn default() -> Self {
+        Self {
+            z_threshold: 3.5,
+            min_bytes: 1024 * 1024 * 1024,
+            min_doubling_base: 100 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DirChange {
+    pub path: String,
+    /// Depth relative to the compared subtree (1 = direct child)
+    pub depth: u32,
+    pub old_size: u64,
+    pub new_size: u64,
+    pub change: i64,
+    pub old_files: u64,
+    pub new_files: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -42,15 +136,156 @@ pub struct FolderChange {
     pub old_size: u64,
     pub new_size: u64,
     pub change: i64,
+    /// Anomaly reason if this folder's growth is unusual ("spike" | "doubled")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anomaly: Option<String>,
+}
+
+/// Per-root retention tiers (preference "historyRetention"):
+/// keep everything younger than `keep_all_days`, then the newest snapshot per week until
+/// `weekly_days`, then the newest per month until `monthly_months`. Older snapshots are dropped.
+#[derive(Clone, Debug)]
+pub struct RetentionPolicy {
+    pub keep_all_days: u32,
+    pub weekly_days: u32,
+    pub monthly_months: u32,
 }
 
-const MAX_SNAPSHOTS: usize = 50;
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self { keep_all_days: 7, weekly_days: 90, monthly_months: 24 }
+    }
+}
+
+impl RetentionPolicy {
+    fn from_preferences() -> Self {
+        let prefs = crate::commands::read_json_file("preferences.json");
+        let r = prefs.get("historyRetention");
+        let get = |key: &str, default: u32| -> u32 {
+            r.and_then(|v| v.get(key)).and_then(|v| v.as_u64()).map(|v| v as u32).unwrap_or(default)
+        };
+        let d = Self::default();
+        Self {
+            keep_all_days: get("keepAllDays", d.keep_all_days),
+            weekly_days: get("weeklyDays", d.weekly_days),
+            monthly_months: get("monthlyMonths", d.monthly_months),
+        }
+    }
+}
 const HISTORY_FILE: &str = "scan-history.json";
+const INDEX_DIR: &str = "scan-history";
+/// Default disk budget for stored directory indices (preference "historyDiskBudgetMb")
+const DEFAULT_INDEX_BUDGET_MB: u64 = 200;
 
 fn history_path(data_dir: &Path) -> std::path::PathBuf {
     data_dir.join(HISTORY_FILE)
 }
 
+/// Snapshot IDs become file names below scan-history/ — only `[A-Za-z0-9_-]` is allowed
+/// (same character set as the duplicate result files)
+pub fn is_safe_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn index_path(data_dir: &Path, snapshot_id: &str) -> std::path::PathBuf {
+    data_dir.join(INDEX_DIR).join(format!("{}.zst", snapshot_id))
+}
+
+fn index_budget_bytes() -> u64 {
+    let prefs = crate::commands::read_json_file("preferences.json");
+    let mb = prefs.get("historyDiskBudgetMb").and_then(|v| v.as_u64()).unwrap_or(DEFAULT_INDEX_BUDGET_MB);
+    mb * 1024 * 1024
+}
+
+/// Write a snapshot's directory index (zstd-compressed bincode). Returns bytes on disk.
+fn save_dir_index(data_dir: &Path, snapshot_id: &str, dirs: &[DirSize]) -> Result<u64, String> {
+    let path = index_path(data_dir, snapshot_id);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("scan-history/ erstellen: {}", e))?;
+    }
+    let file = std::fs::File::create(&path).map_err(|e| format!("Verzeichnis-Index erstellen: {}", e))?;
+    let zst_writer = zstd::Encoder::new(file, 3)
+        .map_err(|e| format!("zstd-Encoder erstellen: {}", e))?;
+    let mut buf_writer = std::io::BufWriter::with_capacity(256 * 1024, zst_writer);
+    bincode::serialize_into(&mut buf_writer, dirs)
+        .map_err(|e| format!("Verzeichnis-Index schreiben: {}", e))?;
+    let zst_writer = buf_writer.into_inner().map_err(|e| format!("BufWriter flush: {}", e))?;
+    zst_writer.finish().map_err(|e| format!("zstd finalize: {}", e))?;
+    Ok(std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0))
+}
+
+/// Load a snapshot's directory index from disk (None if never stored or evicted)
+pub fn load_dir_index(data_dir: &Path, snapshot_id: &str) -> Option<Vec<DirSize>> {
+    let file = std::fs::File::open(index_path(data_dir, snapshot_id)).ok()?;
+    let zst_reader = zstd::Decoder::new(file).ok()?;
+    let buf_reader = std::io::BufReader::with_capacity(256 * 1024, zst_reader);
+    bincode::deserialize_from(buf_reader).ok()
+}
+
+/// Full file list of a snapshot (same zstd/bincode format as scan-data.zst)
+pub fn file_list_path(data_dir: &Path, snapshot_id: &str) -> std::path::PathBuf {
+    data_dir.join(INDEX_DIR).join(format!("{}.files.zst", snapshot_id))
+}
+
+/// Load a snapshot's full file list from disk (None if never stored or evicted)
+pub fn load_file_list(data_dir: &Path, snapshot_id: &str) -> Option<Vec<crate::scan::FileEntry>> {
+    let path = file_list_path(data_dir, snapshot_id);
+    if !path.exists() {
+        return None;
+    }
+    crate::scan::read_file_list(&path)
+        .map_err(|e| tracing::warn!(snapshot_id = %snapshot_id, error = %e, "Dateiliste des Snapshots nicht lesbar"))
+        .ok()
+}
+
+/// Remove a snapshot's directory index and file list (if any)
+pub fn remove_dir_index(data_dir: &Path, snapshot_id: &str) {
+    // Histories imported before IDs were validated may still hold unsafe IDs
+    if !is_safe_id(snapshot_id) {
+        tracing::warn!(snapshot_id = %snapshot_id, "Unsichere Snapshot-ID — Index-Dateien nicht gelöscht");
+        return;
+    }
+    let _ = std::fs::remove_file(index_path(data_dir, snapshot_id));
+    let _ = std::fs::remove_file(file_list_path(data_dir, snapshot_id));
+}
+
+/// Remove all stored directory indices
+pub fn clear_dir_indices(data_dir: &Path) {
+    let _ = std::fs::remove_dir_all(data_dir.join(INDEX_DIR));
+}
+
+/// Evict stored file lists, then directory indices (oldest first) until the total is within
+/// the disk budget. The lightweight snapshot metadata stays in the history — only drill-down
+/// and file-level diff are lost. The newest snapshot is never evicted.
+fn enforce_index_budget(data_dir: &Path, history: &mut [ScanSnapshot], budget: u64) {
+    let mut total: u64 = history.iter().map(|s| s.dir_index_bytes + s.file_list_bytes).sum();
+    let newest = history.len().saturating_sub(1);
+
+    // Pass 1: file lists (large)
+    for snap in history[..newest].iter_mut() {
+        if total <= budget {
+            return;
+        }
+        if snap.file_list_bytes > 0 {
+            let _ = std::fs::remove_file(file_list_path(data_dir, &snap.id));
+            total -= snap.file_list_bytes;
+            snap.file_list_bytes = 0;
+        }
+    }
+
+    // Pass 2: directory indices (small)
+    for snap in history[..newest].iter_mut() {
+        if total <= budget {
+            return;
+        }
+        if snap.dir_index_bytes > 0 {
+            let _ = std::fs::remove_file(index_path(data_dir, &snap.id));
+            total -= snap.dir_index_bytes;
+            snap.dir_index_bytes = 0;
+        }
+    }
+}
+
 /// Load all snapshots from disk
 pub fn load_history(data_dir: &Path) -> Vec<ScanSnapshot> {
     let path = history_path(data_dir);
@@ -63,75 +298,185 @@ pub fn load_history(data_dir: &Path) -> Vec<ScanSnapshot> {
     }
 }
 
-/// Save history to disk
-fn save_history(data_dir: &Path, history: &[ScanSnapshot]) -> Result<(), String> {
+/// Apply the retention policy per root. Returns the IDs of snapshots to drop.
+/// The newest snapshot of every root is always kept. Imported snapshots (other hosts)
+/// are left alone — the local policy and clock say nothing about them, and an import
+/// must not be thinned out right after it was reported as imported.
+pub fn apply_retention(history: &[ScanSnapshot], policy: &RetentionPolicy, now_ms: i64) -> Vec<String> {
+    use chrono::Datelike;
+    const DAY_MS: i64 = 86_400_000;
+
+    let mut by_root: HashMap<String, Vec<&ScanSnapshot>> = HashMap::new();
+    for snap in history.iter().filter(|s| s.is_local()) {
+        // Same case rule as `pathkey::same_path` — `/data/Foo` and `/data/foo` are two roots on Linux
+        let root = crate::pathkey::key_with(&snap.root_path, crate::pathkey::case_insensitive(&snap.root_path));
+        by_root.entry(root).or_default().push(snap);
+    }
+
+    let mut drop_ids = Vec::new();
+    for snaps in by_root.values_mut() {
+        // Newest first — the first snapshot seen per bucket is the one that stays
+        snaps.sort_by(|a, b| b.timestamp_ms.cmp(&a.timestamp_ms));
+        let mut weeks_seen: std::collections::HashSet<i64> = std::collections::HashSet::new();
+        let mut months_seen: std::collections::HashSet<(i32, u32)> = std::collections::HashSet::new();
+
+        for (i, snap) in snaps.iter().enumerate() {
+            let age_days = (now_ms - snap.timestamp_ms) / DAY_MS;
+            let keep = if i == 0 || age_days < policy.keep_all_days as i64 {
+                true
+            } else if age_days < policy.weekly_days as i64 {
+                weeks_seen.insert(snap.timestamp_ms.div_euclid(7 * DAY_MS))
+            } else if age_days < policy.monthly_months as i64 * 30 {
+                let date = chrono::DateTime::from_timestamp_millis(snap.timestamp_ms).unwrap_or_default();
+                months_seen.insert((date.year(), date.month()))
+            } else {
+                false
+            };
+            if !keep {
+                drop_ids.push(snap.id.clone());
+            }
+        }
+    }
+    drop_ids
+}
+
+/// Save history to disk — applies the retention policy first (removing the directory
+/// indices / file lists of dropped snapshots), then the disk budget to what remains
+fn save_history(data_dir: &Path, history: &mut Vec<ScanSnapshot>) -> Result<(), String> {
+    let drop_ids = apply_retention(history, &RetentionPolicy::from_preferences(), chrono::Utc::now().timestamp_millis());
+    if !drop_ids.is_empty() {
+        tracing::debug!(dropped = drop_ids.len(), "Scan-Verlauf: Aufbewahrungsregel angewendet");
+        for id in &drop_ids {
+            remove_dir_index(data_dir, id);
+        }
+        history.retain(|s| !drop_ids.contains(&s.id));
+    }
+    enforce_index_budget(data_dir, history, index_budget_bytes());
+    write_history(data_dir, history)
+}
+
+fn write_history(data_dir: &Path, history: &[ScanSnapshot]) -> Result<(), String> {
     let path = history_path(data_dir);
     let json = serde_json::to_string_pretty(history).map_err(|e| e.to_string())?;
     std::fs::write(&path, json).map_err(|e| format!("scan-history.json schreiben: {}", e))
 }
 
-/// Create a snapshot from the current in-memory scan data
+/// Create a snapshot from the current in-memory scan data. The directory totals are
+/// computed from the scan itself — the global index may belong to another root.
 pub fn create_snapshot(scan_id: &str) -> Option<ScanSnapshot> {
-    crate::scan::with_scan(scan_id, |data| {
-        let mut category_sizes: HashMap<String, u64> = HashMap::new();
-        let mut category_counts: HashMap<String, u64> = HashMap::new();
-        let mut folder_sizes: HashMap<String, u64> = HashMap::new();
-
-        for f in &data.files {
-            let cat = crate::scan::file_category(&f.extension).to_string();
-            *category_sizes.entry(cat.clone()).or_default() += f.size;
-            *category_counts.entry(cat).or_default() += 1;
-
-            // Top-level folder: first directory segment after root
-            let rel = if f.path.len() > data.root_path.len() {
-                &f.path[data.root_path.len()..]
-            } else {
-                continue;
-            };
-            let rel = rel.trim_start_matches('\\');
-            if let Some(sep) = rel.find('\\') {
-                let top_folder = format!("{}\\{}", data.root_path.trim_end_matches('\\'), &rel[..sep]);
-                *folder_sizes.entry(top_folder).or_default() += f.size;
-            }
-        }
+    crate::scan::with_scan(scan_id, |data| snapshot_of(data, crate::scan::dir_totals_of(&data.root_path, &data.files)))
+}
+
+/// Build a snapshot from scan data + its per-directory totals (path, size, files)
+pub fn snapshot_of(data: &crate::scan::ScanData, dir_totals: Vec<(String, u64, u64)>) -> ScanSnapshot {
+    let mut category_sizes: HashMap<String, u64> = HashMap::new();
+    let mut category_counts: HashMap<String, u64> = HashMap::new();
+    let mut folder_sizes: HashMap<String, u64> = HashMap::new();
 
-        let now = chrono::Utc::now().timestamp_millis();
-        let snapshot_id = format!("snap_{}", now);
-
-        ScanSnapshot {
-            id: snapshot_id,
-            timestamp_ms: now,
-            root_path: data.root_path.clone(),
-            total_size: data.total_size,
-            total_files: data.files.len() as u64,
-            dirs_scanned: data.dirs_scanned,
-            category_sizes,
-            category_counts,
-            folder_sizes,
+    for f in &data.files {
+        let cat = crate::scan::file_category(&f.extension).to_string();
+        *category_sizes.entry(cat.clone()).or_default() += f.size;
+        *category_counts.entry(cat).or_default() += 1;
+
+        // Top-level folder: first directory component after root
+        let rel = match std::path::Path::new(&f.path).strip_prefix(&data.root_path) {
+            Ok(r) => r,
+            Err(_) => continue,
+        };
+        let mut parts = rel.components();
+        if let (Some(top), Some(_)) = (parts.next(), parts.next()) {
+            let top_folder = std::path::Path::new(&data.root_path).join(top).to_string_lossy().to_string();
+            *folder_sizes.entry(top_folder).or_default() += f.size;
         }
-    })
+    }
+
+    let dir_index: Vec<DirSize> = dir_totals.into_iter()
+        .map(|(path, size, files)| DirSize { path, size, files })
+        .collect();
+
+    // Filesystem capacity + free space (statvfs on Unix, GetDiskFreeSpaceEx on Windows)
+    let disk_total = fs2::total_space(&data.root_path).unwrap_or(0);
+    let disk_free = fs2::available_space(&data.root_path).unwrap_or(0);
+
+    let now = chrono::Utc::now().timestamp_millis();
+    // The ID names the index and file-list files — a scheduled and a manual save can finish
+    // in the same millisecond, so a process-wide counter follows the timestamp
+    let snapshot_id = crate::jobs::new_id(&format!("snap_{}", now));
+
+    ScanSnapshot {
+        id: snapshot_id,
+        scan_id: data.scan_id.clone(),
+        hostname: String::new(),
+        timestamp_ms: now,
+        root_path: data.root_path.clone(),
+        total_size: data.total_size,
+        total_files: data.files.len() as u64,
+        dirs_scanned: data.dirs_scanned,
+        category_sizes,
+        category_counts,
+        folder_sizes,
+        disk_total,
+        disk_free,
+        dir_index_bytes: 0,
+        file_list_bytes: 0,
+        dir_index: Some(dir_index),
+    }
 }
 
-/// Save a snapshot to the history file
+/// Save a snapshot to the history file (file list taken from the in-memory scan)
 pub fn save_snapshot(data_dir: &Path, snapshot: ScanSnapshot) -> Result<(), String> {
+    save_snapshot_with_files(data_dir, snapshot, None)
+}
+
+/// Save a snapshot; `files` overrides the in-memory scan as source of the file list
+pub fn save_snapshot_with_files(data_dir: &Path, mut snapshot: ScanSnapshot, files: Option<&[crate::scan::FileEntry]>) -> Result<(), String> {
     let mut history = load_history(data_dir);
 
-    // Prevent duplicate: don't save if last snapshot is less than 5 minutes old
-    if let Some(last) = history.last() {
+    // Prevent duplicate: don't save if the last snapshot of the same root is less than 5 minutes old
+    if let Some(last) = history.iter().rev().find(|s| s.is_local() && crate::pathkey::same_path(&s.root_path, &snapshot.root_path)) {
         if (snapshot.timestamp_ms - last.timestamp_ms).abs() < 300_000 {
-            return Err("Letzter Snapshot ist weniger als 5 Minuten alt".to_string());
+            return Err("Letzter Snapshot dieses Pfads ist weniger als 5 Minuten alt".to_string());
         }
     }
 
-    history.push(snapshot);
+    // Directory index is stored next to the history file, not inside it
+    if let Some(dirs) = snapshot.dir_index.take() {
+        match save_dir_index(data_dir, &snapshot.id, &dirs) {
+            Ok(bytes) => snapshot.dir_index_bytes = bytes,
+            Err(e) => tracing::warn!(snapshot_id = %snapshot.id, error = %e, "Verzeichnis-Index für Snapshot nicht gespeichert"),
+        }
+    }
 
-    // Trim to max
-    if history.len() > MAX_SNAPSHOTS {
-        let excess = history.len() - MAX_SNAPSHOTS;
-        history.drain(..excess);
+    // Full file list for file-level diffs. The in-memory scan is copied under the store
+    // lock and compressed after releasing it — scans and the watcher must not wait on zstd.
+    let scan_files = match files {
+        Some(_) => None,
+        None => crate::scan::with_scan(&snapshot.scan_id, |data| data.files.clone()),
+    };
+    if let Some(files) = files.or(scan_files.as_deref()) {
+        let list_path = file_list_path(data_dir, &snapshot.id);
+        let _ = std::fs::create_dir_all(data_dir.join(INDEX_DIR));
+        match crate::scan::write_file_list(&list_path, files) {
+            Ok(()) => snapshot.file_list_bytes = std::fs::metadata(&list_path).map(|m| m.len()).unwrap_or(0),
+            Err(e) => tracing::warn!(snapshot_id = %snapshot.id, error = %e, "Dateiliste für Snapshot nicht gespeichert"),
+        }
     }
 
-    save_history(data_dir, &history)
+    history.push(snapshot);
+    save_history(data_dir, &mut history)
+}
+
+/// Delete a single snapshot (plus its directory index / file list). Returns false if not found.
+pub fn delete_snapshot(data_dir: &Path, snapshot_id: &str) -> Result<bool, String> {
+    let mut history = load_history(data_dir);
+    let before_len = history.len();
+    history.retain(|s| s.id != snapshot_id);
+    if history.len() == before_len {
+        return Ok(false);
+    }
+    remove_dir_index(data_dir, snapshot_id);
+    save_history(data_dir, &mut history)?;
+    Ok(true)
 }
 
 /// Compare two snapshots to produce a delta
@@ -166,6 +511,7 @@ pub fn compare_snapshots(older: &ScanSnapshot, newer: &ScanSnapshot) -> ScanDelt
                 old_size,
                 new_size,
                 change,
+                anomaly: None,
             });
         }
     }
@@ -181,13 +527,265 @@ pub fn compare_snapshots(older: &ScanSnapshot, newer: &ScanSnapshot) -> ScanDelt
         files_change: newer.total_files as i64 - older.total_files as i64,
         category_changes,
         folder_changes,
+        dir_changes: None,
+        anomalies: Vec::new(),
     }
 }
 
-/// Compute a storage trend from history: growth rate per day, prediction
-pub fn compute_trend(history: &[ScanSnapshot], root_path: &str) -> Value {
+/// Detect anomalous growth between `older` and `newer`. `baseline` are the snapshots of the
+/// same root up to and including `older` (oldest first) — their consecutive growth rates
+/// form each folder's/category's own baseline.
+pub fn detect_anomalies(baseline: &[&ScanSnapshot], older: &ScanSnapshot, newer: &ScanSnapshot, opts: &AnomalyOptions) -> Vec<Anomaly> {
+    let days = ((newer.timestamp_ms - older.timestamp_ms) as f64 / 86_400_000.0).max(0.01);
+    let mut anomalies = Vec::new();
+
+    let series: [(&str, fn(&ScanSnapshot) -> &HashMap<String, u64>); 2] = [
+        ("folder", |s| &s.folder_sizes),
+        ("category", |s| &s.category_sizes),
+    ];
+
+    for (kind, get) in series {
+        let mut keys: std::collections::HashSet<&String> = std::collections::HashSet::new();
+        keys.extend(get(older).keys());
+        keys.extend(get(newer).keys());
+
+        for key in keys {
+            let old_size = *get(older).get(key).unwrap_or(&0);
+            let new_size = *get(newer).get(key).unwrap_or(&0);
+            let change = new_size as i64 - old_size as i64;
+            if change <= 0 {
+                continue;
+            }
+            let rate = change as f64 / days;
+
+            // Baseline: growth per day between consecutive earlier snapshots
+            let mut rates: Vec<f64> = baseline.windows(2).filter_map(|w| {
+                let d = (w[1].timestamp_ms - w[0].timestamp_ms) as f64 / 86_400_000.0;
+                if d < 0.01 { return None; }
+                let a = *get(w[0]).get(key).unwrap_or(&0) as f64;
+                let b = *get(w[1]).get(key).unwrap_or(&0) as f64;
+                Some((b - a) / d)
+            }).collect();
+
+            let (baseline_per_day, score) = if rates.len() >= 3 {
+                let m = median(&mut rates).unwrap_or(0.0);
+                let mut deviations: Vec<f64> = rates.iter().map(|r| (r - m).abs()).collect();
+                let mad = median(&mut deviations).unwrap_or(0.0);
+                // Flat history (MAD = 0): scale by the typical rate, at least min_bytes per week
+                let scale = if mad > 0.0 { mad / 0.6745 } else { m.abs().max(opts.min_bytes as f64 / 7.0) };
+                (Some(m.round() as i64), Some(((rate - m) / scale * 100.0).round() / 100.0))
+            } else {
+                (None, None)
+            };
+
+            let reason = if change as u64 >= opts.min_bytes && score.map(|z| z > opts.z_threshold).unwrap_or(false) {
+                "spike"
+            } else if old_size >= opts.min_doubling_base && new_size >= old_size * 2 {
+                "doubled"
+            } else {
+                continue;
+            };
+
+            anomalies.push(Anomaly {
+                kind: kind.to_string(),
+                key: key.clone(),
+                old_size,
+                new_size,
+                change,
+                growth_per_day: rate.round() as i64,
+                baseline_per_day,
+                score,
+                reason: reason.to_string(),
+            });
+        }
+    }
+
+    anomalies.sort_by(|a, b| b.change.cmp(&a.change));
+    anomalies
+}
+
+/// Anomalies of the latest snapshot of `root_path` compared to the one before it
+pub fn storage_anomalies(history: &[ScanSnapshot], root_path: &str, opts: &AnomalyOptions) -> Value {
+    let relevant: Vec<&ScanSnapshot> = history.iter()
+        .filter(|s| s.is_local() && crate::pathkey::same_path(&s.root_path, root_path))
+        .collect();
+    if relevant.len() < 2 {
+        return json!({
+            "hasEnoughData": false,
+            "message": "Mindestens 2 Scans nötig für Anomalie-Erkennung",
+            "anomalies": []
+        });
+    }
+    let n = relevant.len();
+    let anomalies = detect_anomalies(&relevant[..n - 1], relevant[n - 2], relevant[n - 1], opts);
+    json!({
+        "hasEnoughData": true,
+        "olderId": relevant[n - 2].id,
+        "newerId": relevant[n - 1].id,
+        "baselineSnapshots": n - 1,
+        "anomalies": anomalies
+    })
+}
+
+/// Per-directory growth below `subtree` (all depths up to `max_depth`) between two directory indices.
+/// Sorted by absolute change, unchanged directories are skipped.
+pub fn compare_dir_indices(older: &[DirSize], newer: &[DirSize], subtree: &str, max_depth: u32, limit: usize) -> Vec<DirChange> {
+    let root = crate::scan::normalize_dir_key(subtree);
+    let root_depth = crate::pathkey::depth(&root);
+
+    let in_subtree = |path: &str| crate::pathkey::is_below(path, &root);
+    let old_map: HashMap<&str, &DirSize> = older.iter().filter(|d| in_subtree(&d.path)).map(|d| (d.path.as_str(), d)).collect();
+    let new_map: HashMap<&str, &DirSize> = newer.iter().filter(|d| in_subtree(&d.path)).map(|d| (d.path.as_str(), d)).collect();
+
+    let mut all_paths: std::collections::HashSet<&str> = std::collections::HashSet::new();
+    all_paths.extend(old_map.keys());
+    all_paths.extend(new_map.keys());
+
+    let mut changes: Vec<DirChange> = all_paths.into_iter().filter_map(|path| {
+        let depth = (crate::pathkey::depth(path) - root_depth) as u32;
+        if depth > max_depth {
+            return None;
+        }
+        let (old_size, old_files) = old_map.get(path).map(|d| (d.size, d.files)).unwrap_or((0, 0));
+        let (new_size, new_files) = new_map.get(path).map(|d| (d.size, d.files)).unwrap_or((0, 0));
+        let change = new_size as i64 - old_size as i64;
+        if change == 0 && old_files == new_files {
+            return None;
+        }
+        Some(DirChange { path: path.to_string(), depth, old_size, new_size, change, old_files, new_files })
+    }).collect();
+
+    changes.sort_by(|a, b| b.change.abs().cmp(&a.change.abs()));
+    changes.truncate(limit);
+    changes
+}
+
+/// Linear fit y = intercept + slope * x (x in days, y in bytes)
+struct LinearFit {
+    slope: f64,
+    intercept: f64,
+    /// Standard error of the slope (0 if fewer than 3 points)
+    slope_se: f64,
+    r2: f64,
+}
+
+/// Least-squares fit, or Theil–Sen (median of pairwise slopes) when `robust` is set —
+/// Theil–Sen ignores single outliers such as a one-off cleanup between two scans.
+fn fit_linear(points: &[(f64, f64)], robust: bool) -> Option<LinearFit> {
+    let n = points.len();
+    if n < 2 {
+        return None;
+    }
+    let nf = n as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / nf;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / nf;
+    let sxx: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    if sxx <= f64::EPSILON {
+        return None;
+    }
+
+    let (slope, intercept) = if robust {
+        let mut slopes: Vec<f64> = Vec::with_capacity(n * (n - 1) / 2);
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let dx = points[j].0 - points[i].0;
+                if dx.abs() > f64::EPSILON {
+                    slopes.push((points[j].1 - points[i].1) / dx);
+                }
+            }
+        }
+        let slope = median(&mut slopes)?;
+        let mut intercepts: Vec<f64> = points.iter().map(|p| p.1 - slope * p.0).collect();
+        (slope, median(&mut intercepts)?)
+    } else {
+        let sxy: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+        let slope = sxy / sxx;
+        (slope, mean_y - slope * mean_x)
+    };
+
+    let ss_res: f64 = points.iter().map(|p| (p.1 - (intercept + slope * p.0)).powi(2)).sum();
+    let ss_tot: f64 = points.iter().map(|p| (p.1 - mean_y).powi(2)).sum();
+    let r2 = if ss_tot > 0.0 { (1.0 - ss_res / ss_tot).max(0.0) } else { 1.0 };
+    let slope_se = if n > 2 { (ss_res / (nf - 2.0) / sxx).sqrt() } else { 0.0 };
+
+    Some(LinearFit { slope, intercept, slope_se, r2 })
+}
+
+fn median(values: &mut [f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let mid = values.len() / 2;
+    Some(if values.len() % 2 == 0 { (values[mid - 1] + values[mid]) / 2.0 } else { values[mid] })
+}
+
+/// Two-sided 95% t-quantile for n-2 degrees of freedom (small samples get a wider band)
+fn t_quantile_95(df: usize) -> f64 {
+    const TABLE: [f64; 10] = [12.71, 4.30, 3.18, 2.78, 2.57, 2.45, 2.36, 2.31, 2.26, 2.23];
+    match df {
+        0 => 0.0,
+        1..=10 => TABLE[df - 1],
+        11..=20 => 2.09,
+        21..=30 => 2.04,
+        _ => 1.96,
+    }
+}
+
+/// Days until `free` bytes are used up at `slope` bytes/day (None if not growing)
+fn days_until(free: f64, slope: f64) -> Option<f64> {
+    if slope > 0.0 && free > 0.0 {
+        Some(free / slope)
+    } else {
+        None
+    }
+}
+
+fn full_on(last_ms: i64, days: Option<f64>) -> Option<String> {
+    let days = days?;
+    if !days.is_finite() || days > 365.0 * 100.0 {
+        return None;
+    }
+    let ms = last_ms + (days * 86_400_000.0) as i64;
+    chrono::DateTime::from_timestamp_millis(ms).map(|d| d.format("%Y-%m-%d").to_string())
+}
+
+/// Forecast for one series: fitted growth, confidence band and projected "full on" date
+fn forecast(points: &[(f64, f64)], robust: bool, free: Option<f64>, last_ms: i64) -> Value {
+    let fit = match fit_linear(points, robust) {
+        Some(f) => f,
+        None => return json!(null),
+    };
+    let t = t_quantile_95(points.len().saturating_sub(2));
+    let slope_low = fit.slope - t * fit.slope_se;
+    let slope_high = fit.slope + t * fit.slope_se;
+
+    let days = free.and_then(|f| days_until(f, fit.slope));
+    // Faster growth (upper slope) → earliest date; slower growth (lower slope) → latest date
+    let days_earliest = free.and_then(|f| days_until(f, slope_high));
+    let days_latest = free.and_then(|f| days_until(f, slope_low));
+
+    json!({
+        "growthPerDay": fit.slope.round() as i64,
+        "growthPerDayLow": slope_low.round() as i64,
+        "growthPerDayHigh": slope_high.round() as i64,
+        "intercept": fit.intercept.round() as i64,
+        "r2": (fit.r2 * 1000.0).round() / 1000.0,
+        "daysUntilFull": days.map(|d| (d * 10.0).round() / 10.0),
+        "daysUntilFullEarliest": days_earliest.map(|d| (d * 10.0).round() / 10.0),
+        "daysUntilFullLatest": days_latest.map(|d| (d * 10.0).round() / 10.0),
+        "fullOn": full_on(last_ms, days),
+        "fullOnEarliest": full_on(last_ms, days_earliest),
+        "fullOnLatest": full_on(last_ms, days_latest)
+    })
+}
+
+/// Compute a storage trend from history: regression over all snapshots of the root,
+/// confidence band and projected "disk full" date — per root and per top-level folder.
+/// method: "ols" (least squares, default) | "robust" (Theil–Sen)
+pub fn compute_trend(history: &[ScanSnapshot], root_path: &str, method: &str) -> Value {
     let relevant: Vec<&ScanSnapshot> = history.iter()
-        .filter(|s| s.root_path.eq_ignore_ascii_case(root_path))
+        .filter(|s| s.is_local() && crate::pathkey::same_path(&s.root_path, root_path))
         .collect();
 
     if relevant.len() < 2 {
@@ -207,26 +805,61 @@ pub fn compute_trend(history: &[ScanSnapshot], root_path: &str) -> Value {
         });
     }
 
+    let robust = method == "robust";
+    let x = |s: &ScanSnapshot| (s.timestamp_ms - first.timestamp_ms) as f64 / 86_400_000.0;
+
+    // Root: scanned size over time
+    let size_points: Vec<(f64, f64)> = relevant.iter().map(|s| (x(s), s.total_size as f64)).collect();
+    let size_fit = fit_linear(&size_points, robust);
+    let growth_per_day = size_fit.as_ref().map(|f| f.slope).unwrap_or(0.0);
     let size_change = last.total_size as i64 - first.total_size as i64;
-    let growth_per_day = size_change as f64 / days;
-    let growth_per_week = growth_per_day * 7.0;
-    let growth_per_month = growth_per_day * 30.0;
-
-    // Predict days until disk full (only if growing)
-    let days_until_full: Option<f64> = if growth_per_day > 0.0 {
-        // We'd need free space info — for now estimate from total
-        // The frontend can enhance this with actual free space
-        None
+
+    // Disk: used space (capacity - free) over time — includes growth outside the scanned root
+    let disk_points: Vec<(f64, f64)> = relevant.iter()
+        .filter(|s| s.disk_total > 0)
+        .map(|s| (x(s), s.disk_total.saturating_sub(s.disk_free) as f64))
+        .collect();
+    let free_now = if last.disk_total > 0 { Some(last.disk_free as f64) } else { None };
+    let disk_forecast = if disk_points.len() >= 2 {
+        forecast(&disk_points, robust, free_now, last.timestamp_ms)
     } else {
-        None
+        json!(null)
     };
+    let root_forecast = forecast(&size_points, robust, free_now, last.timestamp_ms);
+
+    // Prefer the disk-level projection, fall back to the scanned root's growth
+    let days_until_full = disk_forecast.get("daysUntilFull").and_then(|v| v.as_f64())
+        .or_else(|| root_forecast.get("daysUntilFull").and_then(|v| v.as_f64()));
+
+    // Per top-level folder: its own growth against the current free space
+    let mut folder_paths: std::collections::BTreeSet<&String> = std::collections::BTreeSet::new();
+    for s in &relevant {
+        folder_paths.extend(s.folder_sizes.keys());
+    }
+    let mut folders: Vec<Value> = folder_paths.into_iter().filter_map(|folder| {
+        let points: Vec<(f64, f64)> = relevant.iter()
+            .map(|s| (x(s), *s.folder_sizes.get(folder).unwrap_or(&0) as f64))
+            .collect();
+        let fc = forecast(&points, robust, free_now, last.timestamp_ms);
+        if fc.is_null() || fc["growthPerDay"].as_i64().unwrap_or(0) == 0 {
+            return None;
+        }
+        let mut obj = fc;
+        obj["path"] = json!(folder);
+        obj["currentSize"] = json!(last.folder_sizes.get(folder).copied().unwrap_or(0));
+        Some(obj)
+    }).collect();
+    folders.sort_by(|a, b| b["growthPerDay"].as_i64().unwrap_or(0).cmp(&a["growthPerDay"].as_i64().unwrap_or(0)));
+    folders.truncate(20);
 
     // Build data points for chart
     let data_points: Vec<Value> = relevant.iter().map(|s| {
         json!({
             "timestamp_ms": s.timestamp_ms,
             "total_size": s.total_size,
-            "total_files": s.total_files
+            "total_files": s.total_files,
+            "disk_total": s.disk_total,
+            "disk_free": s.disk_free
         })
     }).collect();
 
@@ -235,17 +868,68 @@ pub fn compute_trend(history: &[ScanSnapshot], root_path: &str) -> Value {
         "dataPoints": data_points,
         "snapshotCount": relevant.len(),
         "periodDays": (days * 10.0).round() / 10.0,
+        "method": if robust { "robust" } else { "ols" },
         "sizeChange": size_change,
         "growthPerDay": growth_per_day.round() as i64,
-        "growthPerWeek": growth_per_week.round() as i64,
-        "growthPerMonth": growth_per_month.round() as i64,
-        "daysUntilFull": days_until_full
+        "growthPerWeek": (growth_per_day * 7.0).round() as i64,
+        "growthPerMonth": (growth_per_day * 30.0).round() as i64,
+        "diskTotal": last.disk_total,
+        "diskFree": last.disk_free,
+        "daysUntilFull": days_until_full.map(|d| (d * 10.0).round() / 10.0),
+        "root": root_forecast,
+        "disk": disk_forecast,
+        "folders": folders
     })
 }
 
-/// Quick change detection: compare scan-meta.json timestamp with filesystem
-/// Returns changes detected without a full scan
-pub fn quick_change_check(data_dir: &Path) -> Value {
+/// Budget for the recursive change check — stops early once either limit is hit
+pub struct ChangeCheckBudget {
+    pub max_dirs: usize,
+    pub max_ms: u64,
+}
+
+impl Default for ChangeCheckBudget {
+    fn default() -> Self {
+        Self { max_dirs: 20_000, max_ms: 2_000 }
+    }
+}
+
+/// Known directories of the last scan: in-memory index if it was built from that scan,
+/// otherwise the directory index of the newest local snapshot of that root
+fn known_directories(data_dir: &Path, root_path: &str, scan_id: &str) -> Option<Vec<DirSize>> {
+    let active = crate::scan::dir_index_belongs_to(scan_id)
+        && crate::scan::with_scan(scan_id, |d| crate::pathkey::same_path(&d.root_path, root_path)).unwrap_or(false);
+    if active {
+        let dirs: Vec<DirSize> = crate::scan::dir_index_totals().into_iter()
+            .map(|(path, size, files)| DirSize { path, size, files })
+            .collect();
+        if !dirs.is_empty() {
+            return Some(dirs);
+        }
+    }
+    load_history(data_dir).iter().rev()
+        .filter(|s| s.is_local() && crate::pathkey::same_path(&s.root_path, root_path) && s.dir_index_bytes > 0)
+        .find_map(|s| load_dir_index(data_dir, &s.id))
+}
+
+/// Collapse changed directories to their top-most changed ancestors (rescan roots)
+fn collapse_subtrees(mut dirs: Vec<&DirSize>) -> Vec<&DirSize> {
+    dirs.sort_by(|a, b| a.path.len().cmp(&b.path.len()));
+    let mut roots: Vec<&DirSize> = Vec::new();
+    for d in dirs {
+        let covered = roots.iter().any(|r| crate::pathkey::is_below(&d.path, &r.path));
+        if !covered {
+            roots.push(d);
+        }
+    }
+    roots
+}
+
+/// Recursive change detection driven by the stored directory list of the last scan.
+/// A directory's mtime changes when entries are created, deleted or renamed in it — so
+/// every known directory is stat'ed (shallow first) until the budget is exhausted.
+/// Returns the changed subtrees plus an estimate of how stale the stored scan is.
+pub fn quick_change_check(data_dir: &Path, budget: &ChangeCheckBudget) -> Value {
     // Load last scan metadata
     let meta = match crate::scan::read_scan_meta(data_dir) {
         Some(m) => m,
@@ -253,6 +937,7 @@ pub fn quick_change_check(data_dir: &Path) -> Value {
     };
 
     let root_path = meta["root_path"].as_str().unwrap_or("");
+    let scan_id = meta["scan_id"].as_str().unwrap_or("");
     let saved_at = meta["saved_at"].as_i64().unwrap_or(0);
     let old_total_size = meta["total_size"].as_u64().unwrap_or(0);
     let old_files_count = meta["files_count"].as_u64().unwrap_or(0);
@@ -261,7 +946,6 @@ pub fn quick_change_check(data_dir: &Path) -> Value {
         return json!({ "hasBaseline": false, "message": "Kein Root-Pfad im letzten Scan" });
     }
 
-    // Quick filesystem check: just count entries in top-level directories
     let root = Path::new(root_path);
     if !root.exists() {
         return json!({
@@ -273,30 +957,235 @@ pub fn quick_change_check(data_dir: &Path) -> Value {
         });
     }
 
-    // Sample check: read directory modification times of top-level folders
-    let mut modified_folders: Vec<String> = Vec::new();
-    if let Ok(entries) = std::fs::read_dir(root) {
-        let baseline_time = std::time::UNIX_EPOCH + std::time::Duration::from_millis(saved_at as u64);
-        for entry in entries.flatten() {
-            if let Ok(meta) = entry.metadata() {
-                if let Ok(modified) = meta.modified() {
-                    if modified > baseline_time {
-                        modified_folders.push(entry.path().to_string_lossy().to_string());
-                    }
+    let mut dirs = match known_directories(data_dir, root_path, scan_id) {
+        Some(d) => d,
+        None => return json!({ "hasBaseline": false, "message": "Keine Verzeichnisliste des letzten Scans vorhanden" }),
+    };
+    // The index also holds every ancestor of the root (up to / or C:\) — their mtimes
+    // change all the time and would report the whole filesystem as changed
+    let root_key = crate::pathkey::key_with(root_path, crate::pathkey::case_insensitive(root_path));
+    dirs.retain(|d| d.path == root_key || crate::pathkey::is_below(&d.path, &root_key));
+    // Shallow first: if the budget runs out, the upper levels are covered
+    dirs.sort_by_key(|d| crate::pathkey::depth(&d.path));
+
+    let start = std::time::Instant::now();
+    let baseline_time = std::time::UNIX_EPOCH + std::time::Duration::from_millis(saved_at.max(0) as u64);
+    let mut changed: Vec<&DirSize> = Vec::new();
+    let mut missing = 0usize;
+    let mut checked = 0usize;
+    let mut complete = true;
+
+    for d in &dirs {
+        if checked >= budget.max_dirs || start.elapsed().as_millis() as u64 >= budget.max_ms {
+            complete = false;
+            break;
+        }
+        checked += 1;
+        match std::fs::metadata(crate::ospath::decode(&d.path)) {
+            Ok(m) => {
+                if m.modified().map(|t| t > baseline_time).unwrap_or(false) {
+                    changed.push(d);
                 }
             }
+            Err(_) => {
+                missing += 1;
+                changed.push(d);
+            }
         }
     }
 
-    let change_detected = !modified_folders.is_empty();
+    let changed_count = changed.len();
+    let subtrees = collapse_subtrees(changed);
+    let affected_bytes: u64 = subtrees.iter().map(|d| d.size).sum();
+    let root_size = old_total_size.max(1);
+    let age_hours = (chrono::Utc::now().timestamp_millis() - saved_at) as f64 / 3_600_000.0;
+    let subtree_json: Vec<Value> = subtrees.iter().take(100).map(|d| json!({
+        "path": d.path, "size": d.size, "files": d.files
+    })).collect();
+
     json!({
         "hasBaseline": true,
-        "changeDetected": change_detected,
-        "modifiedFolders": modified_folders.len(),
-        "modifiedFolderPaths": &modified_folders[..modified_folders.len().min(10)],
+        "changeDetected": changed_count > 0,
+        "modifiedFolders": changed_count,
+        "modifiedFolderPaths": subtrees.iter().take(10).map(|d| d.path.as_str()).collect::<Vec<_>>(),
+        "changedSubtrees": subtree_json,
+        "missingFolders": missing,
+        "checkedFolders": checked,
+        "knownFolders": dirs.len(),
+        "complete": complete,
+        "elapsedMs": start.elapsed().as_millis() as u64,
+        "staleness": {
+            "ageHours": (age_hours * 10.0).round() / 10.0,
+            "changedRatio": if checked > 0 { (changed_count as f64 / checked as f64 * 1000.0).round() / 1000.0 } else { 0.0 },
+            "affectedBytes": affected_bytes,
+            "affectedRatio": ((affected_bytes as f64 / root_size as f64).min(1.0) * 1000.0).round() / 1000.0
+        },
         "rootPath": root_path,
         "lastScanMs": saved_at,
         "lastTotalSize": old_total_size,
         "lastFilesCount": old_files_count
     })
 }
+
+// ============================================================
+// Export / Import (machine-to-machine comparison)
+// ============================================================
+
+const ARCHIVE_MAGIC: &str = "speicher-analyse-history";
+const ARCHIVE_VERSION: u32 = 1;
+
+/// Portable history archive (zstd-compressed bincode). Snapshot metadata is embedded as JSON
+/// so archives stay readable when ScanSnapshot gains new fields.
+#[derive(Serialize, Deserialize)]
+struct HistoryArchive {
+    magic: String,
+    version: u32,
+    hostname: String,
+    exported_ms: i64,
+    snapshots_json: String,
+    dir_indices: Vec<(String, Vec<DirSize>)>,
+    file_lists: Vec<(String, Vec<crate::scan::FileEntry>)>,
+}
+
+/// Hostname of this machine (COMPUTERNAME on Windows, HOSTNAME or /etc/hostname elsewhere)
+pub fn local_hostname() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| "unbekannt".to_string())
+}
+
+/// Export selected snapshots (all if `snapshot_ids` is empty) into a single archive file
+pub fn export_archive(data_dir: &Path, snapshot_ids: &[String], dest: &Path, include_file_lists: bool) -> Result<Value, String> {
+    let history = load_history(data_dir);
+    let selected: Vec<&ScanSnapshot> = history.iter()
+        .filter(|s| snapshot_ids.is_empty() || snapshot_ids.contains(&s.id))
+        .collect();
+    if selected.is_empty() {
+        return Err("Keine Snapshots für den Export ausgewählt".to_string());
+    }
+
+    let dir_indices: Vec<(String, Vec<DirSize>)> = selected.iter()
+        .filter_map(|s| load_dir_index(data_dir, &s.id).map(|d| (s.id.clone(), d)))
+        .collect();
+    let file_lists: Vec<(String, Vec<crate::scan::FileEntry>)> = if include_file_lists {
+        selected.iter()
+            .filter_map(|s| load_file_list(data_dir, &s.id).map(|f| (s.id.clone(), f)))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let archive = HistoryArchive {
+        magic: ARCHIVE_MAGIC.to_string(),
+        version: ARCHIVE_VERSION,
+        hostname: local_hostname(),
+        exported_ms: chrono::Utc::now().timestamp_millis(),
+        snapshots_json: serde_json::to_string(&selected).map_err(|e| e.to_string())?,
+        dir_indices,
+        file_lists,
+    };
+
+    let file = std::fs::File::create(dest).map_err(|e| format!("Export-Datei erstellen: {}", e))?;
+    let zst_writer = zstd::Encoder::new(file, 9)
+        .map_err(|e| format!("zstd-Encoder erstellen: {}", e))?;
+    let mut buf_writer = std::io::BufWriter::with_capacity(512 * 1024, zst_writer);
+    bincode::serialize_into(&mut buf_writer, &archive)
+        .map_err(|e| format!("Export schreiben: {}", e))?;
+    let zst_writer = buf_writer.into_inner().map_err(|e| format!("BufWriter flush: {}", e))?;
+    zst_writer.finish().map_err(|e| format!("zstd finalize: {}", e))?;
+
+    let size = std::fs::metadata(dest).map(|m| m.len()).unwrap_or(0);
+    tracing::info!(snapshots = selected.len(), file_lists = archive.file_lists.len(), size = size, "Scan-Verlauf exportiert");
+    Ok(json!({
+        "success": true,
+        "path": dest.to_string_lossy(),
+        "size": size,
+        "snapshots": selected.len(),
+        "dirIndices": archive.dir_indices.len(),
+        "fileLists": archive.file_lists.len(),
+        "hostname": archive.hostname
+    }))
+}
+
+/// Import an archive. Snapshots are namespaced by the exporting machine's hostname
+/// (ID prefix + `hostname` field), already imported snapshots are skipped.
+pub fn import_archive(data_dir: &Path, src: &Path) -> Result<Value, String> {
+    let file = std::fs::File::open(src).map_err(|e| format!("Import-Datei öffnen: {}", e))?;
+    let zst_reader = zstd::Decoder::new(file).map_err(|e| format!("zstd-Decoder: {}", e))?;
+    let buf_reader = std::io::BufReader::with_capacity(512 * 1024, zst_reader);
+    let archive: HistoryArchive = bincode::deserialize_from(buf_reader)
+        .map_err(|_| "Keine gültige Scan-Verlauf-Exportdatei".to_string())?;
+    if archive.magic != ARCHIVE_MAGIC {
+        return Err("Keine gültige Scan-Verlauf-Exportdatei".to_string());
+    }
+    if archive.version > ARCHIVE_VERSION {
+        return Err(format!("Export-Version {} wird nicht unterstützt (neuere App-Version nötig)", archive.version));
+    }
+
+    let snapshots: Vec<ScanSnapshot> = serde_json::from_str(&archive.snapshots_json)
+        .map_err(|e| format!("Snapshots im Export ungültig: {}", e))?;
+    let host_tag: String = archive.hostname.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+
+    let mut history = load_history(data_dir);
+    let mut dir_indices: HashMap<String, Vec<DirSize>> = archive.dir_indices.into_iter().collect();
+    let mut file_lists: HashMap<String, Vec<crate::scan::FileEntry>> = archive.file_lists.into_iter().collect();
+    let _ = std::fs::create_dir_all(data_dir.join(INDEX_DIR));
+
+    let mut imported = 0usize;
+    let mut skipped = 0usize;
+    let mut rejected = 0usize;
+    for mut snap in snapshots {
+        let original_id = snap.id.clone();
+        // Re-imports of an already namespaced snapshot keep their original host
+        if snap.hostname.is_empty() {
+            snap.hostname = archive.hostname.clone();
+            snap.id = format!("imp_{}_{}", host_tag, original_id);
+        }
+        // IDs become file names — a crafted export must not reach outside scan-history/
+        if !is_safe_id(&original_id) || !is_safe_id(&snap.id) {
+            tracing::warn!(snapshot_id = %original_id, "Snapshot mit ungültiger ID im Import verworfen");
+            rejected += 1;
+            continue;
+        }
+        if history.iter().any(|s| s.id == snap.id) {
+            skipped += 1;
+            continue;
+        }
+        snap.dir_index_bytes = 0;
+        snap.file_list_bytes = 0;
+        if let Some(dirs) = dir_indices.remove(&original_id) {
+            match save_dir_index(data_dir, &snap.id, &dirs) {
+                Ok(bytes) => snap.dir_index_bytes = bytes,
+                Err(e) => tracing::warn!(snapshot_id = %snap.id, error = %e, "Importierter Verzeichnis-Index nicht gespeichert"),
+            }
+        }
+        if let Some(files) = file_lists.remove(&original_id) {
+            let path = file_list_path(data_dir, &snap.id);
+            match crate::scan::write_file_list(&path, &files) {
+                Ok(()) => snap.file_list_bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+                Err(e) => tracing::warn!(snapshot_id = %snap.id, error = %e, "Importierte Dateiliste nicht gespeichert"),
+            }
+        }
+        history.push(snap);
+        imported += 1;
+    }
+
+    history.sort_by(|a, b| a.timestamp_ms.cmp(&b.timestamp_ms));
+    save_history(data_dir, &mut history)?;
+
+    tracing::info!(imported = imported, skipped = skipped, rejected = rejected, hostname = %archive.hostname, "Scan-Verlauf importiert");
+    Ok(json!({
+        "success": true,
+        "imported": imported,
+        "skipped": skipped,
+        "rejected": rejected,
+        "hostname": archive.hostname,
+        "exportedMs": archive.exported_ms
+    }))
+}
@@ -0,0 +1,639 @@
+use chrono::{Datelike, Local, TimeZone, Timelike};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tauri::Emitter;
+
+use crate::commands::{read_json_file, write_json_file, get_data_dir};
+
+const STATE_FILE: &str = "scan-schedule-state.json";
+const TICK: Duration = Duration::from_secs(30);
+/// How far back a missed cron slot is still caught up (app closed, deferred on battery, …)
+const CRON_LOOKBACK_MINUTES: i64 = 7 * 24 * 60;
+
+/// One entry of the `scanSchedules` preference
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Schedule {
+    pub id: String,
+    pub root_path: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Run every N minutes …
+    #[serde(default)]
+    pub interval_minutes: Option<u64>,
+    /// … or on a cron expression ("m h dom mon dow", local time)
+    #[serde(default)]
+    pub cron: Option<String>,
+    #[serde(default)]
+    pub thresholds: Thresholds,
+}
+
+fn default_true() -> bool { true }
+
+/// Alert conditions checked after each scheduled scan
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Thresholds {
+    /// Free space of the volume below this percentage
+    pub min_free_percent: Option<f64>,
+    /// Growth of the root since the previous snapshot, absolute
+    pub growth_bytes: Option<u64>,
+    /// Growth of the root since the previous snapshot, relative
+    pub growth_percent: Option<f64>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+struct QuietHours {
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default = "default_quiet_start")]
+    start: String,
+    #[serde(default = "default_quiet_end")]
+    end: String,
+}
+
+fn default_quiet_start() -> String { "22:00".to_string() }
+fn default_quiet_end() -> String { "07:00".to_string() }
+
+fn running() -> &'static AtomicBool {
+    static FLAG: OnceLock<AtomicBool> = OnceLock::new();
+    FLAG.get_or_init(|| AtomicBool::new(false))
+}
+
+// === Cron ===
+
+/// Minimal 5-field cron expression: `*`, numbers, lists, ranges and `*/n` steps
+pub struct CronExpr {
+    minutes: Vec<bool>,
+    hours: Vec<bool>,
+    days: Vec<bool>,
+    months: Vec<bool>,
+    weekdays: Vec<bool>,
+    days_any: bool,
+    weekdays_any: bool,
+}
+
+fn parse_cron_field(field: &str, min: u32, max: u32) -> Result<Vec<bool>, String> {
+    let mut set = vec![false; max as usize + 1];
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((r, s)) => (r, s.parse::<u32>().map_err(|_| format!("Ungültige Schrittweite: {}", part))?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            return Err(format!("Ungültige Schrittweite: {}", part));
+        }
+        let (lo, hi) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            let a = a.parse::<u32>().map_err(|_| format!("Ungültiger Wert: {}", part))?;
+            let b = b.parse::<u32>().map_err(|_| format!("Ungültiger Wert: {}", part))?;
+            (a, b)
+        } else {
+            let v = range.parse::<u32>().map_err(|_| format!("Ungültiger Wert: {}", part))?;
+            // "5/15" means "from 5 every 15"
+            (v, if part.contains('/') { max } else { v })
+        };
+        if lo < min || hi > max || lo > hi {
+            return Err(format!("Wert außerhalb {}-{}: {}", min, max, part));
+        }
+        let mut v = lo;
+        while v <= hi {
+            set[v as usize] = true;
+            v += step;
+        }
+    }
+    Ok(set)
+}
+
+impl CronExpr {
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!("Cron-Ausdruck braucht 5 Felder (Minute Stunde Tag Monat Wochentag): {}", expr));
+        }
+        let mut weekdays = parse_cron_field(fields[4], 0, 7)?;
+        // 7 = Sunday as well
+        if weekdays[7] { weekdays[0] = true; }
+        weekdays.truncate(7);
+        Ok(Self {
+            minutes: parse_cron_field(fields[0], 0, 59)?,
+            hours: parse_cron_field(fields[1], 0, 23)?,
+            days: parse_cron_field(fields[2], 1, 31)?,
+            months: parse_cron_field(fields[3], 1, 12)?,
+            weekdays,
+            days_any: fields[2] == "*",
+            weekdays_any: fields[4] == "*",
+        })
+    }
+
+    pub fn matches<T: Datelike + Timelike>(&self, t: &T) -> bool {
+        if !self.minutes[t.minute() as usize] || !self.hours[t.hour() as usize] || !self.months[t.month() as usize] {
+            return false;
+        }
+        self.day_matches(t)
+    }
+
+    /// Day-of-month / weekday rule alone (month, hour and minute not checked)
+    fn day_matches<T: Datelike>(&self, t: &T) -> bool {
+        let day_ok = self.days[t.day() as usize];
+        let weekday_ok = self.weekdays[t.weekday().num_days_from_sunday() as usize];
+        // Classic cron: if both day fields are restricted, either one may match
+        match (self.days_any, self.weekdays_any) {
+            (true, true) => true,
+            (true, false) => weekday_ok,
+            (false, true) => day_ok,
+            (false, false) => day_ok || weekday_ok,
+        }
+    }
+
+    /// Most recent matching minute at or before `now_ms` (within the lookback window)
+    pub fn last_slot_ms(&self, now_ms: i64) -> Option<i64> {
+        let now = Local.timestamp_millis_opt(now_ms).single()?;
+        let mut t = now.with_second(0)?.with_nanosecond(0)?;
+        for _ in 0..CRON_LOOKBACK_MINUTES {
+            if self.matches(&t) {
+                return Some(t.timestamp_millis());
+            }
+            t -= chrono::Duration::minutes(1);
+        }
+        None
+    }
+
+    /// Next matching minute after `now_ms`. Walks days, then the allowed hours and
+    /// minutes of a matching day — up to four years ahead so `29 2` still resolves.
+    pub fn next_slot_ms(&self, now_ms: i64) -> Option<i64> {
+        let now = Local.timestamp_millis_opt(now_ms).single()?;
+        let first = now.with_second(0)?.with_nanosecond(0)? + chrono::Duration::minutes(1);
+        let mut date = first.date_naive();
+        for day in 0..(4 * 366) {
+            if self.months[date.month() as usize] && self.day_matches(&date) {
+                let (from_hour, from_minute) = if day == 0 { (first.hour(), first.minute()) } else { (0, 0) };
+                for hour in (from_hour..24).filter(|h| self.hours[*h as usize]) {
+                    let start_minute = if hour == from_hour { from_minute } else { 0 };
+                    for minute in (start_minute..60).filter(|m| self.minutes[*m as usize]) {
+                        // None inside a DST gap — that minute does not exist locally
+                        if let Some(t) = Local.from_local_datetime(&date.and_hms_opt(hour, minute, 0)?).earliest() {
+                            return Some(t.timestamp_millis());
+                        }
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+}
+
+// === Preferences & state ===
+
+fn load_schedules(prefs: &Value) -> Vec<Schedule> {
+    prefs.get("scanSchedules")
+        .and_then(|v| serde_json::from_value::<Vec<Schedule>>(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+fn load_state() -> Value {
+    let state = read_json_file(STATE_FILE);
+    if state.is_object() { state } else { json!({}) }
+}
+
+fn update_state(id: &str, f: impl FnOnce(&mut serde_json::Map<String, Value>)) {
+    let mut state = load_state();
+    if let Some(obj) = state.as_object_mut() {
+        let entry = obj.entry(id.to_string()).or_insert_with(|| json!({}));
+        if let Some(e) = entry.as_object_mut() {
+            f(e);
+        }
+    }
+    if let Err(e) = write_json_file(STATE_FILE, &state) {
+        tracing::warn!(error = %e, "Zeitplan-Status konnte nicht gespeichert werden");
+    }
+}
+
+fn state_ms(state: &Value, id: &str, key: &str) -> Option<i64> {
+    state.get(id).and_then(|s| s.get(key)).and_then(|v| v.as_i64())
+}
+
+/// Timestamp of the slot the schedule is due for, or None if not due.
+/// The first time a schedule is seen counts as its last run — a new schedule
+/// must not fire immediately for slots before it existed.
+fn due_slot(schedule: &Schedule, state: &Value, now_ms: i64) -> Result<Option<i64>, String> {
+    let last_run = match state_ms(state, &schedule.id, "lastRunMs").or_else(|| state_ms(state, &schedule.id, "firstSeenMs")) {
+        Some(t) => t,
+        None => {
+            update_state(&schedule.id, |e| { e.insert("firstSeenMs".to_string(), json!(now_ms)); });
+            return Ok(None);
+        }
+    };
+    if let Some(expr) = schedule.cron.as_deref().filter(|c| !c.trim().is_empty()) {
+        let cron = CronExpr::parse(expr)?;
+        return Ok(cron.last_slot_ms(now_ms).filter(|slot| *slot > last_run));
+    }
+    match schedule.interval_minutes {
+        Some(m) if m > 0 => {
+            let slot = last_run + (m as i64) * 60_000;
+            Ok(if now_ms >= slot { Some(slot) } else { None })
+        }
+        _ => Err("Weder Intervall noch Cron-Ausdruck angegeben".to_string()),
+    }
+}
+
+fn next_run_ms(schedule: &Schedule, state: &Value, now_ms: i64) -> Option<i64> {
+    if let Some(expr) = schedule.cron.as_deref().filter(|c| !c.trim().is_empty()) {
+        return CronExpr::parse(expr).ok()?.next_slot_ms(now_ms);
+    }
+    let last = state_ms(state, &schedule.id, "lastRunMs").or_else(|| state_ms(state, &schedule.id, "firstSeenMs"))?;
+    schedule.interval_minutes.filter(|m| *m > 0).map(|m| (last + m as i64 * 60_000).max(now_ms))
+}
+
+fn parse_hhmm(s: &str) -> Option<u32> {
+    let (h, m) = s.trim().split_once(':')?;
+    let (h, m) = (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?);
+    if h < 24 && m < 60 { Some(h * 60 + m) } else { None }
+}
+
+fn in_quiet_hours(prefs: &Value) -> bool {
+    let quiet: QuietHours = match prefs.get("scheduleQuietHours").and_then(|v| serde_json::from_value(v.clone()).ok()) {
+        Some(q) => q,
+        None => return false,
+    };
+    if !quiet.enabled {
+        return false;
+    }
+    let (start, end) = match (parse_hhmm(&quiet.start), parse_hhmm(&quiet.end)) {
+        (Some(s), Some(e)) => (s, e),
+        _ => return false,
+    };
+    let now = Local::now();
+    let minute = now.hour() * 60 + now.minute();
+    if start <= end {
+        minute >= start && minute < end
+    } else {
+        // Window over midnight, e.g. 22:00–07:00
+        minute >= start || minute < end
+    }
+}
+
+// === Power ===
+
+/// (on battery, charge percent)
+#[cfg(windows)]
+fn power_state() -> (bool, Option<u64>) {
+    let status = tauri::async_runtime::block_on(crate::commands::get_battery_status()).unwrap_or(Value::Null);
+    (
+        status.get("onBattery").and_then(|v| v.as_bool()).unwrap_or(false),
+        status.get("percent").and_then(|v| v.as_u64()),
+    )
+}
+
+/// (on battery, charge percent) from /sys/class/power_supply
+#[cfg(not(windows))]
+fn power_state() -> (bool, Option<u64>) {
+    let read = |p: &std::path::Path, f: &str| std::fs::read_to_string(p.join(f)).map(|s| s.trim().to_string()).unwrap_or_default();
+    let entries = match std::fs::read_dir("/sys/class/power_supply") {
+        Ok(e) => e,
+        Err(_) => return (false, None),
+    };
+    let mut mains_online = false;
+    let mut has_mains = false;
+    let mut discharging = false;
+    let mut percent = None;
+    for entry in entries.flatten() {
+        let p = entry.path();
+        match read(&p, "type").as_str() {
+            "Mains" => {
+                has_mains = true;
+                mains_online |= read(&p, "online") == "1";
+            }
+            "Battery" => {
+                discharging |= read(&p, "status") == "Discharging";
+                if percent.is_none() {
+                    percent = read(&p, "capacity").parse::<u64>().ok();
+                }
+            }
+            _ => {}
+        }
+    }
+    let on_battery = if has_mains { !mains_online } else { discharging };
+    (on_battery, percent)
+}
+
+/// Reason to postpone background work in the current power state, if any
+fn battery_block_reason(prefs: &Value) -> Option<String> {
+    let (on_battery, percent) = power_state();
+    if !on_battery {
+        return None;
+    }
+    let disable_on_battery = prefs.get("disableBackgroundOnBattery").and_then(|v| v.as_bool()).unwrap_or(true);
+    let mode = prefs.get("energyMode").and_then(|v| v.as_str()).unwrap_or("auto");
+    let threshold = prefs.get("batteryThreshold").and_then(|v| v.as_u64()).unwrap_or(20);
+    if disable_on_battery {
+        return Some("Akkubetrieb (Hintergrund-Tasks deaktiviert)".to_string());
+    }
+    match mode {
+        "powersave" => Some("Akkubetrieb im Energiesparmodus".to_string()),
+        "auto" if percent.map(|p| p < threshold).unwrap_or(false) => {
+            Some(format!("Akkustand unter {}%", threshold))
+        }
+        _ => None,
+    }
+}
+
+// === Priority ===
+
+/// Lower CPU + I/O priority of the calling thread (background scans only)
+#[cfg(target_os = "linux")]
+fn lower_thread_priority() {
+    unsafe {
+        let tid = libc::syscall(libc::SYS_gettid) as libc::id_t;
+        libc::setpriority(libc::PRIO_PROCESS, tid, 19);
+        // ioprio_set(IOPRIO_WHO_PROCESS, tid, IOPRIO_CLASS_IDLE << 13)
+        libc::syscall(libc::SYS_ioprio_set, 1, tid as libc::c_long, 3 << 13);
+    }
+}
+
+/// Background mode lowers CPU, I/O and memory priority of the calling thread
+#[cfg(windows)]
+fn lower_thread_priority() {
+    use windows_sys::Win32::System::Threading::{GetCurrentThread, SetThreadPriority, THREAD_MODE_BACKGROUND_BEGIN};
+    unsafe {
+        SetThreadPriority(GetCurrentThread(), THREAD_MODE_BACKGROUND_BEGIN);
+    }
+}
+
+/// QoS class of the calling thread only — `setpriority(PRIO_PROCESS, 0, …)` would renice the whole app
+#[cfg(target_os = "macos")]
+fn lower_thread_priority() {
+    unsafe {
+        libc::pthread_set_qos_class_self_np(libc::qos_class_t::QOS_CLASS_BACKGROUND, 0);
+    }
+}
+
+/// No per-thread priority API used elsewhere — the scheduler runs at normal priority
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn lower_thread_priority() {}
+
+// === Running ===
+
+/// Start the scheduler thread (called once from setup)
+pub fn start(app: tauri::AppHandle) {
+    let spawned = std::thread::Builder::new()
+        .name("scan-scheduler".to_string())
+        .spawn(move || {
+            lower_thread_priority();
+            tracing::info!("Scan-Zeitplaner gestartet");
+            loop {
+                std::thread::sleep(TICK);
+                tick(&app);
+            }
+        });
+    if let Err(e) = spawned {
+        tracing::warn!(error = %e, "Scan-Zeitplaner konnte nicht gestartet werden");
+    }
+}
+
+fn tick(app: &tauri::AppHandle) {
+    let prefs = read_json_file("preferences.json");
+    let schedules = load_schedules(&prefs);
+    if schedules.iter().all(|s| !s.enabled) {
+        return;
+    }
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let state = load_state();
+
+    for schedule in schedules.iter().filter(|s| s.enabled) {
+        let slot = match due_slot(schedule, &state, now_ms) {
+            Ok(Some(slot)) => slot,
+            Ok(None) => continue,
+            Err(e) => {
+                tracing::debug!(schedule = %schedule.id, error = %e, "Zeitplan ungültig");
+                continue;
+            }
+        };
+
+        let defer = if in_quiet_hours(&prefs) {
+            Some("Ruhezeit".to_string())
+        } else if crate::commands::manual_scan_running() || running().load(Ordering::Relaxed) {
+            Some("Anderer Scan läuft".to_string())
+        } else {
+            battery_block_reason(&prefs)
+        };
+        if let Some(reason) = defer {
+            // Still due — picked up again on the next tick
+            tracing::debug!(schedule = %schedule.id, reason = %reason, "Geplanter Scan verschoben");
+            update_state(&schedule.id, |e| {
+                e.insert("lastStatus".to_string(), json!("deferred"));
+                e.insert("lastMessage".to_string(), json!(reason));
+            });
+            continue;
+        }
+
+        tracing::info!(schedule = %schedule.id, root = %schedule.root_path, slot_ms = slot, "Geplanter Scan fällig");
+        let _ = run_schedule(app, schedule, &prefs);
+        // One scan per tick — the next due schedule follows 30s later
+        return;
+    }
+}
+
+/// Run one scheduled scan now (blocking). Saves a snapshot and emits alerts.
+pub fn run_schedule(app: &tauri::AppHandle, schedule: &Schedule, prefs: &Value) -> Result<Value, String> {
+    if running().swap(true, Ordering::SeqCst) {
+        return Err("Ein geplanter Scan läuft bereits".to_string());
+    }
+    let result = run_schedule_inner(app, schedule, prefs);
+    running().store(false, Ordering::SeqCst);
+
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    update_state(&schedule.id, |e| {
+        // A failed or cancelled run still consumes its slot — no retry storm every 30s
+        e.insert("lastRunMs".to_string(), json!(now_ms));
+        match &result {
+            Ok(v) => {
+                e.insert("lastStatus".to_string(), json!("ok"));
+                e.insert("lastMessage".to_string(), json!(""));
+                e.insert("lastResult".to_string(), v.clone());
+            }
+            Err(err) => {
+                e.insert("lastStatus".to_string(), json!("error"));
+                e.insert("lastMessage".to_string(), json!(err));
+            }
+        }
+    });
+    if let Err(e) = &result {
+        tracing::warn!(schedule = %schedule.id, error = %e, "Geplanter Scan fehlgeschlagen");
+        let _ = app.emit("scheduled-scan-error", json!({
+            "scheduleId": &schedule.id,
+            "rootPath": &schedule.root_path,
+            "error": e
+        }));
+    }
+    result
+}
+
+fn run_schedule_inner(app: &tauri::AppHandle, schedule: &Schedule, prefs: &Value) -> Result<Value, String> {
+    if !std::path::Path::new(&schedule.root_path).exists() {
+        return Err(format!("Pfad existiert nicht: {}", schedule.root_path));
+    }
+    let start = std::time::Instant::now();
+    let scan_id = format!("sched_{}", chrono::Utc::now().timestamp_millis());
+
+    // A user-started scan always wins — the background scan gives up and retries next slot
+    let walked = crate::commands::walk_tree(&schedule.root_path, &crate::commands::manual_scan_running, &mut |_| {})
+        .ok_or("Geplanter Scan zugunsten eines manuellen Scans abgebrochen")?;
+    let elapsed = start.elapsed().as_secs_f64();
+
+    let data = crate::scan::ScanData {
+        scan_id: scan_id.clone(),
+        root_path: schedule.root_path.clone(),
+        files: walked.files,
+        dirs_scanned: walked.dirs_scanned,
+        total_size: walked.total_size,
+        elapsed_seconds: elapsed,
+        archives: Vec::new(),
+    };
+
+    // Not put into the scan store: that would replace the directory index the UI is browsing
+    let data_dir = get_data_dir();
+    let previous = crate::scan_history::load_history(&data_dir).into_iter()
+        .rev()
+        .find(|s| s.is_local() && crate::pathkey::same_path(&s.root_path, &schedule.root_path));
+    let snapshot = crate::scan_history::snapshot_of(&data, crate::scan::dir_totals_of(&data.root_path, &data.files));
+    let (disk_total, disk_free) = (snapshot.disk_total, snapshot.disk_free);
+    let snapshot_id = snapshot.id.clone();
+    let snapshot_saved = match crate::scan_history::save_snapshot_with_files(&data_dir, snapshot, Some(&data.files)) {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::debug!(schedule = %schedule.id, error = %e, "Geplanter Snapshot nicht gespeichert");
+            false
+        }
+    };
+
+    let alerts = check_thresholds(&schedule.thresholds, data.total_size, disk_total, disk_free, previous.as_ref());
+    for alert in &alerts {
+        tracing::info!(schedule = %schedule.id, alert = %alert["message"], "Schwellwert überschritten");
+        let mut payload = alert.clone();
+        if let Some(obj) = payload.as_object_mut() {
+            obj.insert("scheduleId".to_string(), json!(&schedule.id));
+            obj.insert("rootPath".to_string(), json!(&schedule.root_path));
+        }
+        let _ = app.emit("scheduled-scan-alert", payload);
+    }
+
+    tracing::info!(
+        schedule = %schedule.id, files = data.files.len(), errors = walked.errors_count,
+        elapsed_s = format!("{:.1}", elapsed), "Geplanter Scan abgeschlossen"
+    );
+    let summary = json!({
+        "scheduleId": &schedule.id,
+        "rootPath": &schedule.root_path,
+        "snapshotId": if snapshot_saved { Value::from(snapshot_id) } else { Value::Null },
+        "totalSize": data.total_size,
+        "filesFound": data.files.len(),
+        "dirsScanned": data.dirs_scanned,
+        "errorsCount": walked.errors_count,
+        "elapsedSeconds": (elapsed * 10.0).round() / 10.0,
+        "alerts": alerts
+    });
+    let notify = prefs.get("showScanNotification").and_then(|v| v.as_bool()).unwrap_or(true);
+    if notify || !alerts.is_empty() {
+        let _ = app.emit("scheduled-scan-complete", &summary);
+    }
+    Ok(summary)
+}
+
+fn check_thresholds(t: &Thresholds, total_size: u64, disk_total: u64, disk_free: u64, previous: Option<&crate::scan_history::ScanSnapshot>) -> Vec<Value> {
+    let mut alerts = Vec::new();
+    if let (Some(min), true) = (t.min_free_percent, disk_total > 0) {
+        let free_pct = disk_free as f64 / disk_total as f64 * 100.0;
+        if free_pct < min {
+            alerts.push(json!({
+                "kind": "lowFreeSpace",
+                "message": format!("Nur noch {:.1}% freier Speicher", free_pct),
+                "value": (free_pct * 10.0).round() / 10.0,
+                "threshold": min
+            }));
+        }
+    }
+    if let Some(prev) = previous {
+        let growth = total_size as i64 - prev.total_size as i64;
+        if let Some(limit) = t.growth_bytes {
+            if growth > 0 && growth as u64 >= limit {
+                alerts.push(json!({
+                    "kind": "growthBytes",
+                    "message": format!("Ordner ist seit dem letzten Scan um {} Bytes gewachsen", growth),
+                    "value": growth,
+                    "threshold": limit,
+                    "previousSnapshotId": &prev.id
+                }));
+            }
+        }
+        if let (Some(limit), true) = (t.growth_percent, prev.total_size > 0) {
+            let pct = growth as f64 / prev.total_size as f64 * 100.0;
+            if pct >= limit {
+                alerts.push(json!({
+                    "kind": "growthPercent",
+                    "message": format!("Ordner ist seit dem letzten Scan um {:.1}% gewachsen", pct),
+                    "value": (pct * 10.0).round() / 10.0,
+                    "threshold": limit,
+                    "previousSnapshotId": &prev.id
+                }));
+            }
+        }
+    }
+    alerts
+}
+
+/// Schedules with their last/next run for the settings UI
+pub fn status() -> Value {
+    let prefs = read_json_file("preferences.json");
+    let state = load_state();
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let schedules: Vec<Value> = load_schedules(&prefs).iter().map(|s| {
+        let cron_error = s.cron.as_deref()
+            .filter(|c| !c.trim().is_empty())
+            .and_then(|c| CronExpr::parse(c).err());
+        let st = state.get(&s.id).cloned().unwrap_or(json!({}));
+        json!({
+            "id": &s.id,
+            "rootPath": &s.root_path,
+            "enabled": s.enabled,
+            "intervalMinutes": s.interval_minutes,
+            "cron": &s.cron,
+            "cronError": cron_error,
+            "lastRunMs": st.get("lastRunMs"),
+            "lastStatus": st.get("lastStatus"),
+            "lastMessage": st.get("lastMessage"),
+            "lastResult": st.get("lastResult"),
+            "nextRunMs": if s.enabled { next_run_ms(s, &state, now_ms) } else { None }
+        })
+    }).collect();
+    json!({
+        "running": running().load(Ordering::Relaxed),
+        "quietHoursActive": in_quiet_hours(&prefs),
+        "schedules": schedules
+    })
+}
+
+/// Run a configured schedule immediately on a low-priority thread (ignores quiet hours/battery)
+pub fn run_now(app: tauri::AppHandle, schedule_id: &str) -> Result<Value, String> {
+    let prefs = read_json_file("preferences.json");
+    let schedule = load_schedules(&prefs).into_iter()
+        .find(|s| s.id == schedule_id)
+        .ok_or_else(|| format!("Zeitplan nicht gefunden: {}", schedule_id))?;
+    if running().load(Ordering::Relaxed) {
+        return Err("Ein geplanter Scan läuft bereits".to_string());
+    }
+    std::thread::Builder::new()
+        .name("scan-scheduler-run".to_string())
+        .spawn(move || {
+            lower_thread_priority();
+            let _ = run_schedule(&app, &schedule, &prefs);
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(json!({ "started": true, "scheduleId": schedule_id }))
+}
@@ -0,0 +1,373 @@
+use serde_json::{json, Value};
+use std::ffi::{OsStr, OsString};
+use std::io::Write;
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+
+// Native trash following the freedesktop.org Trash specification:
+//   <trash>/files/<name>             the trashed file or directory
+//   <trash>/info/<name>.trashinfo    original path + deletion date
+// Files on the home filesystem go to $XDG_DATA_HOME/Trash, files on other mounts to
+// $topdir/.Trash/$uid (admin-created, sticky) or $topdir/.Trash-$uid. Never copies
+// across filesystems — a trash operation is always a rename.
+
+const INFO_EXT: &str = ".trashinfo";
+
+fn uid() -> u32 {
+    // SAFETY: getuid has no preconditions and cannot fail
+    unsafe { libc::getuid() }
+}
+
+fn home_trash() -> Option<PathBuf> {
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local").join("share")))?;
+    Some(data_home.join("Trash"))
+}
+
+/// Device of a path that may not exist yet (first existing ancestor)
+fn device_of(path: &Path) -> Option<u64> {
+    path.ancestors().find_map(|p| std::fs::metadata(p).ok()).map(|m| m.dev())
+}
+
+/// Mount point of `path`: the topmost ancestor on the same device
+fn mount_root(path: &Path, dev: u64) -> PathBuf {
+    let mut top = path.to_path_buf();
+    for p in path.ancestors().skip(1) {
+        match std::fs::metadata(p) {
+            Ok(m) if m.dev() == dev => top = p.to_path_buf(),
+            _ => break,
+        }
+    }
+    top
+}
+
+fn create_private_dir(path: &Path) -> Result<(), String> {
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(path)
+        .map_err(|e| format!("Papierkorb-Verzeichnis {} erstellen: {}", path.display(), e))
+}
+
+/// Trash directory for a path on `dev` (+ the top directory relative paths refer to)
+fn trash_for(dev: u64, path: &Path) -> Result<(PathBuf, Option<PathBuf>), String> {
+    if let Some(home) = home_trash() {
+        if device_of(&home) == Some(dev) {
+            return Ok((home, None));
+        }
+    }
+    let topdir = mount_root(path, dev);
+    // $topdir/.Trash must be a real directory with the sticky bit — otherwise it is ignored
+    let shared = topdir.join(".Trash");
+    if let Ok(m) = std::fs::symlink_metadata(&shared) {
+        if m.is_dir() && m.permissions().mode() & 0o1000 != 0 {
+            let own = shared.join(uid().to_string());
+            if create_private_dir(&own).is_ok() {
+                return Ok((own, Some(topdir)));
+            }
+        }
+    }
+    let own = topdir.join(format!(".Trash-{}", uid()));
+    create_private_dir(&own)?;
+    Ok((own, Some(topdir)))
+}
+
+/// Top directory of a trash (for relative `Path=` entries), None for the home trash
+fn topdir_of(trash_dir: &Path) -> Option<PathBuf> {
+    let name = trash_dir.file_name()?.to_str()?;
+    if name.starts_with(".Trash-") {
+        return trash_dir.parent().map(Path::to_path_buf);
+    }
+    let parent = trash_dir.parent()?;
+    if parent.file_name() == Some(OsStr::new(".Trash")) {
+        return parent.parent().map(Path::to_path_buf);
+    }
+    None
+}
+
+/// RFC 2396 escaping of the raw path bytes (`/` stays)
+fn percent_encode(path: &Path) -> String {
+    let mut out = String::new();
+    for &b in path.as_os_str().as_bytes() {
+        if b.is_ascii_alphanumeric() || b"-_.!~*'()/".contains(&b) {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{:02X}", b));
+        }
+    }
+    out
+}
+
+fn percent_decode(s: &str) -> PathBuf {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() && bytes[i + 1].is_ascii_hexdigit() && bytes[i + 2].is_ascii_hexdigit() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("00");
+            out.push(u8::from_str_radix(hex, 16).unwrap_or(0));
+            i += 3;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    PathBuf::from(OsString::from_vec(out))
+}
+
+/// One entry of a trash directory
+pub struct TrashItem {
+    /// `<trash>/files/<name>` — identifies the item for restore/empty
+    pub trash_path: PathBuf,
+    pub original_path: PathBuf,
+    pub deletion_date: String,
+}
+
+impl TrashItem {
+    pub fn to_json(&self) -> Value {
+        let meta = std::fs::symlink_metadata(&self.trash_path).ok();
+        let is_dir = meta.as_ref().map(|m| m.is_dir()).unwrap_or(false);
+        let size = if is_dir {
+            walkdir::WalkDir::new(&self.trash_path).follow_links(false).into_iter()
+                .flatten()
+                .filter(|e| e.file_type().is_file())
+                .filter_map(|e| e.metadata().ok())
+                .map(|m| m.len())
+                .sum()
+        } else {
+            meta.map(|m| m.len()).unwrap_or(0)
+        };
+        let original = crate::ospath::encode(self.original_path.as_os_str());
+        crate::ospath::annotate(json!({
+            "id": crate::ospath::encode(self.trash_path.as_os_str()),
+            "path": original,
+            "name": self.original_path.file_name().map(crate::ospath::encode).unwrap_or_default(),
+            "deletionDate": self.deletion_date,
+            "isDir": is_dir,
+            "size": size
+        }))
+    }
+}
+
+/// Move `path` into the trash of its filesystem. Returns the trash location.
+pub fn trash(path: &Path) -> Result<TrashItem, String> {
+    let meta = std::fs::symlink_metadata(path)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    // Absolute path without resolving the entry itself (a symlink is trashed, not its target)
+    let name = path.file_name().ok_or_else(|| format!("Ungültiger Pfad: {}", path.display()))?;
+    let parent = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let original = std::fs::canonicalize(parent)
+        .map_err(|e| format!("{}: {}", parent.display(), e))?
+        .join(name);
+
+    let (trash_dir, topdir) = trash_for(meta.dev(), &original)?;
+    let files_dir = trash_dir.join("files");
+    let info_dir = trash_dir.join("info");
+    create_private_dir(&files_dir)?;
+    create_private_dir(&info_dir)?;
+
+    let stored_path = match &topdir {
+        Some(top) => original.strip_prefix(top).map(Path::to_path_buf).unwrap_or_else(|_| original.clone()),
+        None => original.clone(),
+    };
+    let deletion_date = chrono::Local::now().format("%Y-%m-%dT%H:%M:%S").to_string();
+    let info = format!("[Trash Info]\nPath={}\nDeletionDate={}\n", percent_encode(&stored_path), deletion_date);
+
+    // The .trashinfo file is created exclusively first — it reserves the name
+    for n in 1u32.. {
+        let mut trash_name = name.to_os_string();
+        if n > 1 {
+            trash_name.push(format!(".{}", n));
+        }
+        let mut info_name = trash_name.clone();
+        info_name.push(INFO_EXT);
+        let info_path = info_dir.join(&info_name);
+        let mut file = match std::fs::OpenOptions::new().write(true).create_new(true).open(&info_path) {
+            Ok(f) => f,
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(format!("Papierkorb-Info schreiben: {}", e)),
+        };
+        if let Err(e) = file.write_all(info.as_bytes()).and_then(|_| file.sync_all()) {
+            let _ = std::fs::remove_file(&info_path);
+            return Err(format!("Papierkorb-Info schreiben: {}", e));
+        }
+        let trash_path = files_dir.join(&trash_name);
+        // Orphaned data without .trashinfo must not be overwritten
+        if std::fs::symlink_metadata(&trash_path).is_ok() {
+            let _ = std::fs::remove_file(&info_path);
+            continue;
+        }
+        if let Err(e) = std::fs::rename(path, &trash_path) {
+            let _ = std::fs::remove_file(&info_path);
+            return Err(format!("In den Papierkorb verschieben fehlgeschlagen ({}): {}", path.display(), e));
+        }
+        tracing::debug!(path = %original.display(), trash = %trash_path.display(), "In Papierkorb verschoben");
+        return Ok(TrashItem { trash_path, original_path: original, deletion_date });
+    }
+    unreachable!("u32-Namensraum erschöpft")
+}
+
+fn info_path_of(trash_path: &Path) -> Option<PathBuf> {
+    let files_dir = trash_path.parent()?;
+    if files_dir.file_name() != Some(OsStr::new("files")) {
+        return None;
+    }
+    let mut info_name = trash_path.file_name()?.to_os_string();
+    info_name.push(INFO_EXT);
+    Some(files_dir.parent()?.join("info").join(info_name))
+}
+
+/// Parse `<trash>/info/<name>.trashinfo` of a `<trash>/files/<name>` entry
+pub fn read_item(trash_path: &Path) -> Result<TrashItem, String> {
+    let info_path = info_path_of(trash_path)
+        .ok_or_else(|| format!("Kein Papierkorb-Eintrag: {}", trash_path.display()))?;
+    let content = std::fs::read_to_string(&info_path)
+        .map_err(|_| format!("Papierkorb-Info fehlt: {}", info_path.display()))?;
+    let mut stored = None;
+    let mut deletion_date = String::new();
+    let mut in_section = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_section = line == "[Trash Info]";
+        } else if in_section {
+            if let Some(v) = line.strip_prefix("Path=") {
+                stored = Some(percent_decode(v));
+            } else if let Some(v) = line.strip_prefix("DeletionDate=") {
+                deletion_date = v.to_string();
+            }
+        }
+    }
+    let stored = stored.ok_or_else(|| format!("Papierkorb-Info ohne Pfad: {}", info_path.display()))?;
+    let original_path = if stored.is_absolute() {
+        stored
+    } else {
+        let trash_dir = info_path.parent().and_then(Path::parent).unwrap_or(Path::new("/"));
+        topdir_of(trash_dir).unwrap_or_else(|| PathBuf::from("/")).join(stored)
+    };
+    Ok(TrashItem { trash_path: trash_path.to_path_buf(), original_path, deletion_date })
+}
+
+/// Home trash plus the trashes of all mounted filesystems
+fn trash_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = home_trash().into_iter().collect();
+    let mounts = std::fs::read_to_string("/proc/self/mounts").unwrap_or_default();
+    for line in mounts.lines() {
+        let mount = match line.split_whitespace().nth(1) {
+            // Spaces etc. are octal-escaped (\040)
+            Some(m) => m.replace("\\040", " ").replace("\\011", "\t").replace("\\134", "\\"),
+            None => continue,
+        };
+        let top = PathBuf::from(mount);
+        for d in [top.join(".Trash").join(uid().to_string()), top.join(format!(".Trash-{}", uid()))] {
+            if d.join("files").is_dir() && !dirs.contains(&d) {
+                dirs.push(d);
+            }
+        }
+    }
+    dirs
+}
+
+/// All trashed items, newest first
+pub fn list() -> Vec<TrashItem> {
+    let mut items: Vec<TrashItem> = Vec::new();
+    for dir in trash_dirs() {
+        let entries = match std::fs::read_dir(dir.join("files")) {
+            Ok(e) => e,
+            Err(_) => continue,
+        };
+        items.extend(entries.flatten().filter_map(|e| read_item(&e.path()).ok()));
+    }
+    items.sort_by(|a, b| b.deletion_date.cmp(&a.deletion_date));
+    items
+}
+
+/// Accept only direct children of `<trash>/files` for one of our trashes — the IDs come
+/// from the frontend and must not name arbitrary folders that happen to be called "files".
+/// The parent is canonicalized, the entry itself not (it may be a symlink).
+fn checked_entry(trash_path: &Path) -> Result<PathBuf, String> {
+    let invalid = || format!("Kein Papierkorb-Eintrag: {}", trash_path.display());
+    let name = match trash_path.components().next_back() {
+        Some(std::path::Component::Normal(n)) => n.to_os_string(),
+        _ => return Err(invalid()),
+    };
+    let parent = trash_path.parent()
+        .and_then(|p| std::fs::canonicalize(p).ok())
+        .ok_or_else(invalid)?;
+    let known = trash_dirs().iter()
+        .filter_map(|d| std::fs::canonicalize(d.join("files")).ok())
+        .any(|files| files == parent);
+    if !known {
+        return Err(invalid());
+    }
+    Ok(parent.join(name))
+}
+
+/// Move a trashed item back to its original location (never overwrites)
+pub fn restore(trash_path: &Path) -> Result<PathBuf, String> {
+    let trash_path = &checked_entry(trash_path)?;
+    let item = read_item(trash_path)?;
+    if std::fs::symlink_metadata(&item.original_path).is_ok() {
+        return Err(format!("Am Ursprungsort existiert bereits ein Element: {}", item.original_path.display()));
+    }
+    if let Some(parent) = item.original_path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Zielordner erstellen: {}", e))?;
+    }
+    std::fs::rename(&item.trash_path, &item.original_path)
+        .map_err(|e| format!("Wiederherstellen fehlgeschlagen ({}): {}", item.original_path.display(), e))?;
+    if let Some(info) = info_path_of(trash_path) {
+        let _ = std::fs::remove_file(info);
+    }
+    Ok(item.original_path)
+}
+
+/// Permanently delete one trashed item (data first, then its .trashinfo)
+pub fn purge(trash_path: &Path) -> Result<u64, String> {
+    let trash_path = &checked_entry(trash_path)?;
+    let info = info_path_of(trash_path)
+        .ok_or_else(|| format!("Kein Papierkorb-Eintrag: {}", trash_path.display()))?;
+    let meta = std::fs::symlink_metadata(trash_path).ok();
+    let freed = match &meta {
+        Some(m) if m.is_dir() => {
+            let size = walkdir::WalkDir::new(trash_path).follow_links(false).into_iter()
+                .flatten()
+                .filter(|e| e.file_type().is_file())
+                .filter_map(|e| e.metadata().ok())
+                .map(|m| m.len())
+                .sum();
+            std::fs::remove_dir_all(trash_path).map_err(|e| format!("Löschen fehlgeschlagen: {}", e))?;
+            size
+        }
+        Some(m) => {
+            std::fs::remove_file(trash_path).map_err(|e| format!("Löschen fehlgeschlagen: {}", e))?;
+            m.len()
+        }
+        None => 0,
+    };
+    let _ = std::fs::remove_file(info);
+    Ok(freed)
+}
+
+/// Empty all trashes. Returns (items, bytes freed, errors).
+pub fn empty_all() -> (usize, u64, Vec<String>) {
+    let mut count = 0;
+    let mut freed = 0;
+    let mut errors = Vec::new();
+    for item in list() {
+        match purge(&item.trash_path) {
+            Ok(bytes) => {
+                count += 1;
+                freed += bytes;
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+    // Cached directory sizes refer to entries that are gone now
+    for dir in trash_dirs() {
+        let _ = std::fs::remove_file(dir.join("directorysizes"));
+    }
+    (count, freed, errors)
+}
@@ -1,35 +1,159 @@
+use crate::fileops::{ConflictPolicy, JobKind, JobOptions};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
 
 static UNDO_LOG: Mutex<Option<UndoLog>> = Mutex::new(None);
 
 const MAX_ENTRIES: usize = 100;
-const FILENAME: &str = "undo-log.json";
+const JOURNAL: &str = "undo-journal.log";
+/// Früheres Format (ganze Datei pro Aktion neu geschrieben) — wird einmalig übernommen
+const LEGACY_FILENAME: &str = "undo-log.json";
+/// Ab so vielen Datensätzen wird das Journal zu einem Snapshot verdichtet
+const COMPACT_AFTER: usize = 500;
+const BACKUP_DIR: &str = "undo-backups";
+static BACKUP_SEQ: AtomicU64 = AtomicU64::new(0);
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct UndoEntry {
     pub id: String,
     pub timestamp_ms: i64,
-    pub action_type: String,       // "delete_trash", "delete_permanent", "file_move", "toggle_autostart", "delete_autostart", "privacy_setting"
+    pub action_type: String,       // "delete_trash", "delete_permanent", "file_move", "file_copy", "file_rename", "create_folder", "write_file_content", "group", "toggle_autostart", "delete_autostart", "privacy_setting"
     pub description: String,       // Menschenlesbare Beschreibung (Deutsch)
     pub before_state: Value,       // Vorheriger Zustand (für Undo)
     pub can_undo: bool,            // true = umkehrbar, false = nur Protokoll
     pub undone: bool,              // true = wurde rückgängig gemacht
+    #[serde(default)]
+    pub can_redo: bool,            // true = liegt auf dem Redo-Stapel
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 struct UndoLog {
     version: u32,
     entries: Vec<UndoEntry>,
+    /// IDs rückgängig gemachter Dateiaktionen, zuletzt rückgängig gemachte am Ende
+    #[serde(default)]
+    redo_stack: Vec<String>,
+    /// Nächste laufende Nummer für IDs (`undo-<n>`), nie wiederverwendet
+    #[serde(skip)]
+    next_seq: u64,
+    /// Datensätze im Journal seit der letzten Verdichtung
+    #[serde(skip)]
+    records: usize,
+}
+
+// Undo-Journal: eine Zeile pro Änderung, `<crc32 hex> <json>\n`, nur angehängt.
+// Die erste Zeile ist nach einer Verdichtung ein Snapshot des ganzen Logs. Beim Laden
+// werden die Zeilen nacheinander angewendet, bis eine fehlt, unvollständig ist oder
+// die Prüfsumme nicht passt — alles davor bleibt erhalten, der Rest wird verworfen.
+// Verdichtet wird in eine temporäre Datei, die dann per Rename das Journal ersetzt.
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum Record {
+    Snapshot { next_seq: u64, entries: Vec<UndoEntry>, redo_stack: Vec<String> },
+    Add { entry: UndoEntry },
+    Undone { id: String, redoable: bool },
+    Redone { id: String },
+    /// `entry` ersetzt die Einträge `replaces` (an der Stelle des neuesten)
+    Group { entry: UndoEntry, replaces: Vec<String> },
+    /// Neuer `before_state` eines Eintrags (nur teilweise rückgängig gemacht)
+    State { id: String, before_state: Value },
+    Clear,
+}
+
+fn id_seq(id: &str) -> Option<u64> {
+    id.strip_prefix("undo-")?.parse().ok()
 }
 
 impl UndoLog {
     fn new() -> Self {
-        Self { version: 1, entries: Vec::new() }
+        Self { version: 2, entries: Vec::new(), redo_stack: Vec::new(), next_seq: 1, records: 0 }
+    }
+
+    fn next_id(&self) -> String {
+        format!("undo-{}", self.next_seq)
+    }
+
+    fn claim_id(&mut self, id: &str) {
+        if let Some(n) = id_seq(id) {
+            self.next_seq = self.next_seq.max(n + 1);
+        }
+    }
+
+    /// Wendet einen Datensatz an (live und beim Laden); liefert herausgefallene Einträge
+    fn apply(&mut self, record: Record) -> Vec<UndoEntry> {
+        match record {
+            Record::Snapshot { next_seq, entries, redo_stack } => {
+                self.entries = entries;
+                self.redo_stack = redo_stack;
+                self.next_seq = next_seq;
+                Vec::new()
+            }
+            Record::Add { entry } => {
+                self.claim_id(&entry.id);
+                self.entries.insert(0, entry);
+                // Eine neue Aktion verwirft den Redo-Stapel
+                for redo_id in self.redo_stack.drain(..) {
+                    if let Some(e) = self.entries.iter_mut().find(|e| e.id == redo_id) {
+                        e.can_redo = false;
+                    }
+                }
+                // Max-Einträge einhalten
+                if self.entries.len() > MAX_ENTRIES {
+                    return self.entries.drain(MAX_ENTRIES..).collect();
+                }
+                Vec::new()
+            }
+            Record::Undone { id, redoable } => {
+                if let Some(e) = self.entries.iter_mut().find(|e| e.id == id) {
+                    e.undone = true;
+                    if redoable {
+                        e.can_redo = true;
+                        self.redo_stack.push(id);
+                    }
+                }
+                Vec::new()
+            }
+            Record::Redone { id } => {
+                self.redo_stack.retain(|r| *r != id);
+                if let Some(e) = self.entries.iter_mut().find(|e| e.id == id) {
+                    e.undone = false;
+                    e.can_redo = false;
+                }
+                Vec::new()
+            }
+            Record::Group { entry, replaces } => {
+                self.claim_id(&entry.id);
+                let at = self.entries.iter().position(|e| replaces.contains(&e.id)).unwrap_or(0);
+                self.entries.retain(|e| !replaces.contains(&e.id));
+                self.entries.insert(at.min(self.entries.len()), entry);
+                Vec::new()
+            }
+            Record::State { id, before_state } => {
+                if let Some(e) = self.entries.iter_mut().find(|e| e.id == id) {
+                    e.before_state = before_state;
+                }
+                Vec::new()
+            }
+            Record::Clear => {
+                self.redo_stack.clear();
+                self.entries.drain(..).collect()
+            }
+        }
     }
 }
 
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
 fn get_data_dir() -> std::path::PathBuf {
     let appdata = std::env::var("APPDATA").unwrap_or_else(|_| ".".to_string());
     let dir = std::path::PathBuf::from(appdata).join("speicher-analyse");
@@ -37,18 +161,159 @@ fn get_data_dir() -> std::path::PathBuf {
     dir
 }
 
+fn encode_record(record: &Record) -> Result<Vec<u8>, String> {
+    let body = serde_json::to_vec(record).map_err(|e| e.to_string())?;
+    let mut line = format!("{:08x} ", crc32fast::hash(&body)).into_bytes();
+    line.extend_from_slice(&body);
+    line.push(b'\n');
+    Ok(line)
+}
+
+/// Eine vollständige Zeile (mit `\n`) mit passender Prüfsumme, sonst None
+fn decode_record(line: &[u8]) -> Option<Record> {
+    let line = line.strip_suffix(b"\n")?;
+    let (crc, body) = (line.get(..8)?, line.get(9..)?);
+    if line.get(8) != Some(&b' ') {
+        return None;
+    }
+    let crc = u32::from_str_radix(std::str::from_utf8(crc).ok()?, 16).ok()?;
+    if crc32fast::hash(body) != crc {
+        return None;
+    }
+    serde_json::from_slice(body).ok()
+}
+
+/// Spielt das Journal ab. `false` = beschädigtes Ende wurde verworfen.
+fn replay(bytes: &[u8]) -> (UndoLog, bool) {
+    let mut log = UndoLog::new();
+    let mut valid = 0usize;
+    for line in bytes.split_inclusive(|&b| b == b'\n') {
+        match decode_record(line) {
+            Some(record) => {
+                log.apply(record);
+                log.records += 1;
+                valid += line.len();
+            }
+            None => break,
+        }
+    }
+    let intact = valid == bytes.len();
+    if !intact {
+        tracing::warn!(valid_bytes = valid, dropped_bytes = bytes.len() - valid, entries = log.entries.len(),
+            "Undo-Journal: beschädigtes Ende verworfen, gültige Einträge übernommen");
+    }
+    (log, intact)
+}
+
+/// Übernimmt das alte `undo-log.json`; eine unlesbare Datei wird beiseitegelegt statt verworfen
+fn migrate_legacy(dir: &Path) -> UndoLog {
+    let path = dir.join(LEGACY_FILENAME);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(c) => c,
+        Err(_) => return UndoLog::new(),
+    };
+    let mut log = match serde_json::from_str::<UndoLog>(&content) {
+        Ok(old) => {
+            let mut log = UndoLog::new();
+            for e in &old.entries {
+                log.claim_id(&e.id);
+            }
+            log.entries = old.entries;
+            log.redo_stack = old.redo_stack;
+            log
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "Altes Undo-Log unlesbar — als .corrupt aufbewahrt");
+            let _ = std::fs::rename(&path, dir.join(format!("{}.corrupt", LEGACY_FILENAME)));
+            return UndoLog::new();
+        }
+    };
+    match compact(&mut log) {
+        Ok(()) => {
+            let _ = std::fs::rename(&path, dir.join(format!("{}.bak", LEGACY_FILENAME)));
+            tracing::info!(entries = log.entries.len(), "Undo-Log ins Journal übernommen");
+        }
+        Err(e) => tracing::warn!(error = %e, "Undo-Log konnte nicht ins Journal übernommen werden"),
+    }
+    log
+}
+
 fn load_log() -> UndoLog {
-    let path = get_data_dir().join(FILENAME);
-    match std::fs::read_to_string(&path) {
-        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|_| UndoLog::new()),
-        Err(_) => UndoLog::new(),
+    let dir = get_data_dir();
+    let path = dir.join(JOURNAL);
+    let bytes = match std::fs::read(&path) {
+        Ok(b) => b,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return migrate_legacy(&dir),
+        Err(e) => {
+            tracing::warn!(error = %e, "Undo-Journal nicht lesbar");
+            return UndoLog::new();
+        }
+    };
+    let (mut log, intact) = replay(&bytes);
+    if !intact {
+        // Beschädigte Fassung zur Diagnose behalten, dann sauber neu schreiben
+        let _ = std::fs::write(dir.join(format!("{}.corrupt", JOURNAL)), &bytes);
     }
+    if !intact || log.records > COMPACT_AFTER {
+        if let Err(e) = compact(&mut log) {
+            tracing::warn!(error = %e, "Undo-Journal konnte nicht verdichtet werden");
+        }
+    }
+    log
+}
+
+#[cfg(unix)]
+fn sync_dir(dir: &Path) {
+    if let Ok(d) = std::fs::File::open(dir) {
+        let _ = d.sync_all();
+    }
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) {}
+
+/// Schreibt das Log als einzelnen Snapshot und ersetzt das Journal atomar
+fn compact(log: &mut UndoLog) -> Result<(), String> {
+    let dir = get_data_dir();
+    let tmp = dir.join(format!("{}.tmp", JOURNAL));
+    let line = encode_record(&Record::Snapshot {
+        next_seq: log.next_seq,
+        entries: log.entries.clone(),
+        redo_stack: log.redo_stack.clone(),
+    })?;
+    let written = std::fs::File::create(&tmp)
+        .and_then(|mut f| f.write_all(&line).and_then(|_| f.sync_all()));
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(format!("Undo-Journal verdichten fehlgeschlagen: {}", e));
+    }
+    std::fs::rename(&tmp, dir.join(JOURNAL)).map_err(|e| format!("Undo-Journal ersetzen fehlgeschlagen: {}", e))?;
+    sync_dir(&dir);
+    log.records = 1;
+    Ok(())
+}
+
+fn append(record: &Record) -> Result<(), String> {
+    let line = encode_record(record)?;
+    let mut f = std::fs::OpenOptions::new().create(true).append(true).open(get_data_dir().join(JOURNAL))
+        .map_err(|e| format!("Undo-Journal öffnen fehlgeschlagen: {}", e))?;
+    f.write_all(&line).and_then(|_| f.sync_data())
+        .map_err(|e| format!("Undo-Journal schreiben fehlgeschlagen: {}", e))
 }
 
-fn save_log(log: &UndoLog) -> Result<(), String> {
-    let path = get_data_dir().join(FILENAME);
-    let content = serde_json::to_string_pretty(log).map_err(|e| e.to_string())?;
-    std::fs::write(&path, content).map_err(|e| format!("Undo-Log schreiben fehlgeschlagen: {}", e))
+/// Änderung ans Journal anhängen und erst danach anwenden — scheitert das Schreiben,
+/// bleiben Log und Sicherungsdateien unverändert. Aus dem Log gefallene Einträge
+/// nehmen ihre Sicherungsdateien mit.
+fn commit(log: &mut UndoLog, record: Record) -> Result<(), String> {
+    append(&record)?;
+    for dropped in log.apply(record) {
+        remove_backups(&dropped);
+    }
+    log.records += 1;
+    if log.records > COMPACT_AFTER {
+        compact(log)?;
+    }
+    Ok(())
 }
 
 fn with_log<F, R>(f: F) -> R
@@ -70,32 +335,20 @@ pub fn log_action(
     can_undo: bool,
 ) {
     with_log(|log| {
-        let id = format!("undo-{}", std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis());
-        let timestamp_ms = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map(|d| d.as_millis() as i64)
-            .unwrap_or(0);
-
-        log.entries.insert(0, UndoEntry {
-            id,
-            timestamp_ms,
+        let entry = UndoEntry {
+            id: log.next_id(),
+            timestamp_ms: now_ms(),
             action_type: action_type.to_string(),
             description: description.to_string(),
             before_state,
             can_undo,
             undone: false,
-        });
-
-        // Max-Einträge einhalten
-        if log.entries.len() > MAX_ENTRIES {
-            log.entries.truncate(MAX_ENTRIES);
-        }
-
-        if let Err(e) = save_log(log) {
+            can_redo: false,
+        };
+        if let Err(e) = commit(log, Record::Add { entry: entry.clone() }) {
             tracing::warn!(error = %e, "Undo-Log konnte nicht gespeichert werden");
+            // Nicht protokolliert — die Sicherungen gehören zu keinem Eintrag mehr
+            remove_backups(&entry);
         }
     });
 }
@@ -108,8 +361,24 @@ pub fn get_log() -> Vec<UndoEntry> {
 /// Markiert einen Eintrag als rückgängig gemacht. Die eigentliche Rückgängig-Aktion
 /// wird vom Command ausgeführt (z.B. Papierkorb wiederherstellen).
 pub fn mark_undone(id: &str) -> Result<(), String> {
+    set_undone(id, false)
+}
+
+/// Ersetzt den `before_state` eines Eintrags, der nur teilweise rückgängig gemacht
+/// werden konnte — der Eintrag bleibt offen für den Rest
+pub fn update_before_state(id: &str, before_state: Value) -> Result<(), String> {
+    with_log(|log| {
+        if !log.entries.iter().any(|e| e.id == id) {
+            return Err(format!("Eintrag '{}' nicht gefunden", id));
+        }
+        commit(log, Record::State { id: id.to_string(), before_state })
+    })
+}
+
+/// `redoable`: Eintrag kommt auf den Redo-Stapel (nur Dateiaktionen dieses Moduls)
+fn set_undone(id: &str, redoable: bool) -> Result<(), String> {
     with_log(|log| {
-        let entry = log.entries.iter_mut().find(|e| e.id == id)
+        let entry = log.entries.iter().find(|e| e.id == id)
             .ok_or_else(|| format!("Eintrag '{}' nicht gefunden", id))?;
         if !entry.can_undo {
             return Err("Diese Aktion kann nicht rückgängig gemacht werden".to_string());
@@ -117,9 +386,7 @@ pub fn mark_undone(id: &str) -> Result<(), String> {
         if entry.undone {
             return Err("Diese Aktion wurde bereits rückgängig gemacht".to_string());
         }
-        entry.undone = true;
-        save_log(log)?;
-        Ok(())
+        commit(log, Record::Undone { id: id.to_string(), redoable })
     })
 }
 
@@ -131,7 +398,391 @@ pub fn get_entry(id: &str) -> Option<UndoEntry> {
 /// Leert das gesamte Undo-Log.
 pub fn clear_log() -> Result<(), String> {
     with_log(|log| {
-        log.entries.clear();
-        save_log(log)
+        commit(log, Record::Clear)?;
+        compact(log)
+    })
+}
+
+// === Umkehrbare Dateiaktionen ===
+// Rename, Verschieben, Kopieren, Ordner anlegen und Datei bearbeiten werden hier
+// selbst ausgeführt, rückgängig gemacht und wiederholt — jeweils aus `before_state`.
+// Eine Gruppe bündelt mehrere Schritte und wird nur als Ganzes umgekehrt.
+
+/// Aktionstypen, die `FileAction` abdeckt
+pub fn is_file_action(action_type: &str) -> bool {
+    matches!(action_type, "file_rename" | "file_move" | "file_copy" | "create_folder" | "write_file_content" | "group")
+}
+
+enum FileAction {
+    /// file_rename: old_path → new_path
+    Rename { from: PathBuf, to: PathBuf },
+    /// file_move: (source, dest)
+    Move(Vec<(PathBuf, PathBuf)>),
+    /// file_copy: (source, target, Stand der Kopie) — nur neu angelegte Ziele
+    Copy(Vec<(PathBuf, PathBuf, Option<CopyStamp>)>),
+    /// create_folder: `created` = neu angelegte Ebenen, oberste zuerst
+    CreateFolder { path: PathBuf, created: Vec<PathBuf> },
+    /// write_file_content: Inhalt vorher/nachher als Sicherung (vorher None = Datei war neu)
+    WriteContent { path: PathBuf, before: Option<PathBuf>, after: PathBuf },
+    Group(Vec<FileAction>),
+}
+
+fn path_of(state: &Value, key: &str) -> Result<PathBuf, String> {
+    state.get(key).and_then(|v| v.as_str()).filter(|s| !s.is_empty())
+        .map(crate::ospath::decode)
+        .ok_or_else(|| format!("Undo-Daten unvollständig: '{}' fehlt", key))
+}
+
+fn path_pairs(state: &Value, list: &str, from: &str, to: &str) -> Result<Vec<(PathBuf, PathBuf)>, String> {
+    state.get(list).and_then(|v| v.as_array())
+        .ok_or_else(|| format!("Undo-Daten unvollständig: '{}' fehlt", list))?
+        .iter()
+        .map(|m| Ok((path_of(m, from)?, path_of(m, to)?)))
+        .collect()
+}
+
+impl FileAction {
+    fn parse(action_type: &str, state: &Value) -> Result<Self, String> {
+        Ok(match action_type {
+            "file_rename" => Self::Rename { from: path_of(state, "old_path")?, to: path_of(state, "new_path")? },
+            "file_move" => Self::Move(path_pairs(state, "moves", "source", "dest")?),
+            "file_copy" => Self::Copy(
+                state.get("copies").and_then(|v| v.as_array())
+                    .ok_or_else(|| "Undo-Daten unvollständig: 'copies' fehlt".to_string())?
+                    .iter()
+                    .map(|m| Ok((path_of(m, "source")?, path_of(m, "target")?, CopyStamp::from_state(m))))
+                    .collect::<Result<_, String>>()?,
+            ),
+            "create_folder" => Self::CreateFolder {
+                path: path_of(state, "path")?,
+                created: state.get("created").and_then(|v| v.as_array())
+                    .map(|a| a.iter().filter_map(|v| v.as_str()).map(crate::ospath::decode).collect())
+                    .unwrap_or_default(),
+            },
+            "write_file_content" => Self::WriteContent {
+                path: path_of(state, "path")?,
+                before: path_of(state, "before").ok(),
+                after: path_of(state, "after")?,
+            },
+            "group" => Self::Group(
+                state.get("steps").and_then(|v| v.as_array())
+                    .ok_or_else(|| "Undo-Daten unvollständig: 'steps' fehlt".to_string())?
+                    .iter()
+                    .map(|step| Self::parse(step["action_type"].as_str().unwrap_or(""), &step["before_state"]))
+                    .collect::<Result<_, _>>()?,
+            ),
+            other => return Err(format!("'{}' ist keine umkehrbare Dateiaktion", other)),
+        })
+    }
+
+    /// Ausführen — beim ersten Mal und beim Wiederholen
+    fn apply(&self) -> Result<(), String> {
+        match self {
+            Self::Rename { from, to } => rename_checked(from, to),
+            Self::Move(moves) => transfer(JobKind::Move, moves.clone()),
+            Self::Copy(copies) => transfer(JobKind::Copy, copies.iter().map(|(src, target, _)| (src.clone(), target.clone())).collect()),
+            Self::CreateFolder { path, .. } => {
+                if std::fs::symlink_metadata(path).is_ok() {
+                    return Err(format!("{} existiert bereits", path.display()));
+                }
+                std::fs::create_dir_all(path).map_err(|e| format!("Ordner {} erstellen: {}", path.display(), e))
+            }
+            Self::WriteContent { path, before, after } => {
+                expect_content(path, before.as_deref())?;
+                std::fs::write(path, read_backup(after)?).map_err(|e| format!("{} schreiben: {}", path.display(), e))
+            }
+            Self::Group(steps) => {
+                for (i, step) in steps.iter().enumerate() {
+                    if let Err(e) = step.apply() {
+                        // Gruppe bleibt unverändert: bereits ausgeführte Schritte zurücknehmen
+                        for done in steps[..i].iter().rev() {
+                            if let Err(r) = done.revert() {
+                                tracing::warn!(error = %r, "Gruppenschritt konnte nicht zurückgenommen werden");
+                            }
+                        }
+                        return Err(e);
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Rückgängig machen
+    fn revert(&self) -> Result<(), String> {
+        match self {
+            Self::Rename { from, to } => rename_checked(to, from),
+            Self::Move(moves) => transfer(JobKind::Move, moves.iter().map(|(src, dest)| (dest.clone(), src.clone())).collect()),
+            Self::Copy(copies) => {
+                // Erst alle Ziele prüfen — eine inzwischen geänderte Kopie wird nicht gelöscht
+                for (_, target, stamp) in copies {
+                    if let (Some(stamp), Some(current)) = (stamp, CopyStamp::of(target)) {
+                        if current != *stamp {
+                            return Err(format!("{} wurde inzwischen geändert", target.display()));
+                        }
+                    }
+                }
+                for (_, target, _) in copies {
+                    remove_copy(target)?;
+                }
+                Ok(())
+            }
+            Self::CreateFolder { path, created } => {
+                // Ältere Einträge kennen nur den Ordner selbst
+                let dirs = if created.is_empty() { std::slice::from_ref(path) } else { created.as_slice() };
+                for dir in dirs.iter().rev() {
+                    std::fs::remove_dir(dir).map_err(|e| format!("Ordner {} entfernen (nicht leer?): {}", dir.display(), e))?;
+                }
+                Ok(())
+            }
+            Self::WriteContent { path, before, after } => {
+                expect_content(path, Some(after))?;
+                match before {
+                    Some(b) => std::fs::write(path, read_backup(b)?).map_err(|e| format!("{} schreiben: {}", path.display(), e)),
+                    None => std::fs::remove_file(path).map_err(|e| format!("{} entfernen: {}", path.display(), e)),
+                }
+            }
+            Self::Group(steps) => {
+                for (i, step) in steps.iter().enumerate().rev() {
+                    if let Err(e) = step.revert() {
+                        for undone in &steps[i + 1..] {
+                            if let Err(r) = undone.apply() {
+                                tracing::warn!(error = %r, "Gruppenschritt konnte nicht wiederhergestellt werden");
+                            }
+                        }
+                        return Err(e);
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Sicherungsdateien, die mit dem Eintrag verschwinden
+    fn backups(&self) -> Vec<PathBuf> {
+        match self {
+            Self::WriteContent { before, after, .. } => before.iter().chain(std::iter::once(after)).cloned().collect(),
+            Self::Group(steps) => steps.iter().flat_map(|s| s.backups()).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Umbenennen ohne zu überschreiben (reine Groß-/Kleinschreibung ist erlaubt)
+fn rename_checked(from: &Path, to: &Path) -> Result<(), String> {
+    if std::fs::symlink_metadata(to).is_ok() && !crate::pathkey::same_entry(from, to) {
+        return Err(format!("{} existiert bereits", to.display()));
+    }
+    std::fs::rename(from, to).map_err(|e| format!("{} → {}: {}", from.display(), to.display(), e))
+}
+
+/// Verschieben/Kopieren über die Auftrags-Engine (volumenübergreifend, geprüft, mit Rollback)
+fn transfer(kind: JobKind, items: Vec<(PathBuf, PathBuf)>) -> Result<(), String> {
+    for (src, target) in &items {
+        if std::fs::symlink_metadata(src).is_err() {
+            return Err(format!("{} existiert nicht mehr", src.display()));
+        }
+        if std::fs::symlink_metadata(target).is_ok() {
+            return Err(format!("{} existiert bereits", target.display()));
+        }
+    }
+    let report = crate::fileops::run_to(None, &crate::fileops::new_job_id(), kind, items, JobOptions::new(kind, ConflictPolicy::Skip));
+    let skipped = report["skipped"].as_array().map(Vec::len).unwrap_or(0);
+    if report["status"] == "completed" && skipped == 0 {
+        return Ok(());
+    }
+    Err(report["failed"].get(0).and_then(|f| f["error"].as_str()).map(String::from)
+        .unwrap_or_else(|| format!("{} Element(e) übersprungen — Ziel inzwischen belegt", skipped)))
+}
+
+/// Stand einer Kopie beim Protokollieren: Anzahl, Gesamtgröße und jüngste Änderungszeit
+/// der Dateien (bei einem Ordner über alle Dateien darin). Ältere Einträge haben keinen.
+#[derive(PartialEq, Debug)]
+pub struct CopyStamp {
+    files: u64,
+    size: u64,
+    modified_ms: i64,
+}
+
+impl CopyStamp {
+    /// None, wenn das Ziel (oder ein Teil davon) nicht lesbar ist
+    pub fn of(path: &Path) -> Option<Self> {
+        let mut stamp = Self { files: 0, size: 0, modified_ms: 0 };
+        for entry in walkdir::WalkDir::new(path).follow_links(false) {
+            let meta = entry.ok()?.metadata().ok()?;
+            if meta.is_dir() {
+                continue;
+            }
+            let modified_ms = meta.modified().ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_millis() as i64)
+                .unwrap_or(0);
+            stamp.files += 1;
+            stamp.size += meta.len();
+            stamp.modified_ms = stamp.modified_ms.max(modified_ms);
+        }
+        Some(stamp)
+    }
+
+    /// Trägt den Stand in einen Eintrag von `copies` ein
+    pub fn record(&self, copy: &mut Value) {
+        copy["files"] = json!(self.files);
+        copy["size"] = json!(self.size);
+        copy["modified_ms"] = json!(self.modified_ms);
+    }
+
+    fn from_state(state: &Value) -> Option<Self> {
+        Some(Self {
+            files: state.get("files")?.as_u64()?,
+            size: state.get("size")?.as_u64()?,
+            modified_ms: state.get("modified_ms")?.as_i64()?,
+        })
+    }
+}
+
+fn remove_copy(target: &Path) -> Result<(), String> {
+    let removed = match std::fs::symlink_metadata(target) {
+        Ok(m) if m.is_dir() => std::fs::remove_dir_all(target),
+        Ok(_) => std::fs::remove_file(target),
+        // Schon entfernt
+        Err(_) => return Ok(()),
+    };
+    removed.map_err(|e| format!("Kopie {} entfernen: {}", target.display(), e))
+}
+
+fn read_backup(path: &Path) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|e| format!("Sicherung {} lesen: {}", path.display(), e))
+}
+
+/// Inhalt muss dem erwarteten Stand entsprechen (None = Datei existiert nicht),
+/// sonst würde eine spätere Änderung überschrieben
+fn expect_content(path: &Path, expected: Option<&Path>) -> Result<(), String> {
+    let current = match std::fs::read(path) {
+        Ok(c) => Some(c),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(format!("{} lesen: {}", path.display(), e)),
+    };
+    let expected = match expected {
+        Some(b) => Some(read_backup(b)?),
+        None => None,
+    };
+    if current != expected {
+        return Err(format!("{} wurde inzwischen geändert", path.display()));
+    }
+    Ok(())
+}
+
+fn remove_backups(entry: &UndoEntry) {
+    if !is_file_action(&entry.action_type) {
+        return;
+    }
+    if let Ok(action) = FileAction::parse(&entry.action_type, &entry.before_state) {
+        for b in action.backups() {
+            let _ = std::fs::remove_file(b);
+        }
+    }
+}
+
+/// Sichert alten und neuen Inhalt einer Datei vor dem Schreiben; liefert den `before_state`
+/// für "write_file_content"
+pub fn save_content_backups(path: &Path, new_content: &[u8]) -> Result<Value, String> {
+    let dir = get_data_dir().join(BACKUP_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Sicherungsordner erstellen: {}", e))?;
+    let stem = format!("{}-{}", now_ms(), BACKUP_SEQ.fetch_add(1, Ordering::Relaxed));
+    let before = match std::fs::read(path) {
+        Ok(old) => {
+            let b = dir.join(format!("{}.before", stem));
+            std::fs::write(&b, old).map_err(|e| format!("Sicherung schreiben: {}", e))?;
+            Some(b)
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(format!("{} lesen: {}", path.display(), e)),
+    };
+    let after = dir.join(format!("{}.after", stem));
+    std::fs::write(&after, new_content).map_err(|e| format!("Sicherung schreiben: {}", e))?;
+    let enc = |p: &Path| crate::ospath::encode(p.as_os_str());
+    Ok(json!({ "path": enc(path), "before": before.as_deref().map(enc), "after": enc(&after) }))
+}
+
+/// Führt eine Dateiaktion aus und protokolliert sie (nur bei Erfolg)
+pub fn perform(action_type: &str, description: &str, state: Value) -> Result<(), String> {
+    let action = FileAction::parse(action_type, &state)?;
+    if let Err(e) = action.apply() {
+        for b in action.backups() {
+            let _ = std::fs::remove_file(b);
+        }
+        return Err(e);
+    }
+    log_action(action_type, description, state, true);
+    Ok(())
+}
+
+/// Macht eine protokollierte Dateiaktion rückgängig; sie landet danach auf dem Redo-Stapel
+pub fn undo_file_action(id: &str) -> Result<Value, String> {
+    let entry = get_entry(id).ok_or_else(|| format!("Eintrag '{}' nicht gefunden", id))?;
+    if !entry.can_undo {
+        return Err("Diese Aktion kann nicht rückgängig gemacht werden".to_string());
+    }
+    if entry.undone {
+        return Err("Diese Aktion wurde bereits rückgängig gemacht".to_string());
+    }
+    FileAction::parse(&entry.action_type, &entry.before_state)?.revert()?;
+    set_undone(id, true)?;
+    tracing::info!(id = %id, action = %entry.action_type, "Aktion rückgängig gemacht");
+    Ok(json!({ "success": true, "message": format!("Rückgängig gemacht: {}", entry.description) }))
+}
+
+/// Wiederholt die zuletzt rückgängig gemachte Aktion. Eine angegebene ID muss oben
+/// auf dem Redo-Stapel liegen — sonst würde die Reihenfolge der Änderungen vertauscht.
+pub fn redo(id: Option<&str>) -> Result<Value, String> {
+    let top = with_log(|log| log.redo_stack.last().cloned())
+        .ok_or_else(|| "Keine Aktion zum Wiederholen".to_string())?;
+    if id.is_some_and(|id| id != top) {
+        return Err("Nur die zuletzt rückgängig gemachte Aktion kann wiederholt werden".to_string());
+    }
+    let entry = get_entry(&top).ok_or_else(|| format!("Eintrag '{}' nicht gefunden", top))?;
+    FileAction::parse(&entry.action_type, &entry.before_state)?.apply()?;
+    with_log(|log| commit(log, Record::Redone { id: top.clone() }))?;
+    tracing::info!(id = %top, action = %entry.action_type, "Aktion wiederholt");
+    Ok(json!({ "success": true, "id": top, "message": format!("Wiederholt: {}", entry.description) }))
+}
+
+/// Fasst protokollierte Dateiaktionen zu einem Eintrag zusammen, der nur als Ganzes
+/// rückgängig gemacht und wiederholt wird
+pub fn group_entries(ids: &[String], description: &str) -> Result<UndoEntry, String> {
+    with_log(|log| {
+        let mut positions: Vec<usize> = Vec::new();
+        for id in ids {
+            let pos = log.entries.iter().position(|e| e.id == *id)
+                .ok_or_else(|| format!("Eintrag '{}' nicht gefunden", id))?;
+            let e = &log.entries[pos];
+            if !e.can_undo || e.undone || !is_file_action(&e.action_type) {
+                return Err(format!("\"{}\" kann nicht gruppiert werden", e.description));
+            }
+            positions.push(pos);
+        }
+        positions.sort_unstable();
+        positions.dedup();
+        if positions.len() < 2 {
+            return Err("Eine Gruppe braucht mindestens zwei Einträge".to_string());
+        }
+        // Log ist neueste zuerst — Schritte laufen in ursprünglicher Reihenfolge
+        let steps: Vec<Value> = positions.iter().rev().map(|&p| {
+            let e = &log.entries[p];
+            json!({ "action_type": e.action_type, "description": e.description, "before_state": e.before_state })
+        }).collect();
+        let group = UndoEntry {
+            id: log.next_id(),
+            timestamp_ms: log.entries[positions[0]].timestamp_ms,
+            action_type: "group".to_string(),
+            description: description.to_string(),
+            before_state: json!({ "steps": steps }),
+            can_undo: true,
+            undone: false,
+            can_redo: false,
+        };
+        let replaces = positions.iter().map(|&p| log.entries[p].id.clone()).collect();
+        commit(log, Record::Group { entry: group.clone(), replaces })?;
+        Ok(group)
     })
 }
@@ -0,0 +1,182 @@
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+/// Quiet period before a batch of filesystem events is applied
+const DEBOUNCE: Duration = Duration::from_millis(750);
+/// Upper bound for one batch — a constant stream of events must still produce updates
+const MAX_BATCH_WAIT: Duration = Duration::from_secs(5);
+
+struct ActiveWatch {
+    scan_id: String,
+    root_path: String,
+    stop: Arc<AtomicBool>,
+    // Dropping the watcher unregisters inotify / ReadDirectoryChangesW
+    _watcher: notify::RecommendedWatcher,
+}
+
+fn active() -> &'static Mutex<Option<ActiveWatch>> {
+    static ACTIVE: OnceLock<Mutex<Option<ActiveWatch>>> = OnceLock::new();
+    ACTIVE.get_or_init(|| Mutex::new(None))
+}
+
+/// Start watching the root of `scan_id`. Replaces any previous watch (only one scan is live).
+pub fn start(app: tauri::AppHandle, scan_id: &str) -> Result<Value, String> {
+    use notify::Watcher;
+
+    let root_path = crate::scan::with_scan(scan_id, |d| d.root_path.clone())
+        .ok_or("Scan-Daten nicht gefunden")?;
+    stop();
+
+    let (tx, rx) = std::sync::mpsc::channel::<notify::Result<notify::Event>>();
+    let mut watcher = notify::recommended_watcher(tx)
+        .map_err(|e| format!("Dateisystem-Überwachung konnte nicht gestartet werden: {}", e))?;
+    watcher.watch(Path::new(&root_path), notify::RecursiveMode::Recursive)
+        .map_err(|e| format!("Pfad kann nicht überwacht werden: {}", e))?;
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let thread_stop = stop_flag.clone();
+    // Registered before the thread runs — its exit path clears the registration
+    *active().lock().unwrap_or_else(|e| e.into_inner()) = Some(ActiveWatch {
+        scan_id: scan_id.to_string(),
+        root_path: root_path.clone(),
+        stop: stop_flag,
+        _watcher: watcher,
+    });
+    let sid = scan_id.to_string();
+    std::thread::spawn(move || run_loop(app, sid, rx, thread_stop));
+
+    tracing::info!(scan_id = %scan_id, root = %root_path, "Live-Überwachung gestartet");
+    Ok(json!({ "watching": true, "scanId": scan_id, "rootPath": root_path }))
+}
+
+/// Stop the active watch (no-op if none)
+pub fn stop() -> bool {
+    let mut guard = active().lock().unwrap_or_else(|e| e.into_inner());
+    match guard.take() {
+        Some(w) => {
+            w.stop.store(true, Ordering::Relaxed);
+            tracing::info!(scan_id = %w.scan_id, "Live-Überwachung beendet");
+            true
+        }
+        None => false,
+    }
+}
+
+/// Stop the watch only if it belongs to `scan_id` (scan released from memory)
+pub fn stop_for_scan(scan_id: &str) {
+    let watching = active().lock().unwrap_or_else(|e| e.into_inner())
+        .as_ref().map(|w| w.scan_id == scan_id).unwrap_or(false);
+    if watching {
+        stop();
+    }
+}
+
+pub fn status() -> Value {
+    let guard = active().lock().unwrap_or_else(|e| e.into_inner());
+    match guard.as_ref() {
+        Some(w) => json!({ "watching": true, "scanId": w.scan_id, "rootPath": w.root_path }),
+        None => json!({ "watching": false }),
+    }
+}
+
+/// Collect events until the debounce window is quiet, then apply the batch to the scan
+fn run_loop(app: tauri::AppHandle, scan_id: String, rx: std::sync::mpsc::Receiver<notify::Result<notify::Event>>, stop: Arc<AtomicBool>) {
+    let mut pending: HashSet<PathBuf> = HashSet::new();
+    let mut batch_started: Option<Instant> = None;
+
+    while !stop.load(Ordering::Relaxed) {
+        match rx.recv_timeout(DEBOUNCE) {
+            Ok(Ok(event)) => {
+                if matches!(event.kind, notify::EventKind::Access(_)) {
+                    continue;
+                }
+                pending.extend(event.paths);
+                batch_started.get_or_insert_with(Instant::now);
+                if batch_started.map(|t| t.elapsed() < MAX_BATCH_WAIT).unwrap_or(true) {
+                    continue;
+                }
+            }
+            Ok(Err(e)) => {
+                tracing::warn!(error = %e, "Dateisystem-Ereignis fehlerhaft");
+                continue;
+            }
+            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
+            Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+
+        if pending.is_empty() {
+            continue;
+        }
+        let paths: Vec<PathBuf> = pending.drain().collect();
+        batch_started = None;
+        if !apply_batch(&app, &scan_id, paths) {
+            // Scan was released from memory — nothing left to keep up to date
+            break;
+        }
+    }
+    // Ended on its own (scan replaced or released): drop the registration so the status
+    // no longer reports a watch — unless a newer watch has taken its place already
+    let mut guard = active().lock().unwrap_or_else(|e| e.into_inner());
+    if guard.as_ref().is_some_and(|w| Arc::ptr_eq(&w.stop, &stop)) {
+        *guard = None;
+        tracing::info!(scan_id = %scan_id, "Live-Überwachung beendet (Scan nicht mehr im Speicher)");
+    }
+    drop(guard);
+    tracing::debug!(scan_id = %scan_id, "Überwachungs-Thread beendet");
+}
+
+/// Re-stat every touched path: existing files are upserted, existing directories are
+/// walked (moved-in trees), vanished paths are removed (files or whole subtrees).
+fn apply_batch(app: &tauri::AppHandle, scan_id: &str, paths: Vec<PathBuf>) -> bool {
+    let start = Instant::now();
+    let mut removed: Vec<String> = Vec::new();
+    let mut upserts: Vec<crate::scan::FileEntry> = Vec::new();
+    let mut seen: HashSet<PathBuf> = HashSet::new();
+
+    for path in paths {
+        match std::fs::symlink_metadata(&path) {
+            Ok(meta) if meta.is_dir() => {
+                for entry in walkdir::WalkDir::new(&path).follow_links(false).into_iter().flatten() {
+                    if entry.file_type().is_file() && seen.insert(entry.path().to_path_buf()) {
+                        if let Ok(m) = entry.metadata() {
+                            upserts.push(crate::scan::file_entry(entry.path(), &m));
+                        }
+                    }
+                }
+            }
+            Ok(meta) => {
+                if seen.insert(path.clone()) {
+                    upserts.push(crate::scan::file_entry(&path, &meta));
+                }
+            }
+            Err(_) => removed.push(crate::ospath::encode(path.as_os_str())),
+        }
+    }
+
+    let stats = match crate::scan::apply_changes(scan_id, &removed, upserts) {
+        Some(s) => s,
+        None => return false,
+    };
+    let (files_count, total_size) = crate::scan::with_scan(scan_id, |d| (d.files.len(), d.total_size)).unwrap_or((0, 0));
+
+    tracing::debug!(
+        scan_id = %scan_id,
+        added = stats.added, updated = stats.updated, removed = stats.removed,
+        elapsed_ms = start.elapsed().as_millis(),
+        "Scan-Daten durch Live-Überwachung aktualisiert"
+    );
+    let _ = app.emit("scan-updated", json!({
+        "scan_id": scan_id,
+        "added": stats.added,
+        "updated": stats.updated,
+        "removed": stats.removed,
+        "files_found": files_count,
+        "total_size": total_size
+    }));
+    true
+}
@@ -74,8 +74,18 @@ export interface OperationResult {
 
 // === Drive & Scan ===
 export const getDrives = () => invoke<DriveInfo[]>('get_drives');
-export const startScan = (path: string) => invoke<{ scan_id: string }>('start_scan', { path });
+export const startScan = (path: string, options?: { archives?: boolean }) =>
+  invoke<{ scan_id: string }>('start_scan', { path, options });
 export const cancelScan = () => invoke<{ cancelled: boolean }>('cancel_scan');
+export const resumeScan = (scanId: string) => invoke<{ scan_id: string; resumed: boolean }>('resume_scan', { scanId });
+export const startEstimateScan = (path: string, options?: { fullDepth?: number; maxMs?: number; maxProbes?: number }) =>
+  invoke<{ estimateId: string; tree: any }>('start_estimate_scan', { path, options });
+export const getEstimateTree = (estimateId: string, path?: string, depth?: number) =>
+  invoke<any>('get_estimate_tree', { estimateId, path, depth });
+export const refineEstimate = (estimateId: string, path: string) => invoke<any>('refine_estimate', { estimateId, path });
+export const cancelRefineEstimate = () => invoke<{ cancelled: boolean }>('cancel_refine_estimate');
+export const getScanCheckpoints = () => invoke<any[]>('get_scan_checkpoints');
+export const discardScanCheckpoint = (scanId: string) => invoke<any>('discard_scan_checkpoint', { scanId });
 
 // === Tree Data ===
 export const getTreeNode = (scanId: string, path: string, depth?: number) =>
@@ -84,10 +94,13 @@ export const getTreemapData = (scanId: string, path: string, depth?: number) =>
   invoke<any>('get_treemap_data', { scanId, path, depth });
 
 // === File Data ===
+// `path`/`name` are exact and must be passed back unchanged to file commands. Names that
+// are not valid Unicode (e.g. Latin-1 on Linux) carry escapes; such results additionally
+// contain `displayPath`/`displayName` for rendering.
 export const getTopFiles = (scanId: string, limit: number) =>
   invoke<any[]>('get_top_files', { scanId, limit });
-export const getFileTypes = (scanId: string) =>
-  invoke<any[]>('get_file_types', { scanId });
+export const getFileTypes = (scanId: string, includeArchives = false) =>
+  invoke<any[]>('get_file_types', { scanId, includeArchives });
 export const search = (scanId: string, query: string, minSize?: number) =>
   invoke<any[]>('search', { scanId, query, minSize });
 export const getFilesByExtension = (scanId: string, ext: string, limit?: number) =>
@@ -95,21 +108,62 @@ export const getFilesByExtension = (scanId: string, ext: string, limit?: number)
 export const getFilesByCategory = (scanId: string, category: string, limit?: number) =>
   invoke<any[]>('get_files_by_category', { scanId, category, limit });
 
+// Paged variants — shared contract for all file lists (exact total, stable cursor)
+export interface PageOptions {
+  sortBy?: 'size' | 'name' | 'modified' | 'path';
+  sortDir?: 'asc' | 'desc';
+  offset?: number;
+  limit?: number;
+  cursor?: string | null;
+}
+export interface Page<T = any> {
+  items: T[];
+  total: number;
+  offset: number;
+  limit: number;
+  hasMore: boolean;
+  nextCursor: string | null;
+  sortBy: string;
+  sortDir: 'asc' | 'desc';
+}
+export const getTopFilesPage = (scanId: string, page: PageOptions = {}) =>
+  invoke<Page>('get_top_files', { scanId, page });
+export const searchPage = (scanId: string, query: string, minSize?: number, page: PageOptions = {}) =>
+  invoke<Page>('search', { scanId, query, minSize, page });
+export const getFilesByExtensionPage = (scanId: string, ext: string, page: PageOptions = {}) =>
+  invoke<Page>('get_files_by_extension', { scanId, ext, page });
+export const getFilesByCategoryPage = (scanId: string, category: string, page: PageOptions = {}) =>
+  invoke<Page>('get_files_by_category', { scanId, category, page });
+
 // === Export ===
 export const exportCSV = (scanId: string) => invoke<string>('export_csv', { scanId });
 export const showSaveDialog = (options: any) => invoke<any>('show_save_dialog', { options });
 
 // === File Management ===
 export const deleteToTrash = (paths: string[]) => invoke<OperationResult>('delete_to_trash', { paths });
+// Native trash (Linux, freedesktop.org spec) — `id` identifies an entry for restore/empty
+export const listTrash = () => invoke<{ items: any[]; count: number }>('list_trash');
+export const restoreFromTrash = (ids: string[]) => invoke<any>('restore_from_trash', { ids });
+export const emptyTrash = (ids?: string[]) => invoke<any>('empty_trash', { ids });
 export const deletePermanent = (paths: string[]) => invoke<OperationResult>('delete_permanent', { paths });
 export const createFolder = (parentPath: string, name: string) =>
   invoke<OperationResult>('create_folder', { parentPath, name });
 export const fileRename = (oldPath: string, newName: string) =>
   invoke<OperationResult>('file_rename', { oldPath, newName });
-export const fileMove = (sourcePaths: string[], destDir: string) =>
-  invoke<OperationResult>('file_move', { sourcePaths, destDir });
-export const fileCopy = (sourcePaths: string[], destDir: string) =>
-  invoke<OperationResult>('file_copy', { sourcePaths, destDir });
+export type ConflictPolicy = 'ask' | 'skip' | 'overwrite' | 'rename' | 'overwrite_newer' | 'fail';
+// verify: compare BLAKE3 of source and copy (default on for moves); preserve: times, permissions, xattrs (default on)
+export interface FileJobOptions { conflict?: ConflictPolicy; verify?: boolean; preserve?: boolean }
+export const fileMove = (sourcePaths: string[], destDir: string, options?: FileJobOptions) =>
+  invoke<OperationResult>('file_move', { sourcePaths, destDir, options });
+export const fileCopy = (sourcePaths: string[], destDir: string, options?: FileJobOptions) =>
+  invoke<OperationResult>('file_copy', { sourcePaths, destDir, options });
+// Background copy/move jobs — progress, conflicts and the report arrive as file-job-* events
+export const startFileJob = (kind: 'copy' | 'move', sourcePaths: string[], destDir: string, options?: FileJobOptions) =>
+  invoke<{ jobId: string }>('start_file_job', { kind, sourcePaths, destDir, options });
+export const cancelFileJob = (jobId: string) => invoke<OperationResult>('cancel_file_job', { jobId });
+export const resolveFileConflict = (jobId: string, action: Exclude<ConflictPolicy, 'ask'>, applyToAll?: boolean) =>
+  invoke<OperationResult>('resolve_file_conflict', { jobId, action, applyToAll });
+export const getFileJobs = () => invoke<any[]>('get_file_jobs');
 export const fileProperties = (filePath: string) =>
   invoke<any>('file_properties', { filePath });
 export const filePropertiesGeneral = (filePath: string) =>
@@ -120,15 +174,59 @@ export const filePropertiesDetails = (filePath: string) =>
   invoke<any>('file_properties_details', { filePath });
 export const filePropertiesVersions = (filePath: string) =>
   invoke<any>('file_properties_versions', { filePath });
-export const filePropertiesHash = (filePath: string, algorithm: string) =>
-  invoke<any>('file_properties_hash', { filePath, algorithm });
+export const filePropertiesHash = (filePath: string, algorithm: string, jobId?: string) =>
+  invoke<any>('file_properties_hash', { filePath, algorithm, jobId });
+// Native hashing: MD5, SHA1, SHA224/256/384/512, BLAKE3, CRC32, XXH32/XXH64/XXH3/XXH128.
+// Pass a jobId to cancel via cancelHash; progress arrives via onHashProgress.
+export const hashFile = (filePath: string, algorithms: string[], jobId?: string) =>
+  invoke<{ jobId: string; path: string; size: number; hashes: Record<string, string>; elapsedMs: number }>('hash_file', { filePath, algorithms, jobId });
+export const verifyChecksumFile = (checksumPath: string, algorithm?: string, jobId?: string) =>
+  invoke<any>('verify_checksum_file', { checksumPath, algorithm, jobId });
+export const cancelHash = (jobId: string) => invoke<OperationResult>('cancel_hash', { jobId });
 export const openFile = (filePath: string) => invoke<void>('open_file', { filePath });
 export const showInExplorer = (filePath: string) => invoke<void>('show_in_explorer', { filePath });
 export const runAsAdmin = (filePath: string) => invoke<any>('run_as_admin', { filePath });
-export const extractArchive = (archivePath: string, destDir?: string) =>
-  invoke<any>('extract_archive', { archivePath, destDir });
-export const createArchive = (sourcePaths: string[], destPath?: string) =>
-  invoke<any>('create_archive', { sourcePaths, destPath });
+export type ArchiveFormat = 'zip' | 'tar' | 'tar.gz' | 'tar.xz' | 'tar.zst';
+export interface ExtractArchiveOptions {
+  conflict?: Exclude<ConflictPolicy, 'ask'>;
+  allowSymlinks?: boolean;
+  maxTotalBytes?: number;
+  maxEntries?: number;
+  maxRatio?: number;
+  jobId?: string;
+}
+export interface CreateArchiveOptions {
+  format?: ArchiveFormat;
+  level?: number;
+  conflict?: Exclude<ConflictPolicy, 'ask'>;
+  jobId?: string;
+}
+// Progress arrives via onArchiveProgress; pass a jobId to cancel via cancelArchiveJob.
+export const extractArchive = (archivePath: string, destDir?: string, options?: ExtractArchiveOptions) =>
+  invoke<any>('extract_archive', { archivePath, destDir, options });
+export const createArchive = (sourcePaths: string[], destPath?: string, options?: CreateArchiveOptions) =>
+  invoke<any>('create_archive', { sourcePaths, destPath, options });
+export interface ArchiveEntryNode {
+  name: string;
+  path: string;
+  kind: 'file' | 'dir' | 'symlink' | 'hardlink' | 'other';
+  size: number;
+  compressedSize: number | null;
+  modifiedMs: number | null;
+  encrypted: boolean;
+  linkTarget: string | null;
+  mode: number | null;
+  implicit: boolean;
+  children?: ArchiveEntryNode[];
+}
+export const listArchive = (archivePath: string, maxEntries?: number) =>
+  invoke<{
+    path: string; format: ArchiveFormat; entries: ArchiveEntryNode[]; totalEntries: number; totalSize: number;
+    totalCompressedSize: number | null; archiveSize: number; encrypted: boolean; truncated: boolean;
+  }>('list_archive', { archivePath, maxEntries });
+export const readArchiveEntry = (archivePath: string, entry: string, range?: { offset?: number; length?: number }) =>
+  invoke<{ data: string; offset: number; length: number; size: number; eof: boolean }>('read_archive_entry', { archivePath, entry, range });
+export const cancelArchiveJob = (jobId: string) => invoke<OperationResult>('cancel_archive_job', { jobId });
 
 // Convenience aliases
 export const rename = fileRename;
@@ -147,12 +245,24 @@ export const showConfirmDialog = (options: any) =>
 // === Old Files ===
 export const getOldFiles = (scanId: string, thresholdDays: number, minSize: number) =>
   invoke<any>('get_old_files', { scanId, thresholdDays, minSize });
+export const getOldFilesPage = (scanId: string, thresholdDays: number, minSize: number, page: PageOptions = {}) =>
+  invoke<Page & { totalSize: number }>('get_old_files', { scanId, thresholdDays, minSize, page });
 
 // === Duplicate Finder ===
 export const startDuplicateScan = (scanId: string, options?: any) =>
   invoke<any>('start_duplicate_scan', { scanId, options });
 export const cancelDuplicateScan = (scanId: string) =>
   invoke<void>('cancel_duplicate_scan', { scanId });
+export const getDuplicateResults = (scanId: string, offset?: number, limit?: number, sortBy?: string, sortDir?: 'asc' | 'desc') =>
+  invoke<any>('get_duplicate_results', { scanId, offset, limit, sortBy, sortDir });
+export const exportDuplicateResults = (scanId: string, format: 'csv' | 'json' = 'csv') =>
+  invoke<any>('export_duplicate_results', { scanId, format });
+export const startCrossDuplicateScan = (scanIdA: string, scanIdB: string, options?: any) =>
+  invoke<any>('start_cross_duplicate_scan', { scanIdA, scanIdB, options });
+export const cancelCrossDuplicateScan = () =>
+  invoke<void>('cancel_cross_duplicate_scan');
+export const getCrossDuplicateResults = (scanIdA: string, scanIdB: string, bucket: 'both' | 'onlyA' | 'onlyB' = 'both', offset?: number, limit?: number) =>
+  invoke<any>('get_cross_duplicate_results', { scanIdA, scanIdB, bucket, offset, limit });
 
 // === Size Duplicates ===
 export const getSizeDuplicates = (scanId: string, minSize: number) =>
@@ -169,8 +279,9 @@ export const cleanCategory = (categoryId: string, paths: string[]) =>
   invoke<any>('clean_category', { categoryId, paths });
 
 // === Preview ===
-export const readFilePreview = (filePath: string, maxLines?: number) =>
-  invoke<any>('read_file_preview', { filePath, maxLines });
+// With archiveEntry, filePath is an archive and the member is previewed without extracting.
+export const readFilePreview = (filePath: string, maxLines?: number, archiveEntry?: string) =>
+  invoke<any>('read_file_preview', { filePath, maxLines, archiveEntry });
 
 // === Editor ===
 export const readFileContent = (filePath: string) =>
@@ -386,16 +497,36 @@ export const openPdfWindow = (filePath: string) =>
 // === Undo-Log ===
 export const getUndoLog = () => invoke<any[]>('get_undo_log');
 export const undoAction = (id: string) => invoke<any>('undo_action', { id });
+// Redo the most recently undone file action (an id must be the top of the redo stack)
+export const redoAction = (id?: string) => invoke<any>('redo_action', { id });
+// Merge logged file actions into one entry that is undone/redone as a whole
+export const groupUndoEntries = (ids: string[], description: string) =>
+  invoke<any>('group_undo_entries', { ids, description });
 export const clearUndoLog = () => invoke<any>('clear_undo_log');
 
 // === Scan History (Delta-Scan) ===
 export const saveScanSnapshot = (scanId: string) => invoke<any>('save_scan_snapshot', { scanId });
 export const getScanHistory = () => invoke<any[]>('get_scan_history');
-export const compareScans = (olderId: string, newerId: string) => invoke<any>('compare_scans', { olderId, newerId });
-export const getStorageTrend = (rootPath: string) => invoke<any>('get_storage_trend', { rootPath });
-export const quickChangeCheck = () => invoke<any>('quick_change_check');
+export const compareScans = (olderId: string, newerId: string, subtree?: string, maxDepth?: number, limit?: number) =>
+  invoke<any>('compare_scans', { olderId, newerId, subtree, maxDepth, limit });
+export const diffScans = (olderId: string, newerId: string, changeType: string = 'all', offset?: number, limit?: number) =>
+  invoke<any>('diff_scans', { olderId, newerId, changeType, offset, limit });
+export const getStorageTrend = (rootPath: string, method: 'ols' | 'robust' = 'ols') =>
+  invoke<any>('get_storage_trend', { rootPath, method });
+export const getStorageAnomalies = (rootPath: string, options?: any) =>
+  invoke<any>('get_storage_anomalies', { rootPath, options });
+export const quickChangeCheck = (options?: { maxDirs?: number; maxMs?: number }) =>
+  invoke<any>('quick_change_check', { options });
+export const getScanScheduleStatus = () => invoke<any>('get_scan_schedule_status');
+export const runScheduledScanNow = (scheduleId: string) => invoke<any>('run_scheduled_scan_now', { scheduleId });
+export const startScanWatch = (scanId: string) => invoke<any>('start_scan_watch', { scanId });
+export const stopScanWatch = () => invoke<any>('stop_scan_watch');
+export const getScanWatchStatus = () => invoke<any>('get_scan_watch_status');
 export const deleteScanSnapshot = (snapshotId: string) => invoke<any>('delete_scan_snapshot', { snapshotId });
 export const clearScanHistory = () => invoke<any>('clear_scan_history');
+export const exportScanHistory = (snapshotIds: string[], destPath: string, includeFileLists = false) =>
+  invoke<any>('export_scan_history', { snapshotIds, destPath, includeFileLists });
+export const importScanHistory = (srcPath: string) => invoke<any>('import_scan_history', { srcPath });
 
 // === Frontend Logging ===
 export const logFrontend = (level: string, message: string, context?: string) =>
@@ -413,9 +544,45 @@ export function onScanComplete(cb: TauriEventCallback): Promise<UnlistenFn> {
 export function onScanError(cb: TauriEventCallback): Promise<UnlistenFn> {
   return listen('scan-error', (e) => cb(e.payload));
 }
+export function onScanUpdated(cb: TauriEventCallback): Promise<UnlistenFn> {
+  return listen('scan-updated', (e) => cb(e.payload));
+}
+export function onScheduledScanComplete(cb: TauriEventCallback): Promise<UnlistenFn> {
+  return listen('scheduled-scan-complete', (e) => cb(e.payload));
+}
+export function onScheduledScanAlert(cb: TauriEventCallback): Promise<UnlistenFn> {
+  return listen('scheduled-scan-alert', (e) => cb(e.payload));
+}
+export function onScheduledScanError(cb: TauriEventCallback): Promise<UnlistenFn> {
+  return listen('scheduled-scan-error', (e) => cb(e.payload));
+}
 export function onContextMenuAction(cb: TauriEventCallback): Promise<UnlistenFn> {
   return listen('context-menu-action', (e) => cb(e.payload));
 }
+export function onFileJobProgress(cb: TauriEventCallback): Promise<UnlistenFn> {
+  return listen('file-job-progress', (e) => cb(e.payload));
+}
+export function onFileJobConflict(cb: TauriEventCallback): Promise<UnlistenFn> {
+  return listen('file-job-conflict', (e) => cb(e.payload));
+}
+export function onFileJobComplete(cb: TauriEventCallback): Promise<UnlistenFn> {
+  return listen('file-job-complete', (e) => cb(e.payload));
+}
+export function onFileJobError(cb: TauriEventCallback): Promise<UnlistenFn> {
+  return listen('file-job-error', (e) => cb(e.payload));
+}
+export function onHashProgress(cb: TauriEventCallback): Promise<UnlistenFn> {
+  return listen('hash-progress', (e) => cb(e.payload));
+}
+export function onArchiveProgress(cb: TauriEventCallback): Promise<UnlistenFn> {
+  return listen('archive-progress', (e) => cb(e.payload));
+}
+export function onArchiveComplete(cb: TauriEventCallback): Promise<UnlistenFn> {
+  return listen('archive-complete', (e) => cb(e.payload));
+}
+export function onArchiveError(cb: TauriEventCallback): Promise<UnlistenFn> {
+  return listen('archive-error', (e) => cb(e.payload));
+}
 export function onDuplicateProgress(cb: TauriEventCallback): Promise<UnlistenFn> {
   return listen('duplicate-progress', (e) => cb(e.payload));
 }
@@ -425,6 +592,12 @@ export function onDuplicateComplete(cb: TauriEventCallback): Promise<UnlistenFn>
 export function onDuplicateError(cb: TauriEventCallback): Promise<UnlistenFn> {
   return listen('duplicate-error', (e) => cb(e.payload));
 }
+export function onCrossDuplicateProgress(cb: TauriEventCallback): Promise<UnlistenFn> {
+  return listen('cross-duplicate-progress', (e) => cb(e.payload));
+}
+export function onCrossDuplicateComplete(cb: TauriEventCallback): Promise<UnlistenFn> {
+  return listen('cross-duplicate-complete', (e) => cb(e.payload));
+}
 export function onDeepSearchResult(cb: TauriEventCallback): Promise<UnlistenFn> {
   return listen('deep-search-result', (e) => cb(e.payload));
 }
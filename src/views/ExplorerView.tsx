@@ -564,7 +564,7 @@ export default function ExplorerView() {
         break;
       case 'extract': {
         // Alle ausgewählten Archive entpacken (jeweils in Unterordner neben dem Archiv)
-        const archiveExts = ['.zip'];
+        const archiveExts = ['.zip', '.tar', '.gz', '.tgz', '.xz', '.txz', '.zst', '.tzst'];
         const archivePaths = paths.filter(p => {
           const e = p.substring(p.lastIndexOf('.')).toLowerCase();
           return archiveExts.includes(e);
@@ -587,7 +587,7 @@ export default function ExplorerView() {
       }
       case 'extract-to': {
         // Entpacken nach... — Zielordner wählen
-        const archiveExtsTo = ['.zip'];
+        const archiveExtsTo = ['.zip', '.tar', '.gz', '.tgz', '.xz', '.txz', '.zst', '.tzst'];
         const archivePathsTo = paths.filter(p => {
           const e = p.substring(p.lastIndexOf('.')).toLowerCase();
           return archiveExtsTo.includes(e);
@@ -1430,9 +1430,10 @@ export default function ExplorerView() {
         const multi = selectedPaths.size > 1;
         const isDir = entry?.isDirectory;
         const ext = entry?.extension?.toLowerCase() || '';
-        const isArchive = ext === '.zip';
+        const archivePattern = /\.(zip|tar|tgz|txz|tzst|tar\.(gz|xz|zst))$/i;
+        const isArchive = !!entry && archivePattern.test(entry.path);
         // Bei Mehrfachauswahl: prüfen ob mindestens ein Archiv dabei ist
-        const hasArchiveInSelection = multi ? [...selectedPaths].some(p => p.toLowerCase().endsWith('.zip')) : isArchive;
+        const hasArchiveInSelection = multi ? [...selectedPaths].some(p => archivePattern.test(p)) : isArchive;
         const isPdf = ext === '.pdf';
         const isExecutable = ['.exe', '.msi', '.bat', '.cmd', '.ps1'].includes(ext);
         const isTextFile = ['.txt', '.log', '.md', '.json', '.xml', '.csv', '.ini', '.cfg', '.yaml', '.yml',
@@ -9,12 +9,18 @@ interface UndoEntry {
   description: string;
   can_undo: boolean;
   undone: boolean;
+  can_redo?: boolean;
 }
 
 const ACTION_LABELS: Record<string, string> = {
   delete_trash: 'Papierkorb',
   delete_permanent: 'Endgültig gelöscht',
   file_move: 'Verschoben',
+  file_copy: 'Kopiert',
+  file_rename: 'Umbenannt',
+  create_folder: 'Ordner erstellt',
+  write_file_content: 'Bearbeitet',
+  group: 'Gruppe',
   toggle_autostart: 'Autostart',
   delete_autostart: 'Autostart gelöscht',
   privacy_setting: 'Datenschutz',
@@ -54,6 +60,16 @@ export default function UndoLogView() {
     }
   }, [showToast, loadEntries]);
 
+  const handleRedo = useCallback(async (id: string) => {
+    try {
+      const result = await api.redoAction(id);
+      showToast(result.message || 'Aktion wiederholt', 'success');
+      loadEntries();
+    } catch (err: any) {
+      showToast('Wiederholen fehlgeschlagen: ' + err.message, 'error');
+    }
+  }, [showToast, loadEntries]);
+
   const handleClear = useCallback(async () => {
     const confirmed = await api.showConfirmDialog({
       type: 'warning', title: 'Protokoll leeren',
@@ -113,6 +129,11 @@ export default function UndoLogView() {
                     Rückgängig
                   </button>
                 )}
+                {entry.undone && entry.can_redo && (
+                  <button className="btn btn-sm" onClick={() => handleRedo(entry.id)} title="Wiederholen">
+                    Wiederholen
+                  </button>
+                )}
                 {entry.undone && <span className="undo-log-badge-undone">Rückgängig</span>}
                 {!entry.can_undo && <span className="undo-log-badge-permanent">Nicht umkehrbar</span>}
               </div>